@@ -0,0 +1,113 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pi_calculator::{
+    bench_once, binary_split, chudnovsky_terms, compute_pi_binary_split, format_block, format_scalar,
+    fraction_digits, pi_string, stream_pi, write_pi_to_file_chunked, Algorithm, FormatOptions,
+    WriteOptions, DEFAULT_BUFFER_SIZE,
+};
+
+// BBP 与 Chudnovsky 在 1000 位和 10000 位下的计算耗时
+fn bench_algorithms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_pi");
+    group.sample_size(10);
+    
+    for algorithm in [Algorithm::Bbp, Algorithm::Chudnovsky] {
+        for digits in [1000, 10000] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", algorithm), digits),
+                &digits,
+                |b, &digits| {
+                    b.iter_custom(|iters| (0..iters).map(|_| bench_once(algorithm, digits).unwrap()).sum())
+                },
+            );
+        }
+    }
+    
+    group.finish();
+}
+
+// 50000 位的 BBP，每项的 16^(-k) 由上一项递推
+fn bench_bbp_50k(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_pi_50k");
+    group.sample_size(10);
+    group.bench_function("Bbp", |b| {
+        b.iter_custom(|iters| (0..iters).map(|_| bench_once(Algorithm::Bbp, 50_000).unwrap()).sum())
+    });
+    group.finish();
+}
+
+// 50 万位的二进分割，合并中的乘法串行与并行执行的耗时
+fn bench_binary_split_merge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary_split_merge");
+    group.sample_size(10);
+    
+    let terms = chudnovsky_terms(500_000).unwrap() as u64;
+    for parallel in [false, true] {
+        let name = if parallel { "parallel" } else { "serial" };
+        group.bench_with_input(BenchmarkId::new(name, 500_000), &terms, |b, &terms| {
+            b.iter(|| binary_split(0, terms, parallel))
+        });
+    }
+    
+    group.finish();
+}
+
+// 以标准格式写入 100 万位，比较 8 KB 与默认 1 MB 缓冲区的耗时
+fn bench_write_buffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_buffer");
+    group.sample_size(10);
+    
+    let digits = 1_000_000;
+    let pi = compute_pi_binary_split(digits, 4).unwrap().value;
+    let path = std::env::temp_dir().join("pi_bench_write_buffer.txt");
+    let filename = path.to_str().unwrap();
+    for buffer_size in [8 * 1024, DEFAULT_BUFFER_SIZE] {
+        let options = WriteOptions { buffer_size, ..WriteOptions::default() };
+        group.bench_with_input(BenchmarkId::from_parameter(buffer_size), &options, |b, options| {
+            b.iter(|| write_pi_to_file_chunked(&pi, digits, filename, None, options).unwrap())
+        });
+    }
+    let _ = std::fs::remove_file(&path);
+    
+    group.finish();
+}
+
+// 100 万位的十进制转换和标准格式的分组排版，输出到内存不计文件写入
+// 同一段 100 万位数字分别用逐字符的参照实现和按整组切片拷贝的 format_block 排版
+fn bench_format_1m(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_1m");
+    group.sample_size(10);
+    
+    let digits = 1_000_000;
+    let pi = compute_pi_binary_split(digits, 4).unwrap().value;
+    let format = FormatOptions::default();
+    group.bench_function("pi_string", |b| b.iter(|| pi_string(&pi, digits)));
+    group.bench_function("write_standard", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(digits * 2);
+            stream_pi(&pi, digits, &mut out, &format).unwrap();
+            out
+        })
+    });
+    
+    let body: Vec<u8> = fraction_digits(&pi, digits).map(|d| d + b'0').collect();
+    group.bench_function("format_scalar", |b| b.iter(|| format_scalar(&body, &format)));
+    group.bench_function("format_block", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(digits * 2);
+            format_block(&body, 0, &format, &mut out);
+            out
+        })
+    });
+    
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_algorithms,
+    bench_bbp_50k,
+    bench_binary_split_merge,
+    bench_write_buffer,
+    bench_format_1m
+);
+criterion_main!(benches);
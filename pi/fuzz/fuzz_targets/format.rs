@@ -0,0 +1,86 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pi_calculator::{
+    compute_pi_optimized, pi_string, stream_pi, FormatOptions, LineEnding, OutputMode,
+};
+
+// 输入格式: [位数低字节, 位数高字节, 每组字符数, 每行组数, 标志位]
+// 标志位 bit0 = 位置标记, bit1 = raw 格式, bit2 = Windows 换行符, bit3 = 列标尺
+// 位数限制在内置常数范围内，避免每次都做级数计算
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 5 {
+        return;
+    }
+    
+    let digits = (u16::from_le_bytes([data[0], data[1]]) as usize % 1000) + 1;
+    let format = FormatOptions {
+        mode: if data[4] & 2 != 0 { OutputMode::Raw } else { OutputMode::Standard },
+        group_size: 1 + data[2] as usize % 20,
+        groups_per_line: 1 + data[3] as usize % 10,
+        index_markers: data[4] & 1 != 0,
+        line_ending: if data[4] & 4 != 0 { LineEnding::Windows } else { LineEnding::Unix },
+        ruler: data[4] & 8 != 0,
+    };
+    
+    // 内置常数范围内不应出错，出错时作为崩溃报告
+    let pi = compute_pi_optimized(digits, 1).unwrap().value;
+    let mut output = Vec::new();
+    stream_pi(&pi, digits, &mut output, &format).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    
+    let expected: String = pi_string(&pi, digits).chars().filter(|c| c.is_ascii_digit()).collect();
+    
+    if format.mode == OutputMode::Raw {
+        // raw 格式是 digits 位小数，不含整数部分
+        let fraction: String = pi_string(&pi, digits).chars().skip(2).collect();
+        assert_eq!(output, fraction);
+        return;
+    }
+    
+    // 每个行边界都使用指定的换行符，不出现多余的 \r
+    if format.line_ending == LineEnding::Windows {
+        assert_eq!(output.matches("\r\n").count(), output.matches('\n').count());
+        assert_eq!(output.matches('\r').count(), output.matches('\n').count());
+    } else {
+        assert!(!output.contains('\r'));
+    }
+    
+    // 标准格式：取两条分隔线之间的正文，去掉位置标记后重新拼出数字
+    let mut body: Vec<&str> = output
+        .lines()
+        .skip_while(|line| !line.starts_with('='))
+        .skip(1)
+        .take_while(|line| !line.starts_with('='))
+        .filter(|line| !line.is_empty())
+        .collect();
+    
+    // 标尺紧跟在单独一行的整数部分之后，宽度与一整行小数相同，空格与分组间隔对齐
+    if format.ruler {
+        assert_eq!(body[0], "3.");
+        let ruler = body.remove(1);
+        let line_width = format.group_size * format.groups_per_line;
+        assert_eq!(ruler.len(), line_width + format.groups_per_line - 1);
+        for (i, group) in ruler.split(' ').enumerate() {
+            assert_eq!(group.len(), format.group_size);
+            for (j, c) in group.chars().enumerate() {
+                let position = i * format.group_size + j;
+                assert_eq!(c, char::from(b'0' + (position % 10) as u8));
+            }
+        }
+    }
+    
+    for line in &body {
+        assert!(!line.ends_with(' '), "行末有多余空格: {:?}", line);
+    }
+    
+    let parsed: String = body
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .filter(|token| !token.starts_with(':'))
+        .flat_map(|token| token.chars().filter(|c| c.is_ascii_digit()))
+        .collect();
+    // 整数部分 3 加上 digits 位小数
+    assert_eq!(parsed.len(), digits + 1);
+    assert_eq!(parsed, expected);
+});
@@ -0,0 +1,17 @@
+// 在工作线程开始时调用，enabled 时第 index 个线程绑定到第 index 个核心（超出核心数时循环）
+// 需要启用 core_affinity 特性，否则或平台不支持时不做任何事
+#[cfg(feature = "core_affinity")]
+pub(crate) fn pin_worker(index: usize, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    
+    if let Some(core_ids) = core_affinity::get_core_ids() {
+        if !core_ids.is_empty() && !core_affinity::set_for_current(core_ids[index % core_ids.len()]) {
+            log::debug!("无法将第 {} 个工作线程绑定到核心", index);
+        }
+    }
+}
+
+#[cfg(not(feature = "core_affinity"))]
+pub(crate) fn pin_worker(_index: usize, _enabled: bool) {}
@@ -0,0 +1,134 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use rug::ops::{NegAssign, Pow};
+use rug::{Assign, Float};
+
+use crate::bbp::{sum_series_with, Progress, SeriesTerm};
+use crate::{required_precision, ComputeError, ComputeOptions};
+
+// arctan(1/n) 的 Taylor 级数单项计算器: (-1)^k / ((2k+1) * n^(2k+1))
+struct ArctanCalculator {
+    n: u64,
+    // 1/n^2，相邻两项的幂次之比
+    inv_n_squared: Float,
+    // 当前 k 对应的 1/n^(2k+1)
+    power: Float,
+    term: Float,
+    last_k: Option<usize>,
+}
+
+impl ArctanCalculator {
+    fn new(n: u64, precision: u32) -> Self {
+        let n_squared = Float::with_val(precision, n).square();
+        Self {
+            n,
+            inv_n_squared: n_squared.recip(),
+            power: Float::with_val(precision, 0),
+            term: Float::with_val(precision, 0),
+            last_k: None,
+        }
+    }
+}
+
+impl SeriesTerm<Float> for ArctanCalculator {
+    fn compute_term(&mut self, k: usize) -> &Float {
+        // 与上一项连续时只需乘以 1/n^2，否则直接计算 n^(2k+1) 再取倒数
+        if self.last_k.is_some_and(|last_k| last_k + 1 == k) {
+            self.power *= &self.inv_n_squared;
+        } else {
+            let exponent = (2 * k + 1) as u32;
+            self.power.assign(Float::with_val(self.power.prec(), self.n).pow(exponent));
+            self.power.recip_mut();
+        }
+        self.last_k = Some(k);
+        
+        self.term.assign(&self.power / (2 * k as u64 + 1));
+        if k % 2 == 1 {
+            self.term.neg_assign();
+        }
+        &self.term
+    }
+}
+
+// 精确到 2^-precision 需要的项数：第 K 项小于 n^-(2K+1)，由 (2K+1)·log2(n) > precision 得出
+fn arctan_terms(n: u64, precision: u32) -> usize {
+    let bits_per_term = 2.0 * (n as f64).log2();
+    (precision as f64 / bits_per_term).ceil() as usize + 1
+}
+
+// 用 Taylor 级数并行计算 arctan(1/n) 到 digits 位小数，可用于组合 Machin 类公式
+// n 越大收敛越快；n = 1 时级数几乎不收敛，改用 arctan(1) = 4·arctan(1/5) - arctan(1/239)，
+// n = 0 时返回 arctan(∞) = π/2
+pub fn arctan_inv(n: u64, digits: usize, num_threads: usize) -> Result<Float, ComputeError> {
+    arctan_inv_with(n, digits, num_threads, &ComputeOptions::default())
+}
+
+// 同 arctan_inv，按 options 调整计算过程，例如把工作线程绑定到各自的核心
+pub fn arctan_inv_with(
+    n: u64,
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<Float, ComputeError> {
+    match n {
+        0 => return Ok(arctan_inv_with(1, digits, num_threads, options)? * 2u32),
+        1 => {
+            return Ok(arctan_inv_with(5, digits, num_threads, options)? * 4u32
+                - arctan_inv_with(239, digits, num_threads, options)?);
+        }
+        _ => {}
+    }
+    
+    let base_precision = required_precision(digits);
+    let terms_needed = arctan_terms(n, base_precision);
+    // 累加 terms_needed 项的舍入误差最多损失 log2(terms_needed) 位
+    let precision = base_precision + (usize::BITS - terms_needed.leading_zeros());
+    
+    log::info!("使用 {} 个线程计算 arctan(1/{})，共 {} 项...", num_threads.max(1), n, terms_needed);
+    
+    // 由共用的求和驱动分块并行累加，工作线程 panic 或局部和异常时返回错误
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sum, _) = sum_series_with(
+        precision,
+        0..terms_needed,
+        num_threads,
+        &stop,
+        &mut Progress::new(terms_needed, 0, Instant::now(), None),
+        options,
+        move || ArctanCalculator::new(n, precision),
+    )?;
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::float::Constant;
+    
+    // 与高精度 MPFR 参考值之差小于 10^-(digits-2)，留出乘以 16 等系数放大末位误差的余量
+    fn assert_close(value: &Float, expected: &Float, digits: usize) {
+        let error = Float::with_val(expected.prec(), value - expected).abs();
+        let tolerance = Float::with_val(expected.prec(), 10).pow(2 - digits as i32);
+        assert!(error < tolerance, "误差 {}", error.to_f64());
+    }
+    
+    // 4·arctan(1) 和 Machin 公式 16·arctan(1/5) - 4·arctan(1/239) 都等于 π；arctan(1/n) 与 MPFR 一致
+    #[test]
+    fn machin_formula_gives_pi() {
+        let digits = 1000;
+        let reference = Float::with_val(required_precision(digits) + 256, Constant::Pi);
+        
+        let quarter_pi = arctan_inv(1, digits, 2).unwrap();
+        assert_close(&Float::with_val(reference.prec(), &quarter_pi * 4u32), &reference, digits);
+        
+        let machin = Float::with_val(reference.prec(), arctan_inv(5, digits, 3).unwrap() * 16u32)
+            - arctan_inv(239, digits, 3).unwrap() * 4u32;
+        assert_close(&machin, &reference, digits);
+        
+        let expected = Float::with_val(reference.prec(), 7).recip().atan();
+        assert_close(&arctan_inv(7, digits, 2).unwrap(), &expected, digits);
+        let half_pi = arctan_inv(0, digits, 2).unwrap();
+        assert_close(&Float::with_val(reference.prec(), &half_pi * 2u32), &reference, digits);
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use rug::Float;
+use tokio_util::sync::CancellationToken;
+
+use crate::{compute_pi_with_stop, ComputeError, Config};
+
+// 异步计算 π，按 config.algorithm 选择算法，CPU 密集的计算在 spawn_blocking 线程中执行
+// cancel 被触发后通知工作线程停止，并等待其退出后返回 Cancelled
+pub async fn compute_pi_async(config: Config, cancel: CancellationToken) -> Result<Float, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let worker_stop = Arc::clone(&stop);
+    
+    let mut task = tokio::task::spawn_blocking(move || {
+        compute_pi_with_stop(
+            config.algorithm,
+            config.compute_digits(),
+            config.num_threads,
+            &worker_stop,
+            &config.options,
+        )
+    });
+    
+    tokio::select! {
+        result = &mut task => {
+            let result = result.map_err(|_| ComputeError::WorkerPanicked)??;
+            Ok(result.value)
+        }
+        _ = cancel.cancelled() => {
+            stop.store(true, Ordering::SeqCst);
+            let _ = task.await;
+            Err(ComputeError::Cancelled)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use super::*;
+    use crate::output::pi_string;
+    use crate::reference::load_reference_chunk;
+    use crate::Algorithm;
+    
+    // 每种算法都能在异步接口中算完，结果与内置常数一致
+    #[tokio::test]
+    async fn every_algorithm_completes() {
+        for &algorithm in Algorithm::all() {
+            let mut config = Config::new(1000);
+            config.algorithm = algorithm;
+            config.num_threads = 2;
+            
+            let pi = compute_pi_async(config, CancellationToken::new()).await.unwrap();
+            let expected = load_reference_chunk(0, 1000).unwrap();
+            assert_eq!(pi_string(&pi, 1000)[2..].as_bytes(), expected, "{}", algorithm.name());
+        }
+    }
+    
+    // 开始前已取消时直接返回 Cancelled
+    #[tokio::test]
+    async fn cancelled_chudnovsky_returns_cancelled() {
+        let mut config = Config::new(200_000);
+        config.algorithm = Algorithm::Chudnovsky;
+        config.num_threads = 2;
+        
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = compute_pi_async(config, cancel).await;
+        assert!(matches!(result, Err(ComputeError::Cancelled)));
+    }
+    
+    // 计算进行中取消，Chudnovsky 和二进分割的工作线程都很快退出，返回 Cancelled
+    #[tokio::test]
+    async fn cancelling_a_running_computation_returns_promptly() {
+        let cases = [(Algorithm::Chudnovsky, 1_000_000), (Algorithm::ChudnovskyBinarySplit, 10_000_000)];
+        for (algorithm, digits) in cases {
+            let mut config = Config::new(digits);
+            config.algorithm = algorithm;
+            config.num_threads = 2;
+            
+            let cancel = CancellationToken::new();
+            let task = tokio::spawn(compute_pi_async(config, cancel.clone()));
+            // 在阻塞线程中等待，期间运行时开始执行上面的任务
+            tokio::task::spawn_blocking(|| std::thread::sleep(Duration::from_millis(200))).await.unwrap();
+            assert!(!task.is_finished(), "{}", algorithm.name());
+            
+            let cancelled_at = Instant::now();
+            cancel.cancel();
+            let result = task.await.unwrap();
+            assert!(matches!(result, Err(ComputeError::Cancelled)), "{}", algorithm.name());
+            assert!(
+                cancelled_at.elapsed() < Duration::from_secs(2),
+                "{} 取消后 {:?} 才返回",
+                algorithm.name(),
+                cancelled_at.elapsed()
+            );
+        }
+    }
+}
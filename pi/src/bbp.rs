@@ -0,0 +1,1137 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use rug::float::Round;
+use rug::Float;
+
+use crate::affinity::pin_worker;
+use crate::checkpoint::{load_checkpoint, save_checkpoint, Checkpoint, CheckpointOptions};
+use crate::chudnovsky::{spawn_worker, worker_stack_size};
+use crate::reference::{cached_pi, CACHED_DIGITS};
+use crate::scalar::Scalar;
+use crate::throughput::{throughput_trace, ProgressSamples, BBP_DIGITS_PER_TERM, SAMPLE_INTERVAL};
+use crate::{required_precision, Algorithm, ComputeError, ComputeOptions, ComputeResult};
+
+// 级数单项计算器，每个工作线程持有一个，供并行求和驱动使用
+pub(crate) trait SeriesTerm<S: Scalar>: 'static {
+    // 计算第 k 项，返回的引用在下一次调用前有效
+    fn compute_term(&mut self, k: usize) -> &S;
+}
+
+// 只由精度和计算选项决定的单项计算器，sum_series 在各工作线程中直接创建
+pub(crate) trait FromPrecision<S: Scalar>: SeriesTerm<S> + Sized {
+    fn new(precision: u32) -> Self;
+    
+    // 按计算选项创建，不受选项影响的计算器直接使用 new
+    fn with_options(precision: u32, _options: &ComputeOptions) -> Self {
+        Self::new(precision)
+    }
+}
+
+// 内存优化的 BBP 公式项计算
+// 重用预分配的对象以减少内存分配，对浮点后端泛化
+pub(crate) struct BBPCalculator<S: Scalar> {
+    // 预分配的临时变量，避免每次计算都重新分配
+    term1: S,
+    term2: S,
+    term3: S,
+    term4: S,
+    eight_k: S,
+    sixteen_pow_k: S,
+    denominator1: S,
+    denominator2: S,
+    denominator3: S,
+    denominator4: S,
+    one_over_16: S,
+    sixteen: S,
+    // 上一次计算的 k，用于递推 16^(-k)
+    last_k: Option<usize>,
+}
+
+impl<S: Scalar> FromPrecision<S> for BBPCalculator<S> {
+    fn new(precision: u32) -> Self {
+        let prec = precision;
+        let sixteen = S::from_u64(prec, 16);
+        let mut one_over_16 = S::from_u64(prec, 1);
+        one_over_16.div_mut(&sixteen);
+        
+        Self {
+            term1: S::from_u64(prec, 0),
+            term2: S::from_u64(prec, 0),
+            term3: S::from_u64(prec, 0),
+            term4: S::from_u64(prec, 0),
+            eight_k: S::from_u64(prec, 0),
+            sixteen_pow_k: S::from_u64(prec, 0),
+            denominator1: S::from_u64(prec, 1),
+            denominator2: S::from_u64(prec, 4),
+            denominator3: S::from_u64(prec, 5),
+            denominator4: S::from_u64(prec, 6),
+            one_over_16,
+            sixteen,
+            last_k: None,
+        }
+    }
+}
+
+impl<S: Scalar> SeriesTerm<S> for BBPCalculator<S> {
+    // 计算 BBP 公式的单项
+    fn compute_term(&mut self, k: usize) -> &S {
+        // 计算 8k
+        self.eight_k.assign_u64(8 * k as u64);
+        
+        // 计算分母
+        self.denominator1.assign_u64(1);
+        self.denominator1.add_mut(&self.eight_k);
+        
+        self.denominator2.assign_u64(4);
+        self.denominator2.add_mut(&self.eight_k);
+        
+        self.denominator3.assign_u64(5);
+        self.denominator3.add_mut(&self.eight_k);
+        
+        self.denominator4.assign_u64(6);
+        self.denominator4.add_mut(&self.eight_k);
+        
+        // 计算 4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6)
+        self.term1.assign_u64(4);
+        self.term1.div_mut(&self.denominator1);
+        
+        self.term2.assign_u64(2);
+        self.term2.div_mut(&self.denominator2);
+        
+        self.term3.assign_u64(1);
+        self.term3.div_mut(&self.denominator3);
+        
+        self.term4.assign_u64(1);
+        self.term4.div_mut(&self.denominator4);
+        
+        // 合并项
+        self.term1.sub_mut(&self.term2);
+        self.term1.sub_mut(&self.term3);
+        self.term1.sub_mut(&self.term4);
+        
+        // 计算 16^(-k)，与上一项连续时只需乘以 1/16
+        if self.last_k.is_some_and(|last_k| last_k + 1 == k) {
+            self.sixteen_pow_k.mul_mut(&self.one_over_16);
+        } else if k == 0 {
+            self.sixteen_pow_k.assign_u64(1);
+        } else if k == 1 {
+            self.sixteen_pow_k.assign_from(&self.one_over_16);
+        } else {
+            let pow_result = self.sixteen.pow_u32(k as u32);
+            self.sixteen_pow_k.assign_u64(1);
+            self.sixteen_pow_k.div_mut(&pow_result);
+        }
+        self.last_k = Some(k);
+        
+        // 乘以 16^(-k)
+        self.term1.mul_mut(&self.sixteen_pow_k);
+        
+        &self.term1
+    }
+}
+
+// 进度平滑系数：新采样速率所占权重
+const RATE_SMOOTHING: f64 = 0.3;
+
+// 计算进度及剩余时间估计
+// 分段计算时各段共用同一个 Progress，百分比和剩余时间按全部项数计算
+pub(crate) struct Progress<'a> {
+    done: usize,
+    total: usize,
+    // 从检查点恢复时已完成的项数，不计入本次运行的速率
+    resumed: usize,
+    // 计算开始的时间，已用时间由此得出
+    start: Instant,
+    elapsed: Duration,
+    eta: Duration,
+    // 平滑后的速率（项/秒）
+    rate: f64,
+    samples: usize,
+    // 每次更新后以 (已完成项数, 总项数) 调用
+    callback: Option<&'a dyn Fn(usize, usize)>,
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(
+        total: usize,
+        resumed: usize,
+        start: Instant,
+        callback: Option<&'a dyn Fn(usize, usize)>,
+    ) -> Self {
+        Self {
+            done: resumed,
+            total,
+            resumed,
+            start,
+            elapsed: Duration::ZERO,
+            eta: Duration::ZERO,
+            rate: 0.0,
+            samples: 0,
+            callback,
+        }
+    }
+    
+    // 根据已完成项数和已用时间更新进度
+    fn update(&mut self, done: usize, elapsed: Duration) {
+        self.done = done.min(self.total);
+        self.elapsed = elapsed;
+        
+        let secs = elapsed.as_secs_f64();
+        if secs <= 0.0 || self.done <= self.resumed {
+            return;
+        }
+        
+        // 开始阶段的速率估计波动较大，使用指数滑动平均平滑
+        let current_rate = (self.done - self.resumed) as f64 / secs;
+        self.rate = if self.samples == 0 {
+            current_rate
+        } else {
+            RATE_SMOOTHING * current_rate + (1.0 - RATE_SMOOTHING) * self.rate
+        };
+        self.samples += 1;
+        
+        let remaining = (self.total - self.done) as f64;
+        self.eta = Duration::from_secs_f64(remaining / self.rate);
+    }
+    
+    // 状态行
+    fn status_line(&self) -> String {
+        let percent = if self.total == 0 {
+            100.0
+        } else {
+            self.done as f64 / self.total as f64 * 100.0
+        };
+        format!(
+            "进度: {}/{} 项 ({:.1}%), 已用 {:.1} 秒, 预计剩余 {:.1} 秒",
+            self.done,
+            self.total,
+            percent,
+            self.elapsed.as_secs_f64(),
+            self.eta.as_secs_f64()
+        )
+    }
+}
+
+// 优化的 BBP 公式并行计算
+pub fn compute_pi_optimized(digits: usize, num_threads: usize) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_cancellable(digits, num_threads, &stop, &ComputeOptions::default())
+}
+
+// 可取消的 BBP 并行计算
+// stop 被置位后，各线程在当前项计算完成后退出
+pub fn compute_pi_cancellable(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    // 小位数请求直接使用内置常数
+    if let Some(result) = cached_result(digits, options) {
+        return Ok(result);
+    }
+    
+    compute_pi_series(digits, num_threads, stop, options)
+}
+
+// 位数不超过 CACHED_DIGITS 时由内置常数得到的结果，不累加任何项
+// 用户指定了精度时内置常数的位数可能不够，总是完整计算
+pub(crate) fn cached_result(digits: usize, options: &ComputeOptions) -> Option<ComputeResult> {
+    if options.precision.is_some() {
+        return None;
+    }
+    
+    let precision = required_precision(digits);
+    let value = cached_pi(digits, precision)?;
+    log::info!("位数不超过 {}，直接使用内置的 π 常数", CACHED_DIGITS);
+    
+    Some(ComputeResult {
+        value,
+        digits,
+        terms: 0,
+        precision,
+        algorithm: Algorithm::Bbp,
+        elapsed: Duration::ZERO,
+        throughput: Vec::new(),
+    })
+}
+
+// 达到指定二进制精度需要的 BBP 项数
+// 截断误差约为 16^-k，由 16^-k < 2^-precision 得 k > precision/4
+// 另加随精度对数增长的余量，覆盖尾项系数和累加舍入，保证最后几位正确
+pub(crate) fn bbp_terms(precision: u32) -> usize {
+    let margin = (u32::BITS - precision.leading_zeros()) as usize;
+    (precision as usize).div_ceil(4) + margin + 1
+}
+
+// 不经过内置常数快速路径，总是完整计算 BBP 级数
+pub(crate) fn compute_pi_series(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    log::info!("使用 {} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    
+    let start = Instant::now();
+    let mut workers = SpawnedWorkers::new(num_threads, stop, start);
+    let (value, terms, precision) = bbp_pi(digits, &mut workers, options)?;
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value,
+        digits,
+        terms,
+        precision,
+        algorithm: Algorithm::Bbp,
+        elapsed,
+        throughput: throughput_trace(&workers.samples, BBP_DIGITS_PER_TERM),
+    })
+}
+
+// 累加各项时在目标精度之上额外使用的二进制位，覆盖逐项累加的舍入误差
+const BBP_GUARD_BITS: u32 = 10;
+
+// 以指定的二进制精度完整计算 BBP 级数
+// 同时返回进度采样 (已用时间, 已完成项数)
+pub(crate) fn compute_pi_at_precision(
+    precision: u32,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    start: Instant,
+    options: &ComputeOptions,
+) -> Result<(Float, ProgressSamples), ComputeError> {
+    let mut workers = SpawnedWorkers::new(num_threads, stop, start);
+    let value = bbp_pi_at_precision(precision, &mut workers, options)?;
+    Ok((value, workers.samples))
+}
+
+// 由位数确定精度后完整计算 BBP 级数，返回 (π, 累加项数, 二进制精度)
+// 每次新建线程的 compute_pi_series 与 PiEngine 共用，只有分派求和任务的方式不同
+pub(crate) fn bbp_pi<D: SeriesDispatch>(
+    digits: usize,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<(Float, usize, u32), ComputeError> {
+    let precision = options.precision_for(digits, 0)?;
+    options.check_precision(precision, digits);
+    let value = bbp_pi_at_precision(precision, dispatch, options)?;
+    Ok((value, bbp_terms(precision), precision))
+}
+
+// 以 precision 加上 BBP_GUARD_BITS 的精度累加，检查级数和后只舍入一次到 precision
+fn bbp_pi_at_precision<D: SeriesDispatch>(
+    precision: u32,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<Float, ComputeError> {
+    let terms_needed = bbp_terms(precision);
+    let working_precision = precision.saturating_add(BBP_GUARD_BITS);
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要计算 {} 项...", terms_needed);
+    
+    let mut value = dispatch.sum::<BBPCalculator<Float>>(working_precision, 0..terms_needed, options)?;
+    check_series_sum(&value)?;
+    
+    // 去掉保护位，按最近舍入到 precision，返回值的 prec() 恰好为 precision
+    value.set_prec_round(precision, Round::Nearest);
+    
+    Ok(value)
+}
+
+// 级数求和任务的分派方式：SpawnedWorkers 每次求和新建工作线程，PiEngine 交给常驻线程
+// 两者都按 TERMS_PER_CHUNK 分块、按块的顺序累加，同样的项得到逐位相同的和
+pub(crate) trait SeriesDispatch {
+    fn sum<C: FromPrecision<Float>>(
+        &mut self,
+        precision: u32,
+        terms: Range<usize>,
+        options: &ComputeOptions,
+    ) -> Result<Float, ComputeError>;
+}
+
+// 每次求和新建 num_threads 个工作线程，stop 被置位后返回 Cancelled
+// 依次记录各次求和的进度采样 (已用时间, 已完成项数)
+pub(crate) struct SpawnedWorkers<'a> {
+    pub(crate) num_threads: usize,
+    pub(crate) stop: &'a Arc<AtomicBool>,
+    start: Instant,
+    pub(crate) samples: ProgressSamples,
+}
+
+impl<'a> SpawnedWorkers<'a> {
+    pub(crate) fn new(num_threads: usize, stop: &'a Arc<AtomicBool>, start: Instant) -> Self {
+        Self { num_threads, stop, start, samples: vec![(start.elapsed(), 0)] }
+    }
+}
+
+impl SeriesDispatch for SpawnedWorkers<'_> {
+    fn sum<C: FromPrecision<Float>>(
+        &mut self,
+        precision: u32,
+        terms: Range<usize>,
+        options: &ComputeOptions,
+    ) -> Result<Float, ComputeError> {
+        let mut progress = Progress::new(terms.end, terms.start, self.start, None);
+        let (sum, samples) =
+            sum_series::<Float, C>(precision, terms, self.num_threads, self.stop, &mut progress, options)?;
+        self.samples.extend(samples);
+        Ok(sum)
+    }
+}
+
+// 默认每计算多少项保存一次检查点
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 10_000;
+
+// 分段计算 BBP 级数，每完成 checkpoint.interval 项把部分和保存到 checkpoint.path
+// 与 compute_pi_at_precision 相同，以加上 BBP_GUARD_BITS 的精度累加，检查点也保存这个精度的部分和，
+// 最后只舍入一次到 precision
+// checkpoint.resume 为 true 时从检查点继续，检查点的位数或精度不符时返回 CheckpointMismatch；否则忽略已有的检查点
+// 被取消时保留最近一次的检查点，计算完成后删除检查点文件
+// progress 约每 200 毫秒被调用一次，参数为 (已完成项数, 总项数)
+pub fn compute_pi_checkpointed(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    checkpoint: &CheckpointOptions,
+    progress: Option<&dyn Fn(usize, usize)>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    if let Some(result) = cached_result(digits, options) {
+        return Ok(result);
+    }
+    
+    log::info!("使用 {} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    
+    let start = Instant::now();
+    let precision = options.precision_for(digits, 0)?;
+    options.check_precision(precision, digits);
+    let terms_needed = bbp_terms(precision);
+    let working_precision = precision.saturating_add(BBP_GUARD_BITS);
+    let interval = checkpoint.interval.max(TERMS_PER_CHUNK);
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要计算 {} 项...", terms_needed);
+    
+    let path = checkpoint.path.as_path();
+    
+    let loaded = checkpoint.resume.then(|| load_checkpoint(path, digits, working_precision));
+    let (mut next_term, mut sum) = match loaded {
+        Some(Ok(checkpoint)) => {
+            log::info!("从检查点 {} 继续，已完成 {} 项", path.display(), checkpoint.next_term);
+            (checkpoint.next_term, checkpoint.partial_sum)
+        }
+        // 位数或精度不符的检查点不能接着用，交给调用方决定如何处理
+        Some(Err(e @ ComputeError::CheckpointMismatch { .. })) => return Err(e),
+        Some(Err(e)) => {
+            log::warn!("无法读取检查点 {}: {}，重新开始计算", path.display(), e);
+            (0, Float::with_val(working_precision, 0))
+        }
+        None => (0, Float::with_val(working_precision, 0)),
+    };
+    let mut samples = vec![(start.elapsed(), next_term)];
+    // 各段共用一个进度，百分比和剩余时间不会在每段开始时重新计算
+    let mut overall = Progress::new(terms_needed, next_term, start, progress);
+    
+    while next_term < terms_needed {
+        let end = (next_term + interval).min(terms_needed);
+        let (segment, segment_samples) = sum_series::<Float, BBPCalculator<Float>>(
+            working_precision,
+            next_term..end,
+            num_threads,
+            stop,
+            &mut overall,
+            options,
+        )?;
+        sum += &segment;
+        samples.extend(segment_samples);
+        next_term = end;
+        
+        if next_term < terms_needed {
+            let checkpoint = Checkpoint {
+                digits,
+                precision: working_precision,
+                next_term,
+                partial_sum: sum.clone(),
+            };
+            match save_checkpoint(path, &checkpoint) {
+                Ok(()) => log::debug!("已保存检查点: {} / {} 项", next_term, terms_needed),
+                Err(e) => log::warn!("保存检查点失败: {}", e),
+            }
+        }
+    }
+    
+    check_series_sum(&sum)?;
+    sum.set_prec_round(precision, Round::Nearest);
+    
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("删除检查点 {} 失败: {}", path.display(), e);
+        }
+    }
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value: sum,
+        digits,
+        terms: terms_needed,
+        precision,
+        algorithm: Algorithm::Bbp,
+        elapsed,
+        throughput: throughput_trace(&samples, BBP_DIGITS_PER_TERM),
+    })
+}
+
+// 每个线程一次领取的连续项数
+const TERMS_PER_CHUNK: usize = 64;
+
+// 完整级数和的下限，用于发现没有累加任何项的情况
+const MIN_SERIES_SUM: f64 = 1.0;
+
+// 完整级数的和至少是 π 量级，远小于它说明没有累加任何项（例如线程数为 0）
+pub(crate) fn check_series_sum(sum: &Float) -> Result<(), ComputeError> {
+    if !sum.is_finite() || *sum < MIN_SERIES_SUM {
+        log::error!("级数和异常: {}", sum.to_f64());
+        return Err(ComputeError::EmptySum);
+    }
+    Ok(())
+}
+
+// 级数的并行求和驱动，对浮点后端和单项计算器泛化
+// 计算 terms 范围内各项之和
+// 同时返回每次进度报告时的采样 (已用时间, 已完成项数)
+// 进度的总项数由调用方决定，分段计算时为全部项数而不是本段的结束位置
+// stop 被置位时还有未累加的项则返回 Cancelled
+pub(crate) fn sum_series<S: Scalar, C: FromPrecision<S>>(
+    precision: u32,
+    terms: Range<usize>,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    progress: &mut Progress,
+    options: &ComputeOptions,
+) -> Result<(S, ProgressSamples), ComputeError> {
+    let calculator_options = *options;
+    let new_calculator = move || C::with_options(precision, &calculator_options);
+    sum_series_with(precision, terms, num_threads, stop, progress, options, new_calculator)
+}
+
+// 同 sum_series，各工作线程调用 new_calculator 创建自己的单项计算器
+// 用于除精度外还需要其他参数的级数，如 arctan(1/n) 的 n
+pub(crate) fn sum_series_with<S, C, F>(
+    precision: u32,
+    terms: Range<usize>,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    progress: &mut Progress,
+    options: &ComputeOptions,
+    new_calculator: F,
+) -> Result<(S, ProgressSamples), ComputeError>
+where
+    S: Scalar,
+    C: SeriesTerm<S>,
+    F: Fn() -> C + Copy + Send + 'static,
+{
+    // 至少启动一个工作线程，线程数为 0 时不会累加任何项
+    let num_threads = num_threads.max(1);
+    let pin_threads = options.pin_threads;
+    let early_exit = options.early_exit_for(num_threads);
+    let stack_size = worker_stack_size(terms.len() as u64);
+    let queue = Arc::new(ChunkQueue::new(terms.clone()));
+    
+    // 每个工作线程持有一个发送端，线程结束（包括 panic）时随之丢弃；
+    // 全部丢弃后 recv_timeout 立即返回，不必等到下一个采样时刻
+    let (sender, receiver) = mpsc::channel();
+    let mut handles = Vec::with_capacity(num_threads);
+    for index in 0..num_threads {
+        let queue = Arc::clone(&queue);
+        let stop = Arc::clone(stop);
+        let sender = sender.clone();
+        
+        let handle = spawn_worker(stack_size, move || {
+            pin_worker(index, pin_threads);
+            sum_chunks(&queue, index, precision, early_exit, &stop, &sender, new_calculator);
+        })?;
+        handles.push(handle);
+    }
+    drop(sender);
+    
+    let collected = collect_chunks(precision, &terms, &receiver, stop, progress);
+    for handle in handles {
+        handle.join().map_err(|_| ComputeError::WorkerPanicked)?;
+    }
+    collected
+}
+
+// 一块连续项的部分和，由工作线程发给汇总的一方
+pub(crate) struct ChunkSum<S> {
+    // 块的序号，汇总时按序号顺序累加
+    index: usize,
+    // 计算这一块的工作线程编号
+    worker: usize,
+    // 计入进度的项数，提前结束时包括跳过的其余各项
+    terms: usize,
+    partial: S,
+}
+
+// 按 TERMS_PER_CHUNK 分块的待求和各项，工作线程依次领取下一块
+pub(crate) struct ChunkQueue {
+    terms: Range<usize>,
+    next: AtomicUsize,
+}
+
+impl ChunkQueue {
+    pub(crate) fn new(terms: Range<usize>) -> Self {
+        Self { terms, next: AtomicUsize::new(0) }
+    }
+}
+
+// 工作线程的主循环：领取下一块，用新的单项计算器从块首累加，把部分和发给 sender
+// 每块都从头计算，部分和与由哪个线程、以什么顺序计算无关
+// 直到各块领完、stop 被置位，或 early_exit 时遇到低于最低有效位的项
+pub(crate) fn sum_chunks<S, C, F>(
+    queue: &ChunkQueue,
+    worker: usize,
+    precision: u32,
+    early_exit: bool,
+    stop: &AtomicBool,
+    sender: &mpsc::Sender<ChunkSum<S>>,
+    new_calculator: F,
+) where
+    S: Scalar,
+    C: SeriesTerm<S>,
+    F: Fn() -> C,
+{
+    let (first_term, terms_needed) = (queue.terms.start, queue.terms.end);
+    while !stop.load(Ordering::Relaxed) {
+        let index = queue.next.fetch_add(1, Ordering::SeqCst);
+        let first_k = first_term + index * TERMS_PER_CHUNK;
+        if first_k >= terms_needed {
+            break;
+        }
+        
+        // 块内连续的项可递推计算，如 16^(-k)
+        let chunk_end = (first_k + TERMS_PER_CHUNK).min(terms_needed);
+        let mut calculator = new_calculator();
+        let mut partial = S::from_u64(precision, 0);
+        let mut terms = chunk_end - first_k;
+        let mut exhausted = false;
+        for k in first_k..chunk_end {
+            // 高精度下一块也要算很久，块内同样检查 stop；没算完的块不发送
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let term = calculator.compute_term(k);
+            if early_exit && term.below_ulp(precision) {
+                log::debug!("第 {} 项已低于最低有效位，跳过其余 {} 项", k, terms_needed - k);
+                terms = terms_needed - first_k;
+                exhausted = true;
+                break;
+            }
+            partial.add_mut(term);
+        }
+        
+        // 汇总的一方已经返回时不必再算
+        if sender.send(ChunkSum { index, worker, terms, partial }).is_err() || exhausted {
+            break;
+        }
+    }
+}
+
+// 按块的序号顺序累加各块的部分和，直到所有工作线程都丢弃了发送端；期间按固定间隔报告进度
+// 累加顺序只取决于分块，与线程数和分派方式无关，同样的项得到逐位相同的和
+// 还有未累加的项时：stop 被置位返回 Cancelled，否则是有工作线程 panic，返回 WorkerPanicked
+pub(crate) fn collect_chunks<S: Scalar>(
+    precision: u32,
+    terms: &Range<usize>,
+    receiver: &mpsc::Receiver<ChunkSum<S>>,
+    stop: &AtomicBool,
+    progress: &mut Progress,
+) -> Result<(S, ProgressSamples), ComputeError> {
+    let mut sum = S::from_u64(precision, 0);
+    // 先于前面的块到达的部分和，数量不超过工作线程数的量级
+    let mut pending = BTreeMap::new();
+    let mut next_index = 0;
+    let mut completed = 0;
+    let mut non_finite = None;
+    let mut samples = ProgressSamples::new();
+    let mut next_sample = Instant::now() + SAMPLE_INTERVAL;
+    
+    loop {
+        let received = receiver.recv_timeout(next_sample.saturating_duration_since(Instant::now()));
+        let all_finished = matches!(received, Err(RecvTimeoutError::Disconnected));
+        if let Ok(chunk) = received {
+            completed += chunk.terms;
+            if !chunk.partial.is_finite() && non_finite.is_none() {
+                log::error!("第 {} 个工作线程的局部和不是有限值", chunk.worker);
+                non_finite = Some(chunk.worker);
+            }
+            pending.insert(chunk.index, chunk.partial);
+            while let Some(partial) = pending.remove(&next_index) {
+                sum.add_mut(&partial);
+                next_index += 1;
+            }
+            if Instant::now() < next_sample {
+                continue;
+            }
+        }
+        
+        // 到达采样时刻或线程全部结束时报告一次进度
+        next_sample = Instant::now() + SAMPLE_INTERVAL;
+        progress.update(terms.start + completed, progress.start.elapsed());
+        samples.push((progress.elapsed, progress.done));
+        log::debug!("{}", progress.status_line());
+        if let Some(callback) = progress.callback {
+            callback(progress.done, progress.total);
+        }
+        if all_finished {
+            break;
+        }
+    }
+    
+    if let Some(thread) = non_finite {
+        return Err(ComputeError::NonFinitePartial { thread });
+    }
+    // 置位 stop 之前各项已全部累加完时，结果仍然完整
+    if completed < terms.len() {
+        if stop.load(Ordering::SeqCst) {
+            log::warn!("计算已取消");
+            return Err(ComputeError::Cancelled);
+        }
+        return Err(ComputeError::WorkerPanicked);
+    }
+    
+    Ok((sum, samples))
+}
+
+// BBP 数字提取：不计算前面各位，直接得到 π 小数点后第 n 位十六进制数字（n 从 0 开始）
+// 使用 f64 与模幂运算，n 在千万量级以内结果可靠
+pub fn bbp_hex_digit(n: usize) -> u8 {
+    let x = 4.0 * bbp_partial(1, n) - 2.0 * bbp_partial(4, n) - bbp_partial(5, n) - bbp_partial(6, n);
+    let fraction = x - x.floor();
+    (fraction * 16.0) as u8
+}
+
+// Σ 16^(n-k) / (8k+j) 的小数部分
+fn bbp_partial(j: u64, n: usize) -> f64 {
+    let n = n as u64;
+    let mut sum = 0.0;
+    
+    // k <= n 的部分：分子对分母取模，保持数值很小
+    for k in 0..=n {
+        let denominator = 8 * k + j;
+        sum += mod_pow(16, n - k, denominator) as f64 / denominator as f64;
+        sum -= sum.floor();
+    }
+    
+    // k > n 的部分迅速衰减，取到低于 f64 精度为止
+    let mut k = n + 1;
+    loop {
+        let term = 16f64.powi(-((k - n) as i32)) / (8 * k + j) as f64;
+        if term < 1e-17 {
+            break;
+        }
+        sum += term;
+        k += 1;
+    }
+    
+    sum - sum.floor()
+}
+
+// base^exp mod modulus
+fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+    
+    let mut result = 1u64;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::float::Special;
+    use rug::Assign;
+    use crate::chudnovsky::{inv_pi_from_sum, ChudnovskyCalculator};
+    use crate::output::{fraction_digits, pi_string};
+    use crate::reference::{load_reference_chunk, pi_known_digits};
+    
+    // 临时目录中 digits 位的检查点，不在当前目录留下文件
+    fn temp_checkpoint(digits: usize, interval: usize, resume: bool) -> CheckpointOptions {
+        let name = format!("pi_{}_digits_{}.checkpoint", digits, std::process::id());
+        CheckpointOptions { path: std::env::temp_dir().join(name), interval, resume }
+    }
+    
+    // 内置常数和完整计算两条路径返回的精度都正好是 required_precision，累加时的保护位不会留在结果中；
+    // 命令行使用的分段计算同样如此，各位与完整计算相同
+    #[test]
+    fn returned_precision_matches_required_precision() {
+        for digits in [500, 2000] {
+            let result = compute_pi_optimized(digits, 2).unwrap();
+            assert_eq!(result.value.prec(), required_precision(digits));
+            assert_eq!(result.precision, required_precision(digits));
+        }
+        
+        let digits = 2000;
+        let stop = Arc::new(AtomicBool::new(false));
+        let checkpoint = temp_checkpoint(digits, 256, false);
+        let options = ComputeOptions::default();
+        let segmented = compute_pi_checkpointed(digits, 2, &stop, &checkpoint, None, &options).unwrap();
+        assert_eq!(segmented.value.prec(), required_precision(digits));
+        assert_eq!(segmented.precision, required_precision(digits));
+        assert_eq!(
+            pi_string(&segmented.value, digits),
+            pi_string(&compute_pi_optimized(digits, 2).unwrap().value, digits)
+        );
+        assert!(!checkpoint.path.exists());
+    }
+    
+    #[test]
+    fn empty_sum_is_rejected() {
+        let zero = Float::with_val(64, 0);
+        assert!(matches!(check_series_sum(&zero), Err(ComputeError::EmptySum)));
+        let nan = Float::with_val(64, rug::float::Special::Nan);
+        assert!(matches!(check_series_sum(&nan), Err(ComputeError::EmptySum)));
+        assert!(check_series_sum(&Float::with_val(64, 3)).is_ok());
+    }
+    
+    // 项的范围为空时没有累加任何项：Chudnovsky 由级数和求 1/π 时返回 EmptySum，而不是除以 0；
+    // 从已位于最后一项之后、部分和为 0 的检查点恢复，公开的计算入口同样返回 EmptySum
+    #[test]
+    fn empty_term_range_returns_empty_sum() {
+        let options = ComputeOptions::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let precision = 1024;
+        let (sum, _) = sum_series::<Float, ChudnovskyCalculator>(
+            precision,
+            0..0,
+            2,
+            &stop,
+            &mut Progress::new(0, 0, Instant::now(), None),
+            &options,
+        )
+        .unwrap();
+        assert!(sum.is_zero());
+        assert!(matches!(inv_pi_from_sum(sum, precision), Err(ComputeError::EmptySum)));
+        
+        let digits = 6_011;
+        let precision = options.precision_for(digits, 0).unwrap();
+        let resume = temp_checkpoint(digits, 256, true);
+        let checkpoint = Checkpoint {
+            digits,
+            precision: precision + BBP_GUARD_BITS,
+            next_term: bbp_terms(precision),
+            partial_sum: Float::with_val(precision + BBP_GUARD_BITS, 0),
+        };
+        save_checkpoint(&resume.path, &checkpoint).unwrap();
+        let result = compute_pi_checkpointed(digits, 2, &stop, &resume, None, &options);
+        std::fs::remove_file(&resume.path).unwrap();
+        assert!(matches!(result, Err(ComputeError::EmptySum)), "{:?}", result.map(|r| r.terms));
+    }
+    
+    // 递推得到的 16^(-k) 与直接求幂只差舍入误差，完整计算的结果与参考数据一致
+    #[test]
+    fn incremental_scale_keeps_output_unchanged() {
+        let precision = 256;
+        let mut sequential = BBPCalculator::<Float>::new(precision);
+        for k in 0..50 {
+            sequential.compute_term(k);
+        }
+        let incremental = sequential.compute_term(50).clone();
+        let direct = BBPCalculator::<Float>::new(precision).compute_term(50).clone();
+        let error = Float::with_val(precision, &incremental - &direct).abs();
+        assert!(error < Float::with_val(precision, &direct >> (precision - 8)));
+        
+        let stop = Arc::new(AtomicBool::new(false));
+        let result = compute_pi_series(5000, 4, &stop, &ComputeOptions::default()).unwrap();
+        let computed: Vec<u8> = fraction_digits(&result.value, 5000).map(|d| d + b'0').collect();
+        assert_eq!(Some(computed), load_reference_chunk(0, 5000));
+    }
+    
+    // 按 bbp_terms 累加的项数足以保证最后几位：5000 位的最后 10 位与 Chudnovsky 的结果一致
+    #[test]
+    fn last_ten_of_5000_digits_match_chudnovsky() {
+        let digits = 5000;
+        let stop = Arc::new(AtomicBool::new(false));
+        let options = ComputeOptions::default();
+        let bbp = compute_pi_series(digits, 4, &stop, &options).unwrap();
+        let chudnovsky = crate::chudnovsky::compute_pi_chudnovsky(digits, 4, &options).unwrap();
+        
+        let bbp_str = pi_string(&bbp.value, digits);
+        let chudnovsky_str = pi_string(&chudnovsky.value, digits);
+        assert_eq!(bbp_str[bbp_str.len() - 10..], chudnovsky_str[chudnovsky_str.len() - 10..]);
+        assert_eq!(bbp.terms, bbp_terms(required_precision(digits)));
+    }
+    
+    #[test]
+    fn zero_threads_still_sum_the_series() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let options = ComputeOptions::default();
+        let result = compute_pi_series(2000, 0, &stop, &options).unwrap();
+        let bellard = crate::bellard::compute_pi_bellard(2000, 0, &options).unwrap();
+        assert_eq!(pi_string(&result.value, 2000), pi_string(&bellard.value, 2000));
+    }
+    
+    // 提前结束只跳过低于最低有效位的项，结果的各位与完整求和相同
+    #[test]
+    fn early_exit_keeps_the_digits() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let options = ComputeOptions { early_exit: true, ..ComputeOptions::default() };
+        assert!(options.early_exit_for(1));
+        assert!(!options.early_exit_for(2));
+        
+        let result = compute_pi_series(2000, 1, &stop, &options).unwrap();
+        let computed: Vec<u8> = fraction_digits(&result.value, 2000).map(|d| d + b'0').collect();
+        assert_eq!(Some(computed), load_reference_chunk(0, 2000));
+    }
+    
+    // 分段共用一个进度时，百分比只增不减，速率不变时剩余时间只减不增，不会在每段开始时重置
+    #[test]
+    fn eta_is_monotonic_across_segments() {
+        let total = 10_000;
+        let mut progress = Progress::new(total, 0, Instant::now(), None);
+        let (mut last_done, mut last_eta) = (0, Duration::MAX);
+        
+        for segment in (0..total).step_by(2_500) {
+            for done in (segment + 250..=segment + 2_500).step_by(250) {
+                // 每项 1 毫秒
+                progress.update(done, Duration::from_millis(done as u64));
+                assert_eq!(progress.total, total);
+                assert!(progress.done > last_done);
+                assert!(progress.eta <= last_eta, "第 {} 项: {:?}", done, progress.eta);
+                last_done = progress.done;
+                last_eta = progress.eta;
+            }
+        }
+        assert_eq!(progress.eta, Duration::ZERO);
+        assert!(progress.status_line().contains("(100.0%)"));
+    }
+    
+    // 模拟 --continue：检查点记录前一部分项的和，恢复后只计算其余各项，结果与完整计算一致；
+    // 不指定 --continue 时忽略检查点，记录的和被改动过也不影响结果；完成后删除检查点
+    #[test]
+    fn continue_resumes_from_checkpoint() {
+        let digits = 6_007;
+        let options = ComputeOptions::default();
+        // 检查点保存的是加上保护位的部分和
+        let precision = options.precision_for(digits, 0).unwrap() + BBP_GUARD_BITS;
+        let stop = Arc::new(AtomicBool::new(false));
+        let resume = temp_checkpoint(digits, 256, true);
+        let fresh_start = CheckpointOptions { resume: false, ..resume.clone() };
+        let path = resume.path.as_path();
+        let full = compute_pi_series(digits, 2, &stop, &options).unwrap();
+        let expected = pi_string(&full.value, digits);
+        
+        let next_term = 1_000;
+        let (partial_sum, _) = sum_series::<Float, BBPCalculator<Float>>(
+            precision,
+            0..next_term,
+            2,
+            &stop,
+            &mut Progress::new(next_term, 0, Instant::now(), None),
+            &options,
+        )
+        .unwrap();
+        
+        let checkpoint = Checkpoint { digits, precision, next_term, partial_sum: partial_sum.clone() };
+        save_checkpoint(path, &checkpoint).unwrap();
+        let resumed = compute_pi_checkpointed(digits, 2, &stop, &resume, None, &options).unwrap();
+        assert_eq!(pi_string(&resumed.value, digits), expected);
+        assert!(!path.exists());
+        
+        // 改动过的部分和只有在恢复时才会被使用
+        let checkpoint = Checkpoint { digits, precision, next_term, partial_sum: partial_sum + 1u32 };
+        save_checkpoint(path, &checkpoint).unwrap();
+        let fresh = compute_pi_checkpointed(digits, 2, &stop, &fresh_start, None, &options).unwrap();
+        assert_eq!(pi_string(&fresh.value, digits), expected);
+        assert!(!path.exists());
+        
+        save_checkpoint(path, &checkpoint).unwrap();
+        let tampered = compute_pi_checkpointed(digits, 2, &stop, &resume, None, &options).unwrap();
+        assert!(pi_string(&tampered.value, digits).starts_with("4.14159"));
+        assert!(!path.exists());
+    }
+    
+    // 检查点的位数或精度与本次计算不符时返回 CheckpointMismatch，保留检查点文件
+    #[test]
+    fn mismatched_checkpoint_is_rejected() {
+        let digits = 6_007;
+        let options = ComputeOptions::default();
+        let precision = options.precision_for(digits, 0).unwrap() + BBP_GUARD_BITS;
+        let stop = Arc::new(AtomicBool::new(false));
+        let resume = temp_checkpoint(digits, 256, true);
+        let path = resume.path.as_path();
+        
+        let partial_sum = Float::with_val(precision, 3);
+        let checkpoint = Checkpoint { digits: digits + 1, precision, next_term: 1_000, partial_sum };
+        save_checkpoint(path, &checkpoint).unwrap();
+        let result = compute_pi_checkpointed(digits, 2, &stop, &resume, None, &options);
+        let mismatched = matches!(
+            result,
+            Err(ComputeError::CheckpointMismatch { saved_digits, .. }) if saved_digits == digits + 1
+        );
+        assert!(mismatched, "{:?}", result.map(|r| r.terms));
+        
+        let partial_sum = Float::with_val(precision, 3);
+        let checkpoint = Checkpoint { digits, precision, next_term: 1_000, partial_sum };
+        save_checkpoint(path, &checkpoint).unwrap();
+        let overridden = ComputeOptions { precision: Some(precision + 64), ..options };
+        let result = compute_pi_checkpointed(digits, 2, &stop, &resume, None, &overridden);
+        let mismatched = matches!(
+            result,
+            Err(ComputeError::CheckpointMismatch { saved_precision, .. }) if saved_precision == precision
+        );
+        assert!(mismatched, "{:?}", result.map(|r| r.terms));
+        assert!(path.exists());
+        std::fs::remove_file(path).unwrap();
+    }
+    
+    // 进度按已累加完的项数报告，第一段全部完成时由进度回调置位 stop：
+    // 第一段照常返回并保存检查点，第二段不累加任何项，计算返回 Cancelled
+    // 之后以 resume 继续，结果与参考数据一致，完成后删除检查点
+    #[test]
+    fn interrupted_run_resumes_from_its_checkpoint() {
+        let digits = 10_000;
+        let interval = 1_024;
+        let options = ComputeOptions::default();
+        let precision = options.precision_for(digits, 0).unwrap();
+        let working_precision = precision + BBP_GUARD_BITS;
+        let checkpoint = temp_checkpoint(digits, interval, false);
+        let path = checkpoint.path.as_path();
+        
+        let stop = Arc::new(AtomicBool::new(false));
+        let interrupt = {
+            let stop = Arc::clone(&stop);
+            move |done: usize, _total: usize| {
+                if done >= interval {
+                    stop.store(true, Ordering::SeqCst);
+                }
+            }
+        };
+        let result = compute_pi_checkpointed(digits, 1, &stop, &checkpoint, Some(&interrupt), &options);
+        assert!(matches!(result, Err(ComputeError::Cancelled)), "{:?}", result.map(|r| r.terms));
+        assert!(path.exists());
+        let saved = load_checkpoint(path, digits, working_precision).unwrap();
+        assert_eq!(saved.next_term, interval);
+        assert_eq!(saved.partial_sum.prec(), working_precision);
+        
+        let stop = Arc::new(AtomicBool::new(false));
+        let resume = CheckpointOptions { resume: true, ..checkpoint.clone() };
+        let resumed = compute_pi_checkpointed(digits, 1, &stop, &resume, None, &options).unwrap();
+        assert_eq!(pi_string(&resumed.value, digits)[2..].as_bytes(), load_reference_chunk(0, digits).unwrap());
+        assert!(!path.exists());
+    }
+    
+    // 工作线程全部结束后求和立即返回，短的计算不会等满一个采样间隔；结束时报告一次全部完成的进度
+    #[test]
+    fn short_series_returns_before_first_sample() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let reported = std::cell::Cell::new((0, 0));
+        let report = |done: usize, total: usize| reported.set((done, total));
+        let mut progress = Progress::new(2000, 0, Instant::now(), Some(&report));
+        
+        let start = Instant::now();
+        sum_series::<Float, BBPCalculator<Float>>(
+            1024,
+            0..2000,
+            2,
+            &stop,
+            &mut progress,
+            &ComputeOptions::default(),
+        )
+        .unwrap();
+        assert!(start.elapsed() < SAMPLE_INTERVAL, "耗时 {:?}", start.elapsed());
+        assert_eq!(reported.get(), (2000, 2000));
+    }
+    
+    // f64 后端的 BBP 级数：前 14 项已达到 f64 精度，与 π 一致
+    #[test]
+    fn f64_backend_matches_pi() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let options = ComputeOptions::default();
+        let (sum, _) = sum_series::<f64, BBPCalculator<f64>>(
+            53,
+            0..14,
+            2,
+            &stop,
+            &mut Progress::new(14, 0, Instant::now(), None),
+            &options,
+        )
+        .unwrap();
+        
+        assert!((sum - std::f64::consts::PI).abs() < 1e-15, "{}", sum);
+    }
+    
+    // 50 位请求直接由内置常数得到，不累加任何项，结果与参考数据一致
+    #[test]
+    fn fifty_digits_come_from_the_cache() {
+        let result = compute_pi_optimized(50, 2).unwrap();
+        assert_eq!(result.terms, 0);
+        assert_eq!(result.elapsed, Duration::ZERO);
+        assert_eq!(pi_string(&result.value, 50), pi_known_digits()[..52]);
+        
+        let fraction = load_reference_chunk(0, 50).unwrap();
+        assert_eq!(pi_string(&result.value, 50)[2..].as_bytes(), fraction);
+    }
+    
+    // 第 100 项为 NaN 的单项计算器，用于模拟某个工作线程的局部和变为非有限值
+    struct NanAtTerm {
+        term: Float,
+    }
+    
+    impl FromPrecision<Float> for NanAtTerm {
+        fn new(precision: u32) -> Self {
+            Self { term: Float::new(precision) }
+        }
+    }
+    
+    impl SeriesTerm<Float> for NanAtTerm {
+        fn compute_term(&mut self, k: usize) -> &Float {
+            if k == 100 {
+                self.term.assign(Special::Nan);
+            } else {
+                self.term.assign(1);
+            }
+            &self.term
+        }
+    }
+    
+    // 合并时发现非有限的局部和，返回出错的工作线程编号而不是把 NaN 带入结果
+    #[test]
+    fn non_finite_partial_identifies_worker() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let options = ComputeOptions::default();
+        for num_threads in [1, 4] {
+            let mut progress = Progress::new(1000, 0, Instant::now(), None);
+            let result = sum_series::<Float, NanAtTerm>(
+                256,
+                0..1000,
+                num_threads,
+                &stop,
+                &mut progress,
+                &options,
+            );
+            match result {
+                Err(ComputeError::NonFinitePartial { thread }) => assert!(thread < num_threads),
+                _ => panic!("{} 个线程时应返回 NonFinitePartial", num_threads),
+            }
+        }
+    }
+}
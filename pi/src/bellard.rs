@@ -0,0 +1,191 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use rug::float::Round;
+use rug::Float;
+
+use crate::bbp::{check_series_sum, FromPrecision, SeriesDispatch, SeriesTerm, SpawnedWorkers};
+use crate::scalar::Scalar;
+use crate::throughput::throughput_trace;
+use crate::{Algorithm, ComputeError, ComputeOptions, ComputeResult};
+
+// Bellard 公式每项贡献 10 位二进制，即 log10(1024) 位十进制
+pub const BELLARD_DIGITS_PER_TERM: f64 = 3.010299956639812;
+
+// Bellard 公式单项计算器
+// π = 1/64 * Σ (-1)^k / 1024^k * (-32/(4k+1) - 1/(4k+3) + 256/(10k+1) - 64/(10k+3)
+//                                  - 4/(10k+5) - 4/(10k+7) + 1/(10k+9))
+pub(crate) struct BellardCalculator<S: Scalar> {
+    term: S,
+    fraction: S,
+    denominator: S,
+    // 1024^(-k)
+    scale: S,
+    one_over_1024: S,
+    base: S,
+    // 上一次计算的 k，用于递推 1024^(-k)
+    last_k: Option<usize>,
+}
+
+impl<S: Scalar> BellardCalculator<S> {
+    // term += numerator / denominator，negative 为 true 时减去
+    fn add_fraction(&mut self, numerator: u64, denominator: u64, negative: bool) {
+        self.denominator.assign_u64(denominator);
+        self.fraction.assign_u64(numerator);
+        self.fraction.div_mut(&self.denominator);
+        if negative {
+            self.term.sub_mut(&self.fraction);
+        } else {
+            self.term.add_mut(&self.fraction);
+        }
+    }
+}
+
+impl<S: Scalar> FromPrecision<S> for BellardCalculator<S> {
+    fn new(precision: u32) -> Self {
+        let base = S::from_u64(precision, 1024);
+        let mut one_over_1024 = S::from_u64(precision, 1);
+        one_over_1024.div_mut(&base);
+        
+        Self {
+            term: S::from_u64(precision, 0),
+            fraction: S::from_u64(precision, 0),
+            denominator: S::from_u64(precision, 1),
+            scale: S::from_u64(precision, 1),
+            one_over_1024,
+            base,
+            last_k: None,
+        }
+    }
+}
+
+impl<S: Scalar> SeriesTerm<S> for BellardCalculator<S> {
+    fn compute_term(&mut self, k: usize) -> &S {
+        let k64 = k as u64;
+        // 奇数项整体取反，直接翻转各分式的符号
+        let odd = k % 2 == 1;
+        
+        self.term.assign_u64(0);
+        self.add_fraction(32, 4 * k64 + 1, !odd);
+        self.add_fraction(1, 4 * k64 + 3, !odd);
+        self.add_fraction(256, 10 * k64 + 1, odd);
+        self.add_fraction(64, 10 * k64 + 3, !odd);
+        self.add_fraction(4, 10 * k64 + 5, !odd);
+        self.add_fraction(4, 10 * k64 + 7, !odd);
+        self.add_fraction(1, 10 * k64 + 9, odd);
+        
+        // 计算 1024^(-k)，与上一项连续时只需乘以 1/1024
+        if self.last_k.is_some_and(|last_k| last_k + 1 == k) {
+            self.scale.mul_mut(&self.one_over_1024);
+        } else if k == 0 {
+            self.scale.assign_u64(1);
+        } else {
+            let pow_result = self.base.pow_u32(k as u32);
+            self.scale.assign_u64(1);
+            self.scale.div_mut(&pow_result);
+        }
+        self.last_k = Some(k);
+        
+        self.term.mul_mut(&self.scale);
+        &self.term
+    }
+}
+
+// Bellard 公式的单项（不含 1/64 因子）
+pub fn bellard_term(k: usize, precision: u32) -> Float {
+    let mut calculator = BellardCalculator::<Float>::new(precision);
+    calculator.compute_term(k).clone()
+}
+
+// 达到指定二进制精度需要的 Bellard 项数
+// 截断误差约为 1024^-k，另加随精度对数增长的余量
+pub(crate) fn bellard_terms(precision: u32) -> usize {
+    let margin = (u32::BITS - precision.leading_zeros()) as usize;
+    (precision as usize).div_ceil(10) + margin + 1
+}
+
+// 累加各项时在目标精度之上额外使用的二进制位，覆盖逐项累加的舍入误差
+const BELLARD_GUARD_BITS: u32 = 10;
+
+// 并行 Bellard 公式，与 BBP 共用并行求和驱动
+// 每项约 10 位二进制，项数约为 BBP 的 40%
+pub fn compute_pi_bellard(
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_bellard_cancellable(digits, num_threads, &stop, options)
+}
+
+// 可取消的 Bellard 并行计算，stop 被置位后各线程在当前项计算完成后退出
+pub fn compute_pi_bellard_cancellable(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    log::info!("使用 Bellard 公式、{} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    
+    let start = Instant::now();
+    let mut workers = SpawnedWorkers::new(num_threads, stop, start);
+    let (value, terms, precision) = bellard_pi(digits, &mut workers, options)?;
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value,
+        digits,
+        terms,
+        precision,
+        algorithm: Algorithm::Bellard,
+        elapsed,
+        throughput: throughput_trace(&workers.samples, BELLARD_DIGITS_PER_TERM),
+    })
+}
+
+// 由位数确定精度后完整计算 Bellard 级数，返回 (π, 累加项数, 二进制精度)
+// compute_pi_bellard_cancellable 与 PiEngine 共用，只有分派求和任务的方式不同
+pub(crate) fn bellard_pi<D: SeriesDispatch>(
+    digits: usize,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<(Float, usize, u32), ComputeError> {
+    let precision = options.precision_for(digits, 0)?;
+    options.check_precision(precision, digits);
+    let terms_needed = bellard_terms(precision);
+    let working_precision = precision.saturating_add(BELLARD_GUARD_BITS);
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要计算 {} 项...", terms_needed);
+    
+    let mut value = dispatch.sum::<BellardCalculator<Float>>(working_precision, 0..terms_needed, options)?;
+    check_series_sum(&value)?;
+    value /= 64;
+    // 除以 64 只改变指数；与 BBP 相同，去掉保护位按最近舍入到 precision
+    value.set_prec_round(precision, Round::Nearest);
+    
+    Ok((value, terms_needed, precision))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bbp::compute_pi_series;
+    use crate::pi_string;
+    
+    // 2000 位时与 BBP 逐位一致，同样的精度下项数少于 BBP 的一半
+    #[test]
+    fn matches_bbp_with_fewer_terms() {
+        let options = ComputeOptions::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let bellard = compute_pi_bellard(2000, 2, &options).unwrap();
+        let bbp = compute_pi_series(2000, 2, &stop, &options).unwrap();
+        
+        assert_eq!(bellard.precision, bbp.precision);
+        assert_eq!(bellard.value.prec(), bellard.precision);
+        assert_eq!(pi_string(&bellard.value, 2000), pi_string(&bbp.value, 2000));
+        assert!(bellard.terms * 2 < bbp.terms, "Bellard {} 项，BBP {} 项", bellard.terms, bbp.terms);
+    }
+}
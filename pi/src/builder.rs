@@ -0,0 +1,193 @@
+use std::io::Write;
+
+use crate::retry::{create_file, RetryPolicy};
+use crate::verify::ensure_sane;
+use crate::{
+    compute_pi_with, double_check, recommended_threads, stream_pi, Algorithm, ComputeError,
+    ComputeOptions, ComputeResult, FactorialStrategy, FormatOptions, WriteOptions,
+};
+
+// 链式配置一次计算，未设置的选项与交互式程序的默认值一致
+#[derive(Clone)]
+pub struct PiBuilder {
+    digits: usize,
+    // 未指定时按算法和位数推荐
+    threads: Option<usize>,
+    // 未指定时按位数选择
+    algorithm: Option<Algorithm>,
+    guard_digits: usize,
+    // 直接指定的计算位数，优先于 guard_digits
+    compute_digits: Option<usize>,
+    verify: bool,
+    output_file: Option<String>,
+    format: FormatOptions,
+    write_options: WriteOptions,
+    options: ComputeOptions,
+}
+
+impl Default for PiBuilder {
+    fn default() -> Self {
+        Self {
+            digits: 1000,
+            threads: None,
+            algorithm: None,
+            guard_digits: 0,
+            compute_digits: None,
+            verify: false,
+            output_file: None,
+            format: FormatOptions::default(),
+            write_options: WriteOptions::default(),
+            options: ComputeOptions::default(),
+        }
+    }
+}
+
+impl PiBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn digits(mut self, digits: usize) -> Self {
+        self.digits = digits;
+        self
+    }
+    
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+    
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+    
+    // 额外计算的十进制保护位数
+    pub fn guard_digits(mut self, guard_digits: usize) -> Self {
+        self.guard_digits = guard_digits;
+        self
+    }
+    
+    // 实际计算的位数，输出时仍截断到 digits 位；小于 digits 时按 digits 计算
+    pub fn compute_digits(mut self, compute_digits: usize) -> Self {
+        self.compute_digits = Some(compute_digits);
+        self
+    }
+    
+    // 计算后用更高精度复核全部位数
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+    
+    // 计算后按 format 写入文件
+    pub fn output_file(mut self, filename: impl Into<String>) -> Self {
+        self.output_file = Some(filename.into());
+        self
+    }
+    
+    pub fn format(mut self, format: FormatOptions) -> Self {
+        self.format = format;
+        self
+    }
+    
+    // 写入文件时的缓冲区大小（字节）
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.write_options.buffer_size = buffer_size;
+        self
+    }
+    
+    // 创建输出文件失败时的重试策略
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.write_options.retry = retry;
+        self
+    }
+    
+    // 将工作线程各自绑定到一个 CPU 核心
+    pub fn pin_threads(mut self, pin_threads: bool) -> Self {
+        self.options.pin_threads = pin_threads;
+        self
+    }
+    
+    // 单线程求和时，项低于最低有效位后停止累加
+    pub fn early_exit(mut self, early_exit: bool) -> Self {
+        self.options.early_exit = early_exit;
+        self
+    }
+    
+    // Chudnovsky 级数中阶乘的计算方式
+    pub fn factorial_strategy(mut self, strategy: FactorialStrategy) -> Self {
+        self.options.factorial_strategy = strategy;
+        self
+    }
+    
+    // 直接使用 bits 位二进制精度，不再按位数推导
+    pub fn precision(mut self, bits: u32) -> Self {
+        self.options.precision = Some(bits);
+        self
+    }
+    
+    pub fn compute(&self) -> Result<ComputeResult, ComputeError> {
+        let algorithm = self.algorithm.unwrap_or_else(|| Algorithm::default_for(self.digits));
+        let compute_digits = self.compute_digits
+            .unwrap_or_else(|| self.digits.saturating_add(self.guard_digits))
+            .max(self.digits);
+        let threads = self.threads
+            .unwrap_or_else(|| recommended_threads(algorithm, compute_digits));
+        let result = compute_pi_with(algorithm, compute_digits, threads, &self.options)?;
+        
+        if self.verify {
+            if let Some(position) = double_check(&result.value, self.digits, threads)? {
+                return Err(ComputeError::VerificationFailed { position });
+            }
+        }
+        
+        if let Some(filename) = &self.output_file {
+            ensure_sane(&result.value)?;
+            let mut writer = self.write_options.buffered(create_file(filename, &self.write_options.retry)?);
+            stream_pi(&result.value, self.digits, &mut writer, &self.format)?;
+            writer.flush()?;
+        }
+        
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_reference_chunk, required_precision};
+    
+    // 默认值与交互式程序一致：1000 位，按位数选择算法
+    #[test]
+    fn defaults_match_current_behavior() {
+        let result = PiBuilder::new().compute().unwrap();
+        assert_eq!(result.digits, 1000);
+        assert_eq!(result.algorithm, Algorithm::default_for(1000));
+    }
+    
+    // 指定的算法、保护位和输出格式都生效，写入的文件只包含 digits 位
+    #[test]
+    fn non_default_options_take_effect() {
+        let path = std::env::temp_dir().join(format!("pi_builder_{}.txt", std::process::id()));
+        let result = PiBuilder::new()
+            .digits(500)
+            .algorithm(Algorithm::Bellard)
+            .threads(3)
+            .guard_digits(20)
+            .verify(true)
+            .output_file(path.to_str().unwrap())
+            .format(FormatOptions::raw())
+            .compute()
+            .unwrap();
+        
+        assert_eq!(result.algorithm, Algorithm::Bellard);
+        assert_eq!(result.digits, 520);
+        assert_eq!(result.precision, required_precision(520));
+        assert_eq!(std::fs::read(&path).unwrap(), load_reference_chunk(0, 500).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        
+        let result = PiBuilder::new().digits(500).compute_digits(100).precision(4000).compute().unwrap();
+        assert_eq!((result.digits, result.precision), (500, 4000));
+    }
+}
@@ -0,0 +1,88 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use rug::Float;
+
+use crate::ComputeError;
+
+// BBP 级数计算的中间状态：前 next_term 项的部分和
+pub struct Checkpoint {
+    pub digits: usize,
+    // 部分和的二进制精度，包括累加时的保护位
+    pub precision: u32,
+    pub next_term: usize,
+    pub partial_sum: Float,
+}
+
+// 分段计算时检查点的保存位置和方式
+#[derive(Clone, Debug)]
+pub struct CheckpointOptions {
+    // 检查点文件，命令行使用 checkpoint_path(digits)
+    pub path: PathBuf,
+    // 每计算多少项保存一次检查点
+    pub interval: usize,
+    // 从 path 中的检查点继续，否则忽略已有的检查点
+    pub resume: bool,
+}
+
+// 默认的检查点文件名
+pub fn checkpoint_path(digits: usize) -> String {
+    format!("pi_{}_digits.checkpoint", digits)
+}
+
+// 保存检查点，部分和以十六进制完整记录，不损失精度
+// 先写入临时文件再重命名，中途被打断也不会破坏已有的检查点
+pub fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> io::Result<()> {
+    let tmp_path = path.with_extension("checkpoint.tmp");
+    
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    writeln!(writer, "digits: {}", checkpoint.digits)?;
+    writeln!(writer, "precision: {}", checkpoint.precision)?;
+    writeln!(writer, "next_term: {}", checkpoint.next_term)?;
+    writeln!(writer, "sum: {}", checkpoint.partial_sum.to_string_radix(16, None))?;
+    writer.flush()?;
+    drop(writer);
+    
+    std::fs::rename(&tmp_path, path)
+}
+
+// 读取检查点，位数或精度与 digits、precision 不符时返回 CheckpointMismatch
+// 不同精度下各项的舍入不同，部分和不能接着用
+pub fn load_checkpoint(path: &Path, digits: usize, precision: u32) -> Result<Checkpoint, ComputeError> {
+    let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, format!("检查点文件格式错误: {}", what));
+    
+    let mut saved_digits = None;
+    let mut saved_precision = None;
+    let mut next_term = None;
+    let mut partial_sum = None;
+    
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        let Some((key, value)) = line.split_once(": ") else {
+            continue;
+        };
+        match key {
+            "digits" => saved_digits = Some(value.parse::<usize>().map_err(|_| invalid("digits"))?),
+            "precision" => saved_precision = Some(value.parse::<u32>().map_err(|_| invalid("precision"))?),
+            "next_term" => next_term = Some(value.parse::<usize>().map_err(|_| invalid("next_term"))?),
+            "sum" => {
+                let parsed = Float::parse_radix(value, 16).map_err(|_| invalid("sum"))?;
+                partial_sum = Some(Float::with_val(precision, parsed));
+            }
+            _ => {}
+        }
+    }
+    
+    let saved_digits = saved_digits.ok_or_else(|| invalid("缺少 digits"))?;
+    let saved_precision = saved_precision.ok_or_else(|| invalid("缺少 precision"))?;
+    if saved_digits != digits || saved_precision != precision {
+        return Err(ComputeError::CheckpointMismatch { digits, precision, saved_digits, saved_precision });
+    }
+    
+    Ok(Checkpoint {
+        digits,
+        precision,
+        next_term: next_term.ok_or_else(|| invalid("缺少 next_term"))?,
+        partial_sum: partial_sum.ok_or_else(|| invalid("缺少 sum"))?,
+    })
+}
@@ -0,0 +1,1077 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use rug::{Assign, Float, Integer};
+use rug::ops::Pow;
+
+use crate::affinity::pin_worker;
+use crate::bbp::{check_series_sum, FromPrecision, SeriesDispatch, SeriesTerm, SpawnedWorkers};
+use crate::invariants::{check_chudnovsky_recurrence, check_chudnovsky_sum, check_term_decreasing};
+use crate::throughput::throughput_trace;
+use crate::{checked_precision, Algorithm, ComputeError, ComputeOptions, ComputeResult};
+
+// Chudnovsky 级数每项增加的十进制位数: log10(640320^3 / 12^3)
+pub const CHUDNOVSKY_DIGITS_PER_TERM: f64 = 14.181647462725477;
+
+// 累加项数较多，在 required_precision 之外额外保留的保护位
+pub(crate) const CHUDNOVSKY_GUARD_BITS: u32 = 22;
+
+// 计算 k!、(3k)!、(6k)! 的方式，用于比较阶乘计算对整体耗时的影响
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FactorialStrategy {
+    // 每次从 1 开始逐个相乘
+    Naive,
+    // 每次调用 GMP 的阶乘函数
+    Gmp,
+    // 连续的 k 用递推关系更新，不连续时调用 GMP（默认）
+    #[default]
+    Incremental,
+}
+
+impl FactorialStrategy {
+    pub fn all() -> &'static [FactorialStrategy] {
+        &[FactorialStrategy::Naive, FactorialStrategy::Gmp, FactorialStrategy::Incremental]
+    }
+    
+    // 命令行中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            FactorialStrategy::Naive => "naive",
+            FactorialStrategy::Gmp => "gmp",
+            FactorialStrategy::Incremental => "incremental",
+        }
+    }
+    
+    // 名称不区分大小写
+    pub fn from_name(name: &str) -> Option<FactorialStrategy> {
+        let name = name.trim();
+        Self::all().iter().copied().find(|s| s.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// Chudnovsky 算法单项计算器
+pub(crate) struct ChudnovskyCalculator {
+    // 预分配的临时变量
+    term: Float,
+    numerator: Integer,
+    denominator: Integer,
+    k_factorial: Integer,
+    three_k_factorial: Integer,
+    six_k_factorial: Integer,
+    // 阶乘状态当前对应的 k
+    factorial_k: usize,
+    strategy: FactorialStrategy,
+    // 常数
+    c: Integer,
+    d: Integer,
+    // 上一项的 (k, |项|) 和 (k, 项)，debug 构建中用于检查各项单调递减且符合递推关系
+    previous_term: Option<(usize, Float)>,
+    previous_exact: Option<(usize, Float)>,
+}
+
+impl FromPrecision<Float> for ChudnovskyCalculator {
+    fn new(precision: u32) -> Self {
+        Self::with_strategy(precision, FactorialStrategy::default())
+    }
+    
+    fn with_options(precision: u32, options: &ComputeOptions) -> Self {
+        Self::with_strategy(precision, options.factorial_strategy)
+    }
+}
+
+impl SeriesTerm<Float> for ChudnovskyCalculator {
+    // 计算 Chudnovsky 算法的单项
+    // (-1)^k * (6k)! * (13591409 + 545140134k) / ((3k)! * (k!)^3 * 640320^(3k))
+    fn compute_term(&mut self, k: usize) -> &Float {
+        self.update_factorials(k);
+        
+        // 计算分子: (-1)^k * (6k)! * (13591409 + 545140134k)
+        self.numerator.assign(&self.six_k_factorial);
+        let mut coefficient = Integer::from(&self.c);
+        coefficient += Integer::from(&self.d * k as u64);
+        self.numerator *= &coefficient;
+        
+        if k % 2 == 1 {
+            self.numerator = (-&self.numerator).into();
+        }
+        
+        // 计算分母: (3k)! * (k!)^3 * 640320^(3k)
+        self.denominator.assign(&self.three_k_factorial);
+        let k_fact_cubed = Integer::from(&self.k_factorial).pow(3u32);
+        self.denominator *= &k_fact_cubed;
+        
+        let power_term = Integer::from(640320).pow((3 * k) as u32);
+        self.denominator *= &power_term;
+        
+        // 将分数转换为浮点数
+        let num_float = Float::with_val(self.term.prec(), &self.numerator);
+        let den_float = Float::with_val(self.term.prec(), &self.denominator);
+        
+        self.term.assign(&num_float / &den_float);
+        check_term_decreasing(k, &mut self.previous_term, &self.term);
+        check_chudnovsky_recurrence(k, &mut self.previous_exact, &self.term);
+        &self.term
+    }
+}
+
+impl ChudnovskyCalculator {
+    // 使用指定的阶乘策略
+    fn with_strategy(precision: u32, strategy: FactorialStrategy) -> Self {
+        Self {
+            term: Float::with_val(precision, 0),
+            numerator: Integer::new(),
+            denominator: Integer::new(),
+            k_factorial: Integer::from(1),
+            three_k_factorial: Integer::from(1),
+            six_k_factorial: Integer::from(1),
+            factorial_k: 0,
+            strategy,
+            c: Integer::from(13591409),
+            d: Integer::from(545140134),
+            previous_term: None,
+            previous_exact: None,
+        }
+    }
+    
+    // 按阶乘策略更新到 k，k 可以按任意顺序出现
+    fn update_factorials(&mut self, k: usize) {
+        match self.strategy {
+            FactorialStrategy::Incremental => self.advance_to(k),
+            FactorialStrategy::Gmp => self.reset_to(k),
+            FactorialStrategy::Naive => self.naive_to(k),
+        }
+    }
+    
+    // 使用递推关系从 k-1 更新到 k；当前状态不是 k-1 时（乱序或跳跃）由 reset_to 重新计算
+    fn advance_to(&mut self, k: usize) {
+        if k != self.factorial_k + 1 {
+            self.reset_to(k);
+            return;
+        }
+        
+        // k! = (k-1)! * k
+        self.k_factorial *= k as u64;
+        
+        // (3k)! = (3(k-1))! * (3k-2)*(3k-1)*3k
+        for i in 1..=3 {
+            self.three_k_factorial *= (3 * k - 3 + i) as u64;
+        }
+        
+        // (6k)! = (6(k-1))! * (6k-5)*(6k-4)*(6k-3)*(6k-2)*(6k-1)*6k
+        for i in 1..=6 {
+            self.six_k_factorial *= (6 * k - 6 + i) as u64;
+        }
+        
+        self.factorial_k = k;
+    }
+    
+    // 直接计算任意 k 对应的 k!、(3k)!、(6k)!，不依赖之前的状态
+    fn reset_to(&mut self, k: usize) {
+        if k == self.factorial_k {
+            return;
+        }
+        self.k_factorial.assign(Integer::factorial(k as u32));
+        self.three_k_factorial.assign(Integer::factorial((3 * k) as u32));
+        self.six_k_factorial.assign(Integer::factorial((6 * k) as u32));
+        self.factorial_k = k;
+    }
+    
+    // 逐个相乘计算 k!、(3k)!、(6k)!
+    fn naive_to(&mut self, k: usize) {
+        if k == self.factorial_k {
+            return;
+        }
+        self.k_factorial = naive_factorial(k as u64);
+        self.three_k_factorial = naive_factorial(3 * k as u64);
+        self.six_k_factorial = naive_factorial(6 * k as u64);
+        self.factorial_k = k;
+    }
+}
+
+fn naive_factorial(n: u64) -> Integer {
+    let mut result = Integer::from(1);
+    for i in 2..=n {
+        result *= i;
+    }
+    result
+}
+
+// 按递推关系依次产生 Chudnovsky 级数的各项，与 ChudnovskyCalculator::compute_term 的结果相同
+// M_k = M_{k-1} * (6k-5)(2k-1)(6k-1) / (k^3 * 640320^3 / 24)，第 k 项为 (-1)^k * M_k * (13591409 + 545140134k)
+// 递推因子的分子和分母都是精确整数，每项只在乘、除时各舍入一次，不经过 f64
+pub struct ChudnovskyIter {
+    // M_k 的绝对值
+    m_k: Float,
+    // 下一次产生的项的下标
+    k: u64,
+}
+
+impl ChudnovskyIter {
+    pub fn new(precision: u32) -> Self {
+        Self { m_k: Float::with_val(precision, 1), k: 0 }
+    }
+}
+
+impl Iterator for ChudnovskyIter {
+    type Item = Float;
+    
+    fn next(&mut self) -> Option<Float> {
+        let k = self.k;
+        if k > 0 {
+            let (numerator, denominator) = recurrence_factor(k);
+            self.m_k *= &numerator;
+            self.m_k /= &denominator;
+        }
+        self.k += 1;
+        
+        let mut term = Float::with_val(self.m_k.prec(), &self.m_k * (13591409 + 545140134 * k));
+        if k % 2 == 1 {
+            term = -term;
+        }
+        Some(term)
+    }
+}
+
+// M_k / M_{k-1} 的分子 (6k-5)(2k-1)(6k-1) 和分母 k^3 * 640320^3 / 24（k ≥ 1）
+fn recurrence_factor(k: u64) -> (Integer, Integer) {
+    let mut numerator = Integer::from(6 * k - 5);
+    numerator *= 2 * k - 1;
+    numerator *= 6 * k - 1;
+    let mut denominator = Integer::from(k).pow(3u32);
+    denominator *= C3_OVER_24;
+    (numerator, denominator)
+}
+
+// 由第 k-1 项按递推关系得到第 k 项（k ≥ 1），用于与直接由阶乘计算的项互相核对
+// 相邻两项之比为 -M_k/M_{k-1} * (13591409 + 545140134k) / (13591409 + 545140134(k-1))
+pub(crate) fn next_term_by_recurrence(previous: &Float, k: u64) -> Float {
+    let (mut numerator, mut denominator) = recurrence_factor(k);
+    numerator *= 13591409 + 545140134 * k;
+    denominator *= 13591409 + 545140134 * (k - 1);
+    
+    let mut term = Float::with_val(previous.prec(), previous * &numerator);
+    term /= &denominator;
+    -term
+}
+
+// 计算所需项数
+// 每项约增加 14.18 位十进制数字，多算 1 项作为安全余量
+// 阶乘和幂的参数（最大为 6k）必须放得进 u32，超出时返回错误而不是回绕
+pub fn chudnovsky_terms(digits: usize) -> Result<usize, ComputeError> {
+    let too_many = || ComputeError::TooManyDigits { digits, max: max_chudnovsky_digits() };
+    
+    let terms = (digits as f64 / CHUDNOVSKY_DIGITS_PER_TERM).ceil();
+    if terms >= (u32::MAX / 6) as f64 {
+        return Err(too_many());
+    }
+    
+    let terms_needed = (terms as usize).checked_add(1).ok_or_else(too_many)?;
+    match terms_needed.checked_mul(6) {
+        Some(n) if n <= u32::MAX as usize => Ok(terms_needed),
+        _ => Err(too_many()),
+    }
+}
+
+// 项数限制对应的最大位数
+fn max_chudnovsky_digits() -> usize {
+    let max_terms = u32::MAX as usize / 6 - 1;
+    let max_digits = (max_terms as f64 * CHUDNOVSKY_DIGITS_PER_TERM).floor();
+    if max_digits >= usize::MAX as f64 {
+        usize::MAX
+    } else {
+        max_digits as usize
+    }
+}
+
+// 并行 Chudnovsky 算法
+pub fn compute_pi_chudnovsky(
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_chudnovsky_cancellable(digits, num_threads, &stop, options)
+}
+
+// 可取消的 Chudnovsky 并行计算
+// stop 被置位后，各线程在当前一批项计算完成后退出
+pub fn compute_pi_chudnovsky_cancellable(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    log::info!("使用 Chudnovsky 算法、{} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    
+    let start = Instant::now();
+    let mut workers = SpawnedWorkers::new(num_threads, stop, start);
+    let (value, terms, precision) = chudnovsky_pi(digits, &mut workers, options)?;
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value,
+        digits,
+        terms,
+        precision,
+        algorithm: Algorithm::Chudnovsky,
+        elapsed,
+        throughput: throughput_trace(&workers.samples, CHUDNOVSKY_DIGITS_PER_TERM),
+    })
+}
+
+// 直接计算 1/π，不经过先求 π 再取倒数的额外舍入
+pub fn compute_inv_pi(digits: usize, num_threads: usize) -> Result<Float, ComputeError> {
+    log::info!("使用 Chudnovsky 算法、{} 个线程计算 1/π 到小数点后 {} 位...", num_threads, digits);
+    
+    let start = Instant::now();
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut workers = SpawnedWorkers::new(num_threads, &stop, start);
+    let (inv_pi, _, _) = chudnovsky_inv_pi(digits, &mut workers, &ComputeOptions::default())?;
+    log::info!("计算完成，耗时: {:.2} 秒", start.elapsed().as_secs_f64());
+    
+    Ok(inv_pi)
+}
+
+// 由位数确定精度后完整计算 Chudnovsky 级数，返回 (π, 累加项数, 二进制精度)
+// compute_pi_chudnovsky_cancellable 与 PiEngine 共用，只有分派求和任务的方式不同
+pub(crate) fn chudnovsky_pi<D: SeriesDispatch>(
+    digits: usize,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<(Float, usize, u32), ComputeError> {
+    // π = (426880 * sqrt(10005)) / series_sum
+    let (inv_pi, terms, precision) = chudnovsky_inv_pi(digits, dispatch, options)?;
+    Ok((inv_pi.recip(), terms, precision))
+}
+
+// Chudnovsky 级数本身给出的是 1/π = series_sum / (426880 * sqrt(10005))
+// 返回 (1/π, 累加项数, 二进制精度)
+fn chudnovsky_inv_pi<D: SeriesDispatch>(
+    digits: usize,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<(Float, usize, u32), ComputeError> {
+    let precision = options.precision_for(digits, CHUDNOVSKY_GUARD_BITS)?;
+    let terms_needed = chudnovsky_terms(digits)?;
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要计算 {} 项...", terms_needed);
+    options.check_precision(precision, digits);
+    
+    let series_sum = dispatch.sum::<ChudnovskyCalculator>(precision, 0..terms_needed, options)?;
+    let inv_pi = inv_pi_from_sum(series_sum, precision)?;
+    Ok((inv_pi, terms_needed, precision))
+}
+
+// 由级数和得到 1/π = series_sum / (426880 * sqrt(10005))
+pub(crate) fn inv_pi_from_sum(series_sum: Float, precision: u32) -> Result<Float, ComputeError> {
+    // 第 0 项就是 13591409，和远小于它说明没有累加任何项
+    check_series_sum(&series_sum)?;
+    check_chudnovsky_sum(&series_sum);
+    
+    Ok(series_sum / chudnovsky_denominator(precision))
+}
+
+// 426880 * sqrt(10005) = sqrt(426880^2 * 10005)，根号内是精确整数，只需一次舍入
+fn chudnovsky_denominator(precision: u32) -> Float {
+    let mut denominator = Float::with_val(precision, Integer::from(426880).square() * 10005u32);
+    denominator.sqrt_mut();
+    denominator
+}
+
+// 部分和给出的一个近似值
+#[derive(Debug)]
+pub struct ConvergenceStep {
+    // 已累加的项数
+    pub terms: usize,
+    // 由前 terms 项得到的 π 近似值
+    pub value: Float,
+    // 与全部项的结果一致的小数位数，不超过请求的位数
+    pub correct_digits: usize,
+}
+
+// 单线程按顺序累加 Chudnovsky 级数，每累加 block 项记录一次由部分和得到的 π 近似值
+// 用于演示级数的收敛速度：每项约增加 14 位正确数字
+pub fn chudnovsky_convergence(digits: usize, block: usize) -> Result<Vec<ConvergenceStep>, ComputeError> {
+    let precision = checked_precision(digits, CHUDNOVSKY_GUARD_BITS)?;
+    let terms_needed = chudnovsky_terms(digits)?;
+    let block = block.max(1);
+    let denominator = chudnovsky_denominator(precision);
+    
+    let mut calculator = ChudnovskyCalculator::new(precision);
+    let mut sum = Float::with_val(precision, 0);
+    let mut approximations = Vec::with_capacity(terms_needed.div_ceil(block));
+    for k in 0..terms_needed {
+        sum += calculator.compute_term(k);
+        if (k + 1) % block == 0 || k + 1 == terms_needed {
+            approximations.push((k + 1, Float::with_val(precision, &denominator / &sum)));
+        }
+    }
+    
+    // 全部项的结果就是 digits 位的 π，以它为准统计各近似值的正确位数
+    let pi = match approximations.last() {
+        Some((_, value)) => value.clone(),
+        None => return Err(ComputeError::EmptySum),
+    };
+    let steps = approximations
+        .into_iter()
+        .map(|(terms, value)| {
+            let correct_digits = matching_digits(&value, &pi, digits);
+            ConvergenceStep { terms, value, correct_digits }
+        })
+        .collect();
+    Ok(steps)
+}
+
+// 由误差 |a - b| 估算一致的小数位数：误差小于 10^-n 时前 n 位一致，最多 max 位
+fn matching_digits(a: &Float, b: &Float, max: usize) -> usize {
+    let error = Float::with_val(a.prec(), a - b).abs();
+    if error.is_zero() {
+        return max;
+    }
+    
+    let digits = -error.log10().to_f64();
+    if digits <= 0.0 {
+        0
+    } else {
+        (digits.floor() as usize).min(max)
+    }
+}
+
+// 640320^3 / 24
+const C3_OVER_24: u64 = 10_939_058_860_032_000;
+
+// 区间项数不少于此值时，合并中的乘法在不同线程中同时进行
+const PARALLEL_MERGE_MIN_TERMS: u64 = 256;
+
+// Chudnovsky 级数二进分割中区间 [a, b) 的三元组，全部为精确整数
+// T / Q 是区间内各项之和除以第 a 项之前的公共因子，P 是区间内相邻项之比的分子之积
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitTriple {
+    pub p: Integer,
+    pub q: Integer,
+    pub t: Integer,
+}
+
+impl SplitTriple {
+    // 空区间：P = Q = 1，T = 0，与任何三元组合并都不改变结果
+    pub(crate) fn identity() -> Self {
+        Self { p: Integer::from(1), q: Integer::from(1), t: Integer::new() }
+    }
+    
+    // 单项 k：k = 0 时 P = Q = 1，T = 13591409
+    // 否则 P = -(6k-5)(2k-1)(6k-1)，Q = k^3 * 640320^3 / 24，T = P * (13591409 + 545140134k)
+    fn leaf(k: u64) -> Self {
+        if k == 0 {
+            return Self { p: Integer::from(1), q: Integer::from(1), t: Integer::from(13591409) };
+        }
+        
+        let mut p = Integer::from(6 * k - 5);
+        p *= 2 * k - 1;
+        p *= 6 * k - 1;
+        p = -p;
+        
+        let mut q = Integer::from(k).pow(3u32);
+        q *= C3_OVER_24;
+        
+        let t = Integer::from(&p * (13591409 + 545140134 * k));
+        Self { p, q, t }
+    }
+    
+    // 合并相邻区间 [a, m) 和 [m, b): P = P1*P2, Q = Q1*Q2, T = T1*Q2 + P1*T2
+    // parallel 为 true 时四个乘法在不同线程中同时进行
+    pub(crate) fn merge(left: &SplitTriple, right: &SplitTriple, parallel: bool) -> SplitTriple {
+        if !parallel {
+            let mut t = Integer::from(&left.t * &right.q);
+            t += Integer::from(&left.p * &right.t);
+            return SplitTriple {
+                p: Integer::from(&left.p * &right.p),
+                q: Integer::from(&left.q * &right.q),
+                t,
+            };
+        }
+        
+        thread::scope(|scope| {
+            let p = scope.spawn(|| Integer::from(&left.p * &right.p));
+            let q = scope.spawn(|| Integer::from(&left.q * &right.q));
+            let p1t2 = scope.spawn(|| Integer::from(&left.p * &right.t));
+            let mut t = Integer::from(&left.t * &right.q);
+            t += p1t2.join().unwrap();
+            SplitTriple { p: p.join().unwrap(), q: q.join().unwrap(), t }
+        })
+    }
+}
+
+// 二进分割计算第 a 到 b - 1 项的三元组
+// parallel_merge 为 true 时，项数不少于 PARALLEL_MERGE_MIN_TERMS 的区间并行执行合并中的乘法
+pub fn binary_split(a: u64, b: u64, parallel_merge: bool) -> SplitTriple {
+    if b <= a {
+        return SplitTriple::identity();
+    }
+    if b - a == 1 {
+        return SplitTriple::leaf(a);
+    }
+    
+    let m = a + (b - a) / 2;
+    let left = binary_split(a, m, parallel_merge);
+    let right = binary_split(m, b, parallel_merge);
+    SplitTriple::merge(&left, &right, parallel_merge && b - a >= PARALLEL_MERGE_MIN_TERMS)
+}
+
+// 可取消的二进分割：进入每个区间前检查 stop，被置位时返回 None，已算出的部分直接丢弃
+fn binary_split_cancellable(a: u64, b: u64, stop: &AtomicBool) -> Option<SplitTriple> {
+    if stop.load(Ordering::Relaxed) {
+        return None;
+    }
+    if b <= a {
+        return Some(SplitTriple::identity());
+    }
+    if b - a == 1 {
+        return Some(SplitTriple::leaf(a));
+    }
+    
+    let m = a + (b - a) / 2;
+    let left = binary_split_cancellable(a, m, stop)?;
+    let right = binary_split_cancellable(m, b, stop)?;
+    Some(SplitTriple::merge(&left, &right, false))
+}
+
+// 二进分割每层递归占用的栈空间估计（字节），包括合并时的临时变量
+const BINARY_SPLIT_FRAME_SIZE: usize = 64 * 1024;
+
+// 线程栈的下限，与标准库默认的 2 MB 一致
+const MIN_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+// 按递归深度 log2(terms) * 每层栈帧估算处理 terms 项的工作线程需要的栈大小
+pub(crate) fn worker_stack_size(terms: u64) -> usize {
+    let depth = (u64::BITS - terms.leading_zeros()) as usize + 1;
+    MIN_STACK_SIZE + depth * BINARY_SPLIT_FRAME_SIZE
+}
+
+// 以 stack_size 字节的栈创建工作线程，系统无法创建线程时返回 ComputeError::Io
+pub(crate) fn spawn_worker<F, T>(stack_size: usize, f: F) -> Result<JoinHandle<T>, ComputeError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Ok(thread::Builder::new().stack_size(stack_size).spawn(f)?)
+}
+
+// dump_triples 允许的最大项数，避免输出过大的文件
+pub const MAX_DUMP_TRIPLES_TERMS: u64 = 64;
+
+// 按与 binary_split 相同的分割方式计算前 terms 项，把每个叶子和合并节点的 (P, Q, T) 写入 writer，用于调试
+// 每行格式: "leaf|merge a b P Q T"，子节点在父节点之前；返回写入的节点数（terms 项共 2 * terms - 1 个）
+pub fn dump_triples<W: Write>(terms: u64, writer: &mut W) -> io::Result<usize> {
+    if terms == 0 || terms > MAX_DUMP_TRIPLES_TERMS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("只能输出 1 到 {} 项的中间结果，当前 {} 项", MAX_DUMP_TRIPLES_TERMS, terms),
+        ));
+    }
+    
+    let mut nodes = 0;
+    dump_range(0, terms, writer, &mut nodes)?;
+    Ok(nodes)
+}
+
+fn dump_range<W: Write>(
+    a: u64,
+    b: u64,
+    writer: &mut W,
+    nodes: &mut usize,
+) -> io::Result<SplitTriple> {
+    let (kind, triple) = if b - a == 1 {
+        ("leaf", SplitTriple::leaf(a))
+    } else {
+        let m = a + (b - a) / 2;
+        let left = dump_range(a, m, writer, nodes)?;
+        let right = dump_range(m, b, writer, nodes)?;
+        ("merge", SplitTriple::merge(&left, &right, false))
+    };
+    
+    writeln!(writer, "{} {} {} {} {} {}", kind, a, b, triple.p, triple.q, triple.t)?;
+    *nodes += 1;
+    Ok(triple)
+}
+
+// 二进分割中各整数的位数，用于估计内存
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitLengthStats {
+    pub terms: u64,
+    // 最终 P、Q、T 的位数
+    pub p_bits: u32,
+    pub q_bits: u32,
+    pub t_bits: u32,
+    // 所有节点中 P、Q、T 的最大位数
+    pub peak: u32,
+    // 每层节点的最大位数，下标 0 为根节点
+    pub per_depth: Vec<u32>,
+}
+
+impl BitLengthStats {
+    fn record(&mut self, depth: usize, triple: &SplitTriple) {
+        let bits = triple.p.significant_bits()
+            .max(triple.q.significant_bits())
+            .max(triple.t.significant_bits());
+        if self.per_depth.len() <= depth {
+            self.per_depth.resize(depth + 1, 0);
+        }
+        self.per_depth[depth] = self.per_depth[depth].max(bits);
+        self.peak = self.peak.max(bits);
+    }
+}
+
+// 单线程按与 binary_split 相同的方式分割 digits 位所需的项数，统计各节点 P、Q、T 的位数
+pub fn binary_split_bit_lengths(digits: usize) -> Result<BitLengthStats, ComputeError> {
+    let terms = chudnovsky_terms(digits)? as u64;
+    let mut stats = BitLengthStats { terms, ..BitLengthStats::default() };
+    let triple = record_range(0, terms, 0, &mut stats);
+    
+    stats.p_bits = triple.p.significant_bits();
+    stats.q_bits = triple.q.significant_bits();
+    stats.t_bits = triple.t.significant_bits();
+    Ok(stats)
+}
+
+fn record_range(a: u64, b: u64, depth: usize, stats: &mut BitLengthStats) -> SplitTriple {
+    let triple = if b - a == 1 {
+        SplitTriple::leaf(a)
+    } else {
+        let m = a + (b - a) / 2;
+        let left = record_range(a, m, depth + 1, stats);
+        let right = record_range(m, b, depth + 1, stats);
+        SplitTriple::merge(&left, &right, false)
+    };
+    
+    stats.record(depth, &triple);
+    triple
+}
+
+// 二进分割的 Chudnovsky 算法：各项全部用整数累加，最后只做一次开方和两次除法
+// 项数区间均分给各线程，各线程的结果按顺序合并；顶层合并的整数最大，其中的乘法并行执行
+pub fn compute_pi_binary_split(digits: usize, num_threads: usize) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_binary_split_cancellable(digits, num_threads, &stop, &ComputeOptions::default())
+}
+
+// 可取消的二进分割计算
+// 各线程在进入每个子区间前检查 stop，被置位时放弃已算出的部分，不再合并、开方和除法
+pub fn compute_pi_binary_split_cancellable(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    log::info!("使用二进分割的 Chudnovsky 算法、{} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    
+    if stop.load(Ordering::SeqCst) {
+        return Err(ComputeError::Cancelled);
+    }
+    
+    let start = Instant::now();
+    let mut workers = SpawnedWorkers::new(num_threads, stop, start);
+    let (value, terms, precision) = binary_split_pi(digits, &mut workers, options)?;
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value,
+        digits,
+        terms,
+        precision,
+        algorithm: Algorithm::ChudnovskyBinarySplit,
+        elapsed,
+        throughput: Vec::new(),
+    })
+}
+
+// 由位数确定精度后用二进分割计算 Chudnovsky 级数，返回 (π, 累加项数, 二进制精度)
+// compute_pi_binary_split_cancellable 与 PiEngine 共用，只有分派二进分割任务的方式不同
+pub(crate) fn binary_split_pi<D: SplitDispatch>(
+    digits: usize,
+    dispatch: &mut D,
+    options: &ComputeOptions,
+) -> Result<(Float, usize, u32), ComputeError> {
+    let precision = options.precision_for(digits, CHUDNOVSKY_GUARD_BITS)?;
+    let terms_needed = chudnovsky_terms(digits)?;
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要计算 {} 项...", terms_needed);
+    
+    let total = dispatch.split(terms_needed as u64, options)?;
+    let value = pi_from_split(total, precision)?;
+    options.check_precision(precision, digits);
+    
+    Ok((value, terms_needed, precision))
+}
+
+// 二进分割任务的分派方式：SpawnedWorkers 每次新建工作线程，PiEngine 交给常驻线程
+// 三元组是精确整数，与区间如何划分无关，两者得到逐位相同的 π
+pub(crate) trait SplitDispatch {
+    // [0, terms) 的三元组
+    fn split(&mut self, terms: u64, options: &ComputeOptions) -> Result<SplitTriple, ComputeError>;
+}
+
+impl SplitDispatch for SpawnedWorkers<'_> {
+    // 每个线程负责连续的一段区间，按区间顺序合并
+    fn split(&mut self, terms: u64, options: &ComputeOptions) -> Result<SplitTriple, ComputeError> {
+        let stop = self.stop;
+        let num_threads = self.num_threads.max(1) as u64;
+        let terms_per_thread = terms.div_ceil(num_threads);
+        let stack_size = worker_stack_size(terms_per_thread);
+        let pin_threads = options.pin_threads;
+        let handles = (0..num_threads)
+            .map(|index| {
+                let a = (index * terms_per_thread).min(terms);
+                let b = ((index + 1) * terms_per_thread).min(terms);
+                let stop = Arc::clone(stop);
+                spawn_worker(stack_size, move || {
+                    pin_worker(index as usize, pin_threads);
+                    binary_split_cancellable(a, b, &stop)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        // 被取消的线程返回 None；取消后仍等待其余线程退出，但不再合并
+        let mut total = SplitTriple::identity();
+        for handle in handles {
+            let part = handle.join().map_err(|_| ComputeError::WorkerPanicked)?;
+            let Some(part) = part else { continue };
+            if !stop.load(Ordering::Relaxed) {
+                total = SplitTriple::merge(&total, &part, true);
+            }
+        }
+        
+        if stop.load(Ordering::SeqCst) {
+            log::warn!("计算已取消");
+            return Err(ComputeError::Cancelled);
+        }
+        Ok(total)
+    }
+}
+
+// 由 [0, n) 的三元组得到 π：级数和为 T / Q，π = 426880 * sqrt(10005) / (T / Q)
+// 消耗三元组，各整数转换为浮点数后立即释放，开方、除法和之后提取数字时不再占用它们的内存
+pub(crate) fn pi_from_split(triple: SplitTriple, precision: u32) -> Result<Float, ComputeError> {
+    let SplitTriple { p, q, t } = triple;
+    drop(p);
+    
+    let mut series_sum = Float::with_val(precision, &t);
+    drop(t);
+    series_sum /= &q;
+    drop(q);
+    
+    Ok(inv_pi_from_sum(series_sum, precision)?.recip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::pi_string;
+    use crate::reference::{load_reference_chunk, pi_known_digits};
+    use crate::throughput::SAMPLE_INTERVAL;
+    
+    // 逐项求和在最后一个工作线程结束时立即返回，短计算不会被进度采样间隔拖慢
+    #[test]
+    fn short_computation_returns_before_first_sample() {
+        let start = Instant::now();
+        let result = compute_pi_chudnovsky(2000, 2, &ComputeOptions::default()).unwrap();
+        assert!(start.elapsed() < SAMPLE_INTERVAL, "耗时 {:?}", start.elapsed());
+        assert!(pi_string(&result.value, 2000).starts_with(pi_known_digits()));
+    }
+    
+    // 未取消时与 binary_split 结果相同；stop 已置位时不计算任何区间
+    #[test]
+    fn cancellable_split_stops_when_asked() {
+        let stop = AtomicBool::new(false);
+        assert_eq!(binary_split_cancellable(0, 300, &stop), Some(binary_split(0, 300, false)));
+        
+        stop.store(true, Ordering::SeqCst);
+        assert_eq!(binary_split_cancellable(0, 300, &stop), None);
+    }
+    
+    #[test]
+    fn parallel_merge_matches_serial_merge() {
+        assert_eq!(binary_split(0, 300, true), binary_split(0, 300, false));
+    }
+    
+    // 小项数时逐项累加作为参照：T / Q 必须等于前 n 项之和，任意位置切分后并行合并的结果都相同
+    #[test]
+    fn merge_matches_serial_sum_for_small_term_counts() {
+        let precision = 2048;
+        let mut calculator = ChudnovskyCalculator::new(precision);
+        let mut serial_sum = Float::with_val(precision, 0);
+        
+        for n in 1..=40u64 {
+            serial_sum += calculator.compute_term(n as usize - 1);
+            
+            let whole = binary_split(0, n, false);
+            let mut split_sum = Float::with_val(precision, &whole.t);
+            split_sum /= &whole.q;
+            let error = Float::with_val(precision, &split_sum - &serial_sum).abs();
+            let tolerance = Float::with_val(precision, &serial_sum >> (precision - 16));
+            assert!(error < tolerance, "前 {} 项之和不一致", n);
+            
+            for m in 0..=n {
+                let left = binary_split(0, m, false);
+                let right = binary_split(m, n, false);
+                let merged = SplitTriple::merge(&left, &right, true);
+                assert_eq!(merged, whole, "在第 {} 项切分 {} 项时不一致", m, n);
+            }
+        }
+    }
+    
+    #[test]
+    fn binary_split_matches_term_by_term_sum() {
+        let series = compute_pi_chudnovsky(1000, 2, &ComputeOptions::default()).unwrap();
+        let split = compute_pi_binary_split(1000, 4).unwrap();
+        let digits = |pi: &Float| pi.to_string_radix(10, Some(1000));
+        assert_eq!(digits(&split.value), digits(&series.value));
+    }
+    
+    // 递推 100 次得到的第 100 项与直接由阶乘计算的结果只差几位舍入误差
+    #[test]
+    fn iterative_term_matches_direct_term() {
+        let precision = 4096;
+        let iterative = ChudnovskyIter::new(precision).nth(100).unwrap();
+        let direct = ChudnovskyCalculator::new(precision).compute_term(100).clone();
+        
+        let mut relative_error = Float::with_val(precision, &iterative - &direct);
+        relative_error /= &direct;
+        let tolerance = Float::with_val(precision, 1) >> (precision - 10);
+        assert!(relative_error.abs() <= tolerance);
+        
+        let mut iter = ChudnovskyIter::new(precision);
+        let mut calculator = ChudnovskyCalculator::new(precision);
+        for k in 0..3 {
+            let term = iter.next().unwrap();
+            let expected = calculator.compute_term(k);
+            let error = Float::with_val(precision, &term - expected).abs();
+            assert!(error <= Float::with_val(precision, expected.abs_ref()) >> (precision - 4));
+        }
+    }
+    
+    // 16 项共 16 个叶子和 15 个合并节点，根节点与 binary_split 的结果一致
+    #[test]
+    fn dump_of_sixteen_terms_has_every_node() {
+        let mut out = Vec::new();
+        assert_eq!(dump_triples(16, &mut out).unwrap(), 31);
+        
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 31);
+        assert_eq!(lines.iter().filter(|line| line.starts_with("leaf ")).count(), 16);
+        
+        let root = binary_split(0, 16, false);
+        assert_eq!(lines[30], format!("merge 0 16 {} {} {}", root.p, root.q, root.t));
+        assert!(dump_triples(MAX_DUMP_TRIPLES_TERMS + 1, &mut Vec::new()).is_err());
+    }
+    
+    // 每层约 48 KB 的递归 64 层需要 3 MB 以上，超过默认的 2 MB 线程栈，按项数估算的栈可以容纳
+    #[test]
+    fn sized_stack_fits_deep_recursion() {
+        fn recurse(depth: usize) -> u64 {
+            let frame = std::hint::black_box([depth as u8; 48 * 1024]);
+            if depth == 0 {
+                0
+            } else {
+                u64::from(frame[frame.len() - 1]) + recurse(depth - 1)
+            }
+        }
+        
+        let stack_size = worker_stack_size(u64::MAX);
+        assert!(stack_size > 64 * 48 * 1024 + MIN_STACK_SIZE / 2);
+        let sum = spawn_worker(stack_size, || recurse(64)).unwrap().join().unwrap();
+        assert_eq!(sum, (1..=64).sum::<u64>());
+        
+        let split = spawn_worker(worker_stack_size(300), || binary_split(0, 300, false))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(split, binary_split(0, 300, false));
+    }
+    
+    // 乱序、重复和跳跃的 k 都与每项使用新计算器的结果逐位相同
+    #[test]
+    fn out_of_order_terms_match_fresh_calculator() {
+        let precision = 2048;
+        for &strategy in FactorialStrategy::all() {
+            let mut calculator = ChudnovskyCalculator::with_strategy(precision, strategy);
+            for k in [5, 2, 3, 3, 9, 0, 1, 2, 40, 17] {
+                let fresh = ChudnovskyCalculator::new(precision).compute_term(k).clone();
+                assert_eq!(*calculator.compute_term(k), fresh, "{} 策略第 {} 项", strategy.name(), k);
+            }
+        }
+    }
+    
+    // n 项恰好覆盖 floor(n * 14.18...) 位，再多 1 位就需要第 n + 1 项；另外多算的 1 项是安全余量
+    #[test]
+    fn term_count_steps_at_digits_per_term_boundary() {
+        assert_eq!(chudnovsky_terms(0).unwrap(), 1);
+        for n in [1, 70, 1000, 100_000] {
+            let covered = (n as f64 * CHUDNOVSKY_DIGITS_PER_TERM).floor() as usize;
+            assert_eq!(chudnovsky_terms(covered).unwrap(), n + 1);
+            assert_eq!(chudnovsky_terms(covered + 1).unwrap(), n + 2);
+        }
+    }
+    
+    // Q = Π k^3 * 640320^3 / 24 (k = 1..n-1)，位数由各因子的 log2 之和确定
+    #[test]
+    fn bit_lengths_at_1000_digits() {
+        let stats = binary_split_bit_lengths(1000).unwrap();
+        assert_eq!(stats.terms, chudnovsky_terms(1000).unwrap() as u64);
+        
+        let expected: f64 = (1..stats.terms)
+            .map(|k| (k.pow(3) as f64 * C3_OVER_24 as f64).log2())
+            .sum();
+        assert!((f64::from(stats.q_bits) - expected).abs() <= 2.0, "Q 有 {} 位", stats.q_bits);
+        assert!(stats.p_bits < stats.q_bits && stats.q_bits < stats.t_bits);
+        
+        assert_eq!(stats.per_depth[0], stats.t_bits);
+        assert_eq!(stats.peak, stats.t_bits);
+        assert!(stats.per_depth.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+    
+    // debug 构建中会经过 check_chudnovsky_sum，级数和略小于 13591409 也不能触发断言
+    #[test]
+    fn small_digit_count_passes_sum_invariant() {
+        let result = compute_pi_chudnovsky(100, 2, &ComputeOptions::default()).unwrap();
+        assert_eq!(pi_string(&result.value, 100), pi_known_digits());
+    }
+    
+    #[test]
+    fn reciprocal_of_inv_pi_matches_pi() {
+        let inv_pi = compute_inv_pi(1000, 4).unwrap();
+        let pi = compute_pi_chudnovsky(1000, 4, &ComputeOptions::default()).unwrap().value;
+        assert_eq!(pi_string(&inv_pi.recip(), 1000), pi_string(&pi, 1000));
+    }
+    
+    #[test]
+    fn zero_threads_run_one_worker() {
+        let result = compute_pi_chudnovsky(100, 0, &ComputeOptions::default()).unwrap();
+        assert_eq!(pi_string(&result.value, 100), pi_known_digits());
+    }
+    
+    // 阶乘策略只影响耗时：16 项中每一项都逐位相同，完整结果也相同
+    #[test]
+    fn factorial_strategies_give_identical_terms() {
+        let precision = 2048;
+        let terms = |strategy: FactorialStrategy| {
+            let mut calculator = ChudnovskyCalculator::with_strategy(precision, strategy);
+            (0..16).map(|k| calculator.compute_term(k).clone()).collect::<Vec<_>>()
+        };
+        let expected = terms(FactorialStrategy::Incremental);
+        
+        let reference = compute_pi_chudnovsky(200, 1, &ComputeOptions::default()).unwrap().value;
+        for &strategy in FactorialStrategy::all() {
+            assert_eq!(terms(strategy), expected, "{:?}", strategy);
+            let options = ComputeOptions { factorial_strategy: strategy, ..ComputeOptions::default() };
+            assert_eq!(compute_pi_chudnovsky(200, 1, &options).unwrap().value, reference);
+        }
+    }
+    
+    // 二进分割不计算阶乘，[0, 16) 的三元组与各阶乘策略逐项得到的精确分数一致：
+    // T / Q 等于 16 项之和，P / Q 乘以 (13591409 + 545140134k) 等于第 15 项
+    #[test]
+    fn factorial_strategies_match_split_triples() {
+        let split = binary_split(0, 16, false);
+        let last_coefficient = 13591409 + 545140134 * 15u64;
+        
+        for &strategy in FactorialStrategy::all() {
+            let mut calculator = ChudnovskyCalculator::with_strategy(64, strategy);
+            // 已累加各项之和的精确分数 sum_num / sum_den
+            let (mut sum_num, mut sum_den) = (Integer::new(), Integer::from(1));
+            for k in 0..16 {
+                calculator.compute_term(k);
+                let (num, den) = (&calculator.numerator, &calculator.denominator);
+                sum_num = sum_num * den + Integer::from(num * &sum_den);
+                sum_den *= den;
+            }
+            
+            let t_side = Integer::from(&split.t * &sum_den);
+            assert_eq!(t_side, Integer::from(&sum_num * &split.q), "{:?}", strategy);
+            let p_side = split.p.clone() * last_coefficient * &calculator.denominator;
+            assert_eq!(p_side, Integer::from(&calculator.numerator * &split.q), "{:?}", strategy);
+        }
+    }
+    
+    // 超出 32 位 usize 的位数（2^34）在计算前就返回错误，不会在项数或 6k 的计算中回绕
+    #[test]
+    fn huge_digit_counts_return_errors() {
+        assert!(matches!(chudnovsky_terms(usize::MAX), Err(ComputeError::TooManyDigits { .. })));
+        assert!(chudnovsky_terms(max_chudnovsky_digits() / 2).is_ok());
+        
+        // 32 位平台上 2^34 本身放不进 usize，由 usize::MAX 的情形覆盖
+        if let Ok(digits) = usize::try_from(1u64 << 34) {
+            assert!(matches!(chudnovsky_terms(digits), Err(ComputeError::TooManyDigits { .. })));
+            let options = ComputeOptions::default();
+            assert!(matches!(
+                compute_pi_chudnovsky(digits, 1, &options),
+                Err(ComputeError::PrecisionTooLarge { .. })
+            ));
+        }
+    }
+    
+    // 结果记录实际累加的项数和使用的精度：每项约 14.18 位，项数至少为 digits / 14
+    #[test]
+    fn result_records_terms_and_precision() {
+        let options = ComputeOptions::default();
+        for digits in [1000, 10_000] {
+            let result = compute_pi_chudnovsky(digits, 2, &options).unwrap();
+            assert!(result.terms >= digits / 14, "{} 位只累加了 {} 项", digits, result.terms);
+            assert_eq!(result.terms, chudnovsky_terms(digits).unwrap());
+            assert_eq!(result.precision, checked_precision(digits, CHUDNOVSKY_GUARD_BITS).unwrap());
+            assert_eq!(result.algorithm, Algorithm::Chudnovsky);
+        }
+    }
+    
+    // 先开方再乘 426880 要舍入两次；对精确整数开方只舍入一次，与高精度参考值的误差不会更大，
+    // 多数精度下更小；由它得到的 1000 位 π 最后几位也与参考数据一致
+    #[test]
+    fn exact_numerator_is_closer_to_reference() {
+        let mut strictly_closer = 0;
+        for precision in (64..=128).chain([1000, 3322]) {
+            let reference = chudnovsky_denominator(precision + 256);
+            let exact = chudnovsky_denominator(precision);
+            let mut rounded = Float::with_val(precision, 10005).sqrt();
+            rounded *= 426880;
+            
+            let exact_error = Float::with_val(precision + 256, &exact - &reference).abs();
+            let rounded_error = Float::with_val(precision + 256, &rounded - &reference).abs();
+            assert!(exact_error <= rounded_error, "精度 {}", precision);
+            if exact_error < rounded_error {
+                strictly_closer += 1;
+            }
+        }
+        assert!(strictly_closer > 0);
+        
+        let pi = compute_pi_chudnovsky(1000, 2, &ComputeOptions::default()).unwrap().value;
+        let last = &pi_string(&pi, 1000)[992..];
+        assert_eq!(last.as_bytes(), load_reference_chunk(990, 10).unwrap());
+    }
+    
+    // 每 5 项正确位数约增加 5 × 14.18 ≈ 71 位，且从不减少，全部项累加后达到请求的位数
+    #[test]
+    fn convergence_increases_monotonically() {
+        let steps = chudnovsky_convergence(1000, 5).unwrap();
+        assert_eq!(steps.len(), 15);
+        assert_eq!(steps.last().unwrap().terms, 72);
+        assert_eq!(steps.last().unwrap().correct_digits, 1000);
+        
+        for pair in steps.windows(2) {
+            assert!(pair[1].correct_digits >= pair[0].correct_digits, "{:?}", pair);
+        }
+        
+        // 最后一组不足 5 项且受位数上限截断，不参与比较
+        for pair in steps[..steps.len() - 1].windows(2) {
+            let gain = pair[1].correct_digits - pair[0].correct_digits;
+            assert!((60..=80).contains(&gain), "{} 项到 {} 项增加 {} 位", pair[0].terms, pair[1].terms, gain);
+        }
+    }
+}
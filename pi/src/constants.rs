@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::thread;
+use rug::float::{Constant as MpfrConstant, Round};
+use rug::Float;
+
+use crate::{compute_pi_optimized, default_threads, required_precision, ComputeError};
+
+// 2π、π/2、π/4 直接由 π 的级数和得到
+// 乘除 2 的幂只改变二进制指数，不引入额外舍入，结果与 π 同样精确
+
+// τ = 2π
+pub fn compute_tau(digits: usize) -> Result<Float, ComputeError> {
+    let mut pi = compute_pi_optimized(digits, default_threads())?.value;
+    pi <<= 1u32;
+    Ok(pi)
+}
+
+// π/2
+pub fn compute_half_pi(digits: usize) -> Result<Float, ComputeError> {
+    let mut pi = compute_pi_optimized(digits, default_threads())?.value;
+    pi >>= 1u32;
+    Ok(pi)
+}
+
+// π/4
+pub fn compute_quarter_pi(digits: usize) -> Result<Float, ComputeError> {
+    let mut pi = compute_pi_optimized(digits, default_threads())?.value;
+    pi >>= 2u32;
+    Ok(pi)
+}
+
+// 可一次计算的数学常数
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Constant {
+    Pi,
+    E,
+    Sqrt2,
+    Ln2,
+}
+
+// e、√2、ln 2 在目标精度之上额外使用的二进制位，最后只舍入一次
+const CONSTANT_GUARD_BITS: u32 = 16;
+
+// 一次计算多个常数，共用同一个精度设置
+// 每次调用为每个常数新开一个作用域线程：π 在其中再使用 threads 个线程并行求和，其余常数各占一个线程
+pub fn compute_constants(
+    which: &[Constant],
+    digits: usize,
+    threads: usize,
+) -> Result<HashMap<Constant, Float>, ComputeError> {
+    let precision = required_precision(digits);
+    let working_precision = precision.saturating_add(CONSTANT_GUARD_BITS);
+    let rounded = move |mut value: Float| -> Result<Float, ComputeError> {
+        value.set_prec_round(precision, Round::Nearest);
+        Ok(value)
+    };
+    
+    thread::scope(|scope| {
+        let handles: Vec<_> = which
+            .iter()
+            .copied()
+            .map(|constant| {
+                let handle = scope.spawn(move || match constant {
+                    Constant::Pi => compute_pi_optimized(digits, threads).map(|result| result.value),
+                    Constant::E => rounded(Float::with_val(working_precision, 1).exp()),
+                    Constant::Sqrt2 => rounded(Float::with_val(working_precision, 2).sqrt()),
+                    Constant::Ln2 => rounded(Float::with_val(working_precision, MpfrConstant::Log2)),
+                });
+                (constant, handle)
+            })
+            .collect();
+        
+        handles
+            .into_iter()
+            .map(|(constant, handle)| -> Result<_, ComputeError> {
+                let value = handle.join().map_err(|_| ComputeError::WorkerPanicked)??;
+                Ok((constant, value))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_reference_chunk, pi_string};
+    
+    // 与精度高得多的 MPFR π 乘以相应倍数后截断的结果逐位一致，包括最后几位
+    #[test]
+    fn tau_and_fractions_match_scaled_pi() {
+        let digits = 1000;
+        let reference = Float::with_val(required_precision(digits) + 256, MpfrConstant::Pi);
+        let prec = reference.prec();
+        let cases = [
+            (compute_tau(digits).unwrap(), Float::with_val(prec, &reference * 2u32), "6.28318530"),
+            (compute_half_pi(digits).unwrap(), Float::with_val(prec, &reference / 2u32), "1.57079632"),
+            (compute_quarter_pi(digits).unwrap(), Float::with_val(prec, &reference / 4u32), "0.78539816"),
+        ];
+        
+        for (value, expected, prefix) in cases {
+            let value_str = pi_string(&value, digits);
+            assert!(value_str.starts_with(prefix), "{}", prefix);
+            assert_eq!(value_str, pi_string(&expected, digits));
+        }
+    }
+    
+    // 同时请求 π 和 e，只返回请求的常数，π 与参考数据逐位一致，e 与高精度的参考值一致
+    #[test]
+    fn pi_and_e_together() {
+        let digits = 1000;
+        let constants = compute_constants(&[Constant::Pi, Constant::E], digits, 2).unwrap();
+        assert_eq!(constants.len(), 2);
+        
+        let pi = pi_string(&constants[&Constant::Pi], digits);
+        assert_eq!(pi[2..].as_bytes(), load_reference_chunk(0, digits).unwrap());
+        
+        let e = pi_string(&constants[&Constant::E], digits);
+        let reference = Float::with_val(required_precision(digits) + 256, 1).exp();
+        assert!(e.starts_with("2.71828182845904523536"));
+        assert_eq!(e, pi_string(&reference, digits));
+    }
+    
+    // √2 和 ln 2 的全部 digits 位与高精度的参考值一致，返回值的精度正好是 required_precision
+    #[test]
+    fn sqrt2_and_ln2_match_all_digits() {
+        let digits = 2000;
+        let constants = compute_constants(&[Constant::Sqrt2, Constant::Ln2], digits, 2).unwrap();
+        let prec = required_precision(digits) + 256;
+        let cases = [
+            (Constant::Sqrt2, Float::with_val(prec, 2).sqrt(), "1.41421356"),
+            (Constant::Ln2, Float::with_val(prec, MpfrConstant::Log2), "0.69314718"),
+        ];
+        
+        for (constant, reference, prefix) in cases {
+            let value = &constants[&constant];
+            assert_eq!(value.prec(), required_precision(digits));
+            let value_str = pi_string(value, digits);
+            assert!(value_str.starts_with(prefix), "{:?}", constant);
+            assert_eq!(value_str, pi_string(&reference, digits), "{:?}", constant);
+        }
+    }
+}
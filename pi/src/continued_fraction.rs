@@ -0,0 +1,72 @@
+use rug::{Float, Integer};
+
+use crate::{compute_pi_optimized, default_threads, ComputeError};
+
+// π 的连分数系数 [3; 7, 15, 1, 292, ...]，最多 terms 项
+// 由 precision_digits 位精度的 π 逐项展开；精度耗尽后的系数不可靠，
+// 粗略地说每项平均消耗约 1 位十进制精度，precision_digits 应明显大于 terms
+pub fn pi_continued_fraction(terms: usize, precision_digits: usize) -> Result<Vec<u64>, ComputeError> {
+    let pi = compute_pi_optimized(precision_digits, default_threads())?.value;
+    Ok(continued_fraction(&pi, terms))
+}
+
+// 浮点数的连分数展开
+fn continued_fraction(value: &Float, terms: usize) -> Vec<u64> {
+    let mut coefficients = Vec::with_capacity(terms);
+    let mut x = value.clone();
+    
+    while coefficients.len() < terms {
+        let whole = Float::with_val(x.prec(), x.floor_ref());
+        let Some(a) = whole.to_integer().and_then(|n| n.to_u64()) else {
+            break;
+        };
+        coefficients.push(a);
+        
+        // 剩余部分为零时展开结束
+        x -= &whole;
+        if x.is_zero() {
+            break;
+        }
+        x.recip_mut();
+    }
+    
+    coefficients
+}
+
+// 连分数的各级渐近分数 (分子, 分母)，如 3/1、22/7、333/106、355/113
+// h(n) = a(n)·h(n-1) + h(n-2)，k(n) = a(n)·k(n-1) + k(n-2)
+pub fn convergents(cf: &[u64]) -> Vec<(Integer, Integer)> {
+    let mut result = Vec::with_capacity(cf.len());
+    let (mut h_prev, mut h) = (Integer::from(0), Integer::from(1));
+    let (mut k_prev, mut k) = (Integer::from(1), Integer::from(0));
+    
+    for &a in cf {
+        let h_next = Integer::from(&h * a) + &h_prev;
+        let k_next = Integer::from(&k * a) + &k_prev;
+        h_prev = std::mem::replace(&mut h, h_next);
+        k_prev = std::mem::replace(&mut k, k_next);
+        result.push((h.clone(), k.clone()));
+    }
+    
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 前四项系数为 [3, 7, 15, 1]；3/1 之后的第三个渐近分数是 355/113
+    #[test]
+    fn first_terms_and_convergents() {
+        let cf = pi_continued_fraction(10, 100).unwrap();
+        assert_eq!(cf[..4], [3, 7, 15, 1]);
+        assert_eq!(cf[4], 292);
+        
+        let fractions: Vec<(u64, u64)> = convergents(&cf[..4])
+            .iter()
+            .map(|(h, k)| (h.to_u64().unwrap(), k.to_u64().unwrap()))
+            .collect();
+        assert_eq!(fractions, [(3, 1), (22, 7), (333, 106), (355, 113)]);
+        assert!(convergents(&[]).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use rug::Float;
+
+use crate::affinity::pin_worker;
+use crate::bbp::{
+    bbp_pi, cached_result, collect_chunks, sum_chunks, ChunkQueue, FromPrecision, Progress, SeriesDispatch,
+};
+use crate::bellard::bellard_pi;
+use crate::chudnovsky::{
+    binary_split, binary_split_pi, chudnovsky_pi, worker_stack_size, SplitDispatch, SplitTriple,
+};
+use crate::gauss_legendre::compute_pi_gauss_legendre;
+use crate::{Algorithm, ComputeError, ComputeOptions, ComputeResult, Config};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// 每个工作线程平均分到的任务数，任务更小时负载更均衡
+const JOBS_PER_WORKER: usize = 4;
+
+// 持有一组常驻工作线程的计算引擎，多次计算共用同一组线程，避免每次重新创建
+pub struct PiEngine {
+    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    // 实际启动过的工作线程数
+    spawned: Arc<AtomicUsize>,
+}
+
+impl PiEngine {
+    // 创建 num_threads 个工作线程（至少 1 个）
+    pub fn new(num_threads: usize) -> Self {
+        Self::with_options(num_threads, &ComputeOptions::default())
+    }
+    
+    // 同 new，工作线程按 options 创建，例如绑定到各自的核心
+    // 二进分割任务的项数事先未知，线程栈按最大项数的递归深度分配；无法创建的线程跳过
+    pub fn with_options(num_threads: usize, options: &ComputeOptions) -> Self {
+        let pin_threads = options.pin_threads;
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let stack_size = worker_stack_size(u64::MAX);
+        
+        let workers = (0..num_threads.max(1))
+            .filter_map(|index| {
+                let receiver = Arc::clone(&receiver);
+                let spawned = Arc::clone(&spawned);
+                let worker = thread::Builder::new().stack_size(stack_size).spawn(move || {
+                    spawned.fetch_add(1, Ordering::SeqCst);
+                    pin_worker(index, pin_threads);
+                    
+                    loop {
+                        // 只在取任务时持有锁；发送端关闭后退出
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        match job {
+                            // 单个任务 panic 不影响线程继续处理后续任务
+                            Ok(job) => {
+                                let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                });
+                worker.map_err(|e| log::warn!("无法创建第 {} 个工作线程: {}", index, e)).ok()
+            })
+            .collect();
+        
+        Self { sender: Some(sender), workers, spawned }
+    }
+    
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+    
+    // 引擎创建以来启动过的工作线程数，不随计算次数增加
+    pub fn spawned_threads(&self) -> usize {
+        self.spawned.load(Ordering::SeqCst)
+    }
+    
+    // 按配置的算法和位数计算，线程数由引擎决定
+    // 各算法的计算流程与 compute_pi_with 相同，只是求和任务交给常驻线程，结果逐位相同
+    pub fn compute(&self, config: &Config) -> Result<ComputeResult, ComputeError> {
+        let digits = config.compute_digits();
+        let options = &config.options;
+        let start = Instant::now();
+        let mut pool = self;
+        
+        let (value, terms, precision) = match config.algorithm {
+            Algorithm::Bbp => match cached_result(digits, options) {
+                Some(result) => return Ok(result),
+                None => bbp_pi(digits, &mut pool, options)?,
+            },
+            Algorithm::Bellard => bellard_pi(digits, &mut pool, options)?,
+            Algorithm::Chudnovsky => chudnovsky_pi(digits, &mut pool, options)?,
+            Algorithm::ChudnovskyBinarySplit => binary_split_pi(digits, &mut pool, options)?,
+            // 迭代互相依赖，没有可分给工作线程的任务，在调用线程上计算
+            Algorithm::GaussLegendre => return compute_pi_gauss_legendre(digits, 1, options),
+        };
+        
+        Ok(ComputeResult {
+            value,
+            digits,
+            terms,
+            precision,
+            algorithm: config.algorithm,
+            elapsed: start.elapsed(),
+            throughput: Vec::new(),
+        })
+    }
+}
+
+impl SeriesDispatch for &PiEngine {
+    // 每个工作线程一个任务，各任务依次领取下一块项，与 sum_series 的工作线程做同样的事
+    fn sum<C: FromPrecision<Float>>(
+        &mut self,
+        precision: u32,
+        terms: Range<usize>,
+        options: &ComputeOptions,
+    ) -> Result<Float, ComputeError> {
+        let sender = self.sender.as_ref().ok_or(ComputeError::WorkerPanicked)?;
+        let jobs = self.workers.len().max(1);
+        let early_exit = options.early_exit_for(jobs);
+        let calculator_options = *options;
+        let queue = Arc::new(ChunkQueue::new(terms.clone()));
+        // 引擎的计算不能取消，stop 只用于满足共用的求和接口
+        let stop = Arc::new(AtomicBool::new(false));
+        let (result_sender, result_receiver) = mpsc::channel();
+        
+        for worker in 0..jobs {
+            let queue = Arc::clone(&queue);
+            let stop = Arc::clone(&stop);
+            let result_sender = result_sender.clone();
+            let new_calculator = move || C::with_options(precision, &calculator_options);
+            let job: Job = Box::new(move || {
+                sum_chunks(&queue, worker, precision, early_exit, &stop, &result_sender, new_calculator);
+            });
+            sender.send(job).map_err(|_| ComputeError::WorkerPanicked)?;
+        }
+        drop(result_sender);
+        
+        // 任务 panic 时其发送端被丢弃，累加到的项会少于 terms，collect_chunks 返回 WorkerPanicked
+        let mut progress = Progress::new(terms.end, terms.start, Instant::now(), None);
+        let (sum, _) = collect_chunks(precision, &terms, &result_receiver, &stop, &mut progress)?;
+        Ok(sum)
+    }
+}
+
+impl SplitDispatch for &PiEngine {
+    // 把 [0, terms) 分成若干区间交给工作线程做二进分割，按区间顺序合并各自的三元组
+    fn split(&mut self, terms: u64, _options: &ComputeOptions) -> Result<SplitTriple, ComputeError> {
+        let sender = self.sender.as_ref().ok_or(ComputeError::WorkerPanicked)?;
+        let job_count = (self.workers.len() * JOBS_PER_WORKER).clamp(1, terms.max(1) as usize);
+        let terms_per_job = terms.div_ceil(job_count as u64).max(1);
+        let (result_sender, result_receiver) = mpsc::channel();
+        
+        let mut submitted = 0;
+        for (index, first) in (0..terms).step_by(terms_per_job as usize).enumerate() {
+            let end = (first + terms_per_job).min(terms);
+            let result_sender = result_sender.clone();
+            let job: Job = Box::new(move || {
+                let _ = result_sender.send((index, binary_split(first, end, false)));
+            });
+            sender.send(job).map_err(|_| ComputeError::WorkerPanicked)?;
+            submitted += 1;
+        }
+        drop(result_sender);
+        
+        // 结果到达的顺序不确定，按区间序号放好后依次合并；任务 panic 时收到的结果会少于提交的任务数
+        let mut parts = vec![None; submitted];
+        for _ in 0..submitted {
+            let (index, part) = result_receiver.recv().map_err(|_| ComputeError::WorkerPanicked)?;
+            parts[index] = Some(part);
+        }
+        
+        let mut total = SplitTriple::identity();
+        for part in parts.into_iter().flatten() {
+            total = SplitTriple::merge(&total, &part, true);
+        }
+        Ok(total)
+    }
+}
+
+impl Drop for PiEngine {
+    // 关闭任务通道，等待所有工作线程退出
+    fn drop(&mut self) {
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::{
+        compute_pi_bbp_fixedpoint, compute_pi_bbp_fixedpoint_with, compute_pi_with,
+        load_reference_chunk, pi_string,
+    };
+    
+    fn matches_reference(value: &Float, digits: usize) -> bool {
+        pi_string(value, digits)[2..].as_bytes() == load_reference_chunk(0, digits).unwrap()
+    }
+    
+    // 等待所有工作线程启动，最多 5 秒
+    fn wait_for_workers(engine: &PiEngine) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while engine.spawned_threads() < engine.threads() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+    
+    // 绑定核心时工作线程数仍等于请求的线程数，结果正确，二进分割和定点路径同样如此；
+    // 未启用 core_affinity 特性或平台不支持绑定时同样成立
+    #[test]
+    fn pinned_workers_compute_correctly() {
+        let options = ComputeOptions { pin_threads: true, ..ComputeOptions::default() };
+        let engine = PiEngine::with_options(4, &options);
+        let mut config = Config::new(1000);
+        config.algorithm = Algorithm::Chudnovsky;
+        config.options = options;
+        
+        assert!(matches_reference(&engine.compute(&config).unwrap().value, 1000));
+        wait_for_workers(&engine);
+        assert_eq!(engine.threads(), 4);
+        assert_eq!(engine.spawned_threads(), 4);
+        
+        for algorithm in [Algorithm::Bbp, Algorithm::ChudnovskyBinarySplit] {
+            let result = compute_pi_with(algorithm, 2000, 4, &options).unwrap();
+            assert!(matches_reference(&result.value, 2000), "{}", algorithm);
+        }
+        let fixed = compute_pi_bbp_fixedpoint(1000, 4).unwrap();
+        assert_eq!(compute_pi_bbp_fixedpoint_with(1000, 4, &options).unwrap(), fixed);
+    }
+    
+    // 同一个引擎依次用四种级数算法计算不同的位数，结果都正确，报告的算法与配置一致；
+    // 启动过的工作线程数始终等于创建时的线程数
+    #[test]
+    fn reuses_workers_across_computations() {
+        let engine = PiEngine::new(3);
+        wait_for_workers(&engine);
+        assert_eq!(engine.spawned_threads(), 3);
+        
+        let cases = [
+            (500, Algorithm::Bbp),
+            (2000, Algorithm::Chudnovsky),
+            (1500, Algorithm::Bellard),
+            (3000, Algorithm::ChudnovskyBinarySplit),
+        ];
+        for (digits, algorithm) in cases {
+            let mut config = Config::new(digits);
+            config.algorithm = algorithm;
+            let result = engine.compute(&config).unwrap();
+            assert_eq!(result.algorithm, algorithm);
+            assert!(matches_reference(&result.value, digits), "{} 位，{}", digits, algorithm);
+            assert_eq!(engine.spawned_threads(), 3);
+        }
+        assert_eq!(engine.threads(), 3);
+    }
+    
+    // 引擎与每次新建线程的 compute_pi_with 共用各算法的计算流程，线程数不同时结果也逐位相同；
+    // BBP 同时覆盖内置常数和完整计算两条路径
+    #[test]
+    fn matches_compute_pi_with_bit_for_bit() {
+        let engine = PiEngine::new(3);
+        for &algorithm in Algorithm::all() {
+            for digits in [800, 2500] {
+                let mut config = Config::new(digits);
+                config.algorithm = algorithm;
+                let pooled = engine.compute(&config).unwrap();
+                let direct = compute_pi_with(algorithm, config.compute_digits(), 2, &config.options).unwrap();
+                
+                assert_eq!(pooled.precision, direct.precision, "{} 位，{}", digits, algorithm);
+                assert_eq!(pooled.terms, direct.terms, "{} 位，{}", digits, algorithm);
+                assert_eq!(pooled.value.prec(), direct.value.prec(), "{} 位，{}", digits, algorithm);
+                assert_eq!(pooled.value, direct.value, "{} 位，{}", digits, algorithm);
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bbp::bbp_terms;
+use crate::bellard::bellard_terms;
+use crate::chudnovsky::{chudnovsky_terms, CHUDNOVSKY_GUARD_BITS};
+use crate::gauss_legendre::{gauss_legendre_iterations, GAUSS_LEGENDRE_GUARD_BITS};
+use crate::{
+    checked_precision, Algorithm, ComputeError, BBP_DIGITS_PER_TERM, BELLARD_DIGITS_PER_TERM,
+    CHUDNOVSKY_DIGITS_PER_TERM,
+};
+
+// 说明计算 digits 位时使用的公式、项数、精度和每项收敛速度，不做实际计算
+// 位数超出算法或 MPFR 的范围时返回与计算时相同的错误
+pub fn explain(algorithm: Algorithm, digits: usize) -> Result<String, ComputeError> {
+    let (formula, precision, terms, convergence) = match algorithm {
+        Algorithm::Bbp => {
+            let precision = checked_precision(digits, 0)?;
+            (
+                "π = Σ_{k≥0} 16^-k · (4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6))",
+                precision,
+                bbp_terms(precision),
+                per_term(BBP_DIGITS_PER_TERM),
+            )
+        }
+        Algorithm::Bellard => {
+            let precision = checked_precision(digits, 0)?;
+            (
+                "π = 1/64 · Σ_{k≥0} (-1)^k/1024^k · (-32/(4k+1) - 1/(4k+3) + 256/(10k+1) \
+                 - 64/(10k+3) - 4/(10k+5) - 4/(10k+7) + 1/(10k+9))",
+                precision,
+                bellard_terms(precision),
+                per_term(BELLARD_DIGITS_PER_TERM),
+            )
+        }
+        // 二进分割与逐项计算使用同一级数，只是求和方式不同
+        Algorithm::Chudnovsky | Algorithm::ChudnovskyBinarySplit => {
+            let precision = checked_precision(digits, CHUDNOVSKY_GUARD_BITS)?;
+            (
+                "1/π = 12 · Σ_{k≥0} (-1)^k (6k)! (13591409 + 545140134k) \
+                 / ((3k)! (k!)^3 640320^(3k+3/2))",
+                precision,
+                chudnovsky_terms(digits)?,
+                per_term(CHUDNOVSKY_DIGITS_PER_TERM),
+            )
+        }
+        // 项数为迭代次数
+        Algorithm::GaussLegendre => {
+            let precision = checked_precision(digits, GAUSS_LEGENDRE_GUARD_BITS)?;
+            (
+                "a₀ = 1, b₀ = 1/√2, t₀ = 1/4, p₀ = 1; a' = (a+b)/2, b' = √(ab), \
+                 t' = t - p(a-a')², p' = 2p; π ≈ (a+b)² / (4t)",
+                precision,
+                gauss_legendre_iterations(digits),
+                "每次迭代正确位数约翻倍".to_string(),
+            )
+        }
+    };
+    
+    // 精度对应的十进制位数
+    let decimal_digits = (precision as f64 * std::f64::consts::LOG10_2).floor() as u64;
+    
+    let mut text = String::new();
+    text.push_str(&format!("算法: {}\n", algorithm));
+    text.push_str(&format!("公式: {}\n", formula));
+    text.push_str(&format!("目标位数: {} 位小数\n", digits));
+    text.push_str(&format!("二进制精度: {} 位（约 {} 位十进制）\n", precision, decimal_digits));
+    text.push_str(&format!("每项收敛: {}\n", convergence));
+    text.push_str(&format!("需要项数: {}\n", terms));
+    Ok(text)
+}
+
+// 级数每项的收敛速度
+fn per_term(digits_per_term: f64) -> String {
+    format!("约 {:.2} 位十进制", digits_per_term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 1000 位需要 ⌈1000 / 14.18⌉ + 1 = 72 项
+    #[test]
+    fn chudnovsky_explain_mentions_convergence_and_terms() {
+        let text = explain(Algorithm::Chudnovsky, 1000).unwrap();
+        assert!(text.contains("14.18 位十进制"), "{}", text);
+        assert!(text.contains("需要项数: 72\n"), "{}", text);
+        assert_eq!(chudnovsky_terms(1000).unwrap(), 72);
+        assert!(text.contains("目标位数: 1000 位小数"), "{}", text);
+    }
+}
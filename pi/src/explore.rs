@@ -0,0 +1,55 @@
+// 小数部分（小数点之后的字符）
+fn fraction_part(pi_str: &str) -> &str {
+    match pi_str.find('.') {
+        Some(dot) => &pi_str[dot + 1..],
+        None => "",
+    }
+}
+
+// 返回小数点后第 center 位（从 0 开始）附近 radius 位范围内的数字
+// 范围为 [center - radius, center + radius)，超出部分被截断
+pub fn window(pi_str: &str, center: usize, radius: usize) -> &str {
+    let fraction = fraction_part(pi_str);
+    
+    let start = center.saturating_sub(radius).min(fraction.len());
+    let end = center.saturating_add(radius).min(fraction.len());
+    &fraction[start..end]
+}
+
+// 在小数部分中查找数字串第一次出现的位置（小数点后从 0 开始）
+// 空串或在已计算范围内找不到时返回 None
+pub fn find_sequence(pi_str: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    fraction_part(pi_str).find(needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_pi, pi_string, Algorithm};
+    
+    // 以小数点后第 500 位为中心、半径 10 的窗口，即小数第 491 到 510 位
+    #[test]
+    fn window_around_position_500() {
+        let pi_str = pi_string(&compute_pi(Algorithm::Chudnovsky, 1000, 2).unwrap().value, 1000);
+        assert_eq!(window(&pi_str, 500, 10), "83011949129833673362");
+        
+        // 靠近两端时截断
+        assert_eq!(window(&pi_str, 3, 10), "1415926535897");
+        assert_eq!(window(&pi_str, 995, 10).len(), 15);
+        assert_eq!(window(&pi_str, 5000, 10), "");
+    }
+    
+    // "14159" 从小数点后第 0 位开始；1000 位内找不到很长的数字串，空串返回 None
+    #[test]
+    fn find_sequence_in_computed_digits() {
+        let pi_str = pi_string(&compute_pi(Algorithm::Chudnovsky, 1000, 2).unwrap().value, 1000);
+        assert_eq!(find_sequence(&pi_str, "14159"), Some(0));
+        assert_eq!(find_sequence(&pi_str, "26535"), Some(5));
+        assert_eq!(find_sequence(&pi_str, "999999"), Some(761));
+        assert_eq!(find_sequence(&pi_str, "0123456789012345"), None);
+        assert_eq!(find_sequence(&pi_str, ""), None);
+    }
+}
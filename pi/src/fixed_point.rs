@@ -0,0 +1,150 @@
+use rug::Integer;
+
+use crate::affinity::pin_worker;
+use crate::bbp::bbp_terms;
+use crate::chudnovsky::{spawn_worker, worker_stack_size};
+use crate::{required_precision, ComputeError, ComputeOptions};
+
+// digits 位小数对应的定点精度，即结果中二进制小数的位数
+pub fn fixed_point_precision(digits: usize) -> u32 {
+    required_precision(digits)
+}
+
+// 定点 BBP：每项都是乘以 2^precision 后截断的整数，累加时没有浮点规格化和舍入模式
+// 返回 π * 2^precision 的近似整数，precision 由 fixed_point_precision(digits) 给出
+// 每项截断误差不超过 4 个单位，required_precision 的余量足以吸收
+// 无法创建工作线程时返回 Io，工作线程 panic 时返回 WorkerPanicked
+pub fn compute_pi_bbp_fixedpoint(digits: usize, num_threads: usize) -> Result<Integer, ComputeError> {
+    compute_pi_bbp_fixedpoint_with(digits, num_threads, &ComputeOptions::default())
+}
+
+// 同 compute_pi_bbp_fixedpoint，按 options 调整计算过程，例如把工作线程绑定到各自的核心
+pub fn compute_pi_bbp_fixedpoint_with(
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<Integer, ComputeError> {
+    let precision = fixed_point_precision(digits);
+    let terms = bbp_terms(precision);
+    let num_threads = num_threads.max(1);
+    let terms_per_thread = terms.div_ceil(num_threads);
+    let pin_threads = options.pin_threads;
+    let stack_size = worker_stack_size(terms_per_thread as u64);
+    
+    log::info!("使用定点 BBP、{} 个线程计算 π 到小数点后 {} 位...", num_threads, digits);
+    log::info!("精度: {} 位二进制，需要计算 {} 项", precision, terms);
+    
+    // 每个线程负责连续的一段项，整数加法满足结合律，结果与划分方式无关
+    let handles = (0..num_threads)
+        .map(|index| {
+            let first = (index * terms_per_thread).min(terms);
+            let end = (first + terms_per_thread).min(terms);
+            spawn_worker(stack_size, move || {
+                pin_worker(index, pin_threads);
+                let mut sum = Integer::new();
+                for k in first..end {
+                    add_fixed_point_term(&mut sum, k, precision);
+                }
+                sum
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut sum = Integer::new();
+    for handle in handles {
+        sum += handle.join().map_err(|_| ComputeError::WorkerPanicked)?;
+    }
+    Ok(sum)
+}
+
+// 把第 k 项乘以 2^precision 后截断累加到 sum:
+// (4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6)) * 2^(precision - 4k)
+// 4k 超过 precision 时该项不足 1 个单位，直接忽略
+fn add_fixed_point_term(sum: &mut Integer, k: usize, precision: u32) {
+    let shift = match (precision as usize).checked_sub(4 * k) {
+        Some(shift) => shift as u32,
+        None => return,
+    };
+    let eight_k = 8 * k as u64;
+    let one = Integer::from(1) << shift;
+    
+    *sum += Integer::from(&one << 2u32) / (eight_k + 1);
+    *sum -= Integer::from(&one << 1u32) / (eight_k + 4);
+    *sum -= Integer::from(&one / (eight_k + 5));
+    *sum -= one / (eight_k + 6);
+}
+
+// 把 compute_pi_bbp_fixedpoint(digits, _) 的结果写成 "3.14159..." 形式
+// digits 必须与计算时一致，小数部分截断到 digits 位
+pub fn fixed_point_to_decimal(value: &Integer, digits: usize) -> String {
+    let digit_str = scaled_to_decimal(value, digits).to_string();
+    if digits == 0 {
+        return digit_str;
+    }
+    
+    // 不足 digits + 1 位时在前面补零，保证整数部分至少有一位
+    let padded = format!("{:0>width$}", digit_str, width = digits + 1);
+    let (integer_part, fraction) = padded.split_at(padded.len() - digits);
+    format!("{}.{}", integer_part, fraction)
+}
+
+// 把 π * 2^precision 换算为 floor(π * 10^digits)，即 π 的前 digits + 1 位数字组成的整数
+fn scaled_to_decimal(value: &Integer, digits: usize) -> Integer {
+    let precision = fixed_point_precision(digits);
+    let power_of_ten = Integer::from(Integer::u_pow_u(10, digits as u32));
+    Integer::from(value * &power_of_ten) >> precision
+}
+
+// floor(π * 10^digits) mod p，经由定点路径计算，与小数字符串无关
+// 不同实现算到相同位数时余数应当相同，可以作为快速的完整性校验；p 为 0 时返回错误
+pub fn pi_scaled_mod(digits: usize, p: u64, num_threads: usize) -> Result<u64, ComputeError> {
+    if p == 0 {
+        return Err(ComputeError::ZeroModulus);
+    }
+    
+    let value = compute_pi_bbp_fixedpoint(digits, num_threads)?;
+    let residue = scaled_to_decimal(&value, digits) % p;
+    Ok(residue.to_u64().expect("余数小于 p，一定能放进 u64"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use crate::bbp::compute_pi_series;
+    use crate::{compute_pi, pi_string, Algorithm, ComputeOptions};
+    
+    // floor(π * 10^digits) mod p，由小数字符串得到
+    fn string_residue(algorithm: Algorithm, digits: usize, p: u64) -> u64 {
+        let pi = compute_pi(algorithm, digits, 2).unwrap().value;
+        let scaled: String = pi_string(&pi, digits).chars().filter(|c| *c != '.').collect();
+        let residue = scaled.parse::<Integer>().unwrap() % p;
+        residue.to_u64().unwrap()
+    }
+    
+    // 定点结果写成 1000 位小数后与浮点 BBP 的结果逐位相同；浮点路径不经过内置常数
+    #[test]
+    fn fixed_point_matches_float_path() {
+        let digits = 1000;
+        let fixed = fixed_point_to_decimal(&compute_pi_bbp_fixedpoint(digits, 2).unwrap(), digits);
+        
+        let stop = Arc::new(AtomicBool::new(false));
+        let float = compute_pi_series(digits, 2, &stop, &ComputeOptions::default()).unwrap();
+        assert_eq!(fixed, pi_string(&float.value, digits));
+    }
+    
+    #[test]
+    fn residue_matches_the_decimal_string() {
+        for p in [7, 1_000_000_007, u64::MAX] {
+            let residue = pi_scaled_mod(1000, p, 2).unwrap();
+            assert_eq!(residue, string_residue(Algorithm::Bbp, 1000, p));
+            assert_eq!(residue, string_residue(Algorithm::Chudnovsky, 1000, p));
+        }
+    }
+    
+    #[test]
+    fn zero_modulus_is_an_error() {
+        assert!(matches!(pi_scaled_mod(100, 0, 1), Err(ComputeError::ZeroModulus)));
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use rug::Float;
+
+use crate::{Algorithm, ComputeError, ComputeOptions, ComputeResult};
+
+// 开方和除法的舍入误差随迭代累积，多留的保护位
+pub(crate) const GAUSS_LEGENDRE_GUARD_BITS: u32 = 64;
+
+// 计算 digits 位需要的迭代次数
+// 每次迭代正确位数约翻倍，第 k 次迭代后约有 2.6 × 2^k 位正确，取 ceil(log2(digits)) 次足够
+pub(crate) fn gauss_legendre_iterations(digits: usize) -> usize {
+    let digits = digits.max(2);
+    (usize::BITS - (digits - 1).leading_zeros()) as usize
+}
+
+// Gauss-Legendre 算法（算术几何平均）：
+// a = 1, b = 1/√2, t = 1/4, p = 1
+// 每次迭代 a' = (a + b) / 2, b' = √(ab), t' = t - p(a - a')², p' = 2p
+// π ≈ (a + b)² / (4t)
+// 每次迭代都依赖上一次的结果，只能单线程计算，num_threads 仅用于日志
+pub fn compute_pi_gauss_legendre(
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_gauss_legendre_cancellable(digits, num_threads, &stop, options)
+}
+
+// 可取消的 Gauss-Legendre 计算，stop 被置位后在当前迭代完成后退出
+pub fn compute_pi_gauss_legendre_cancellable(
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    log::info!(
+        "使用 Gauss-Legendre 算法计算 π 到小数点后 {} 位（单线程，忽略线程数 {}）...",
+        digits,
+        num_threads
+    );
+    
+    let start = Instant::now();
+    let precision = options.precision_for(digits, GAUSS_LEGENDRE_GUARD_BITS)?;
+    let iterations = gauss_legendre_iterations(digits);
+    
+    log::info!("精度: {} 位二进制", precision);
+    log::info!("需要迭代 {} 次...", iterations);
+    
+    let mut a = Float::with_val(precision, 1);
+    let mut b = Float::with_val(precision, 2).sqrt().recip();
+    let mut t = Float::with_val(precision, 0.25);
+    let mut p = Float::with_val(precision, 1);
+    
+    for _ in 0..iterations {
+        if stop.load(Ordering::Relaxed) {
+            log::warn!("计算已取消");
+            return Err(ComputeError::Cancelled);
+        }
+        
+        let next_a = Float::with_val(precision, &a + &b) / 2u32;
+        b *= &a;
+        b.sqrt_mut();
+        a -= &next_a;
+        a.square_mut();
+        t -= a * &p;
+        p *= 2u32;
+        a = next_a;
+    }
+    
+    let sum = Float::with_val(precision, &a + &b);
+    let value = sum.square() / (t * 4u32);
+    options.check_precision(precision, digits);
+    
+    let elapsed = start.elapsed();
+    log::info!("计算完成，耗时: {:.2} 秒", elapsed.as_secs_f64());
+    
+    Ok(ComputeResult {
+        value,
+        digits,
+        terms: iterations,
+        precision,
+        algorithm: Algorithm::GaussLegendre,
+        elapsed,
+        throughput: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pi_known_digits, pi_string};
+    
+    // 按 gauss_legendre_iterations 迭代的结果与内置常数一致，从 1 位到 5000 位
+    #[test]
+    fn iterations_reach_requested_digits() {
+        let options = ComputeOptions::default();
+        for digits in [1, 2, 3, 10, 100, 1000, 5000] {
+            let result = compute_pi_gauss_legendre(digits, 1, &options).unwrap();
+            let expected = &pi_known_digits()[..(digits + 2).min(102)];
+            assert!(pi_string(&result.value, digits).starts_with(expected), "{} 位", digits);
+        }
+        assert_eq!(gauss_legendre_iterations(1000), 10);
+    }
+    
+    #[test]
+    fn cancelled_before_start() {
+        let stop = Arc::new(AtomicBool::new(true));
+        let options = ComputeOptions::default();
+        let result = compute_pi_gauss_legendre_cancellable(1000, 1, &stop, &options);
+        assert!(matches!(result, Err(ComputeError::Cancelled)));
+    }
+}
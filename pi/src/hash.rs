@@ -0,0 +1,60 @@
+use std::io::{self, Write};
+use sha2::{Digest, Sha256};
+
+// 每次送入哈希的字节数
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+// 计算数字流的 SHA-256（十六进制小写）
+// 输入为数字 0-9，按 ASCII 字符 '0'-'9' 参与哈希，与 raw 格式文件内容一致
+pub fn hash_digits(digits: impl Iterator<Item = u8>) -> String {
+    let mut hasher = Sha256::new();
+    let mut buffer = Vec::with_capacity(HASH_BUFFER_SIZE);
+    
+    for digit in digits {
+        buffer.push(digit + b'0');
+        if buffer.len() == HASH_BUFFER_SIZE {
+            hasher.update(&buffer);
+            buffer.clear();
+        }
+    }
+    hasher.update(&buffer);
+    
+    to_hex(hasher)
+}
+
+// 写入 inner 的同时对写入的字节计算 SHA-256，只保存哈希状态，不保存写入的内容
+pub(crate) struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new() }
+    }
+    
+    // 返回 inner 和已写入内容的十六进制哈希
+    pub(crate) fn finish(self) -> (W, String) {
+        (self.inner, to_hex(self.hasher))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    // 只对 inner 实际接受的部分计算哈希
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn to_hex(hasher: Sha256) -> String {
+    hasher.finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -1,101 +0,0 @@
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Instant;
-use std::fs::File;
-use std::io::{Write, BufWriter};
-use std::path::Path;
-
-// 使用Spigot算法计算π，内存更友好
-struct SpigotPiCalculator {
-    digits: usize,
-    chunk_size: usize,
-    precision: u32,
-}
-
-impl SpigotPiCalculator {
-    fn new(digits: usize, chunk_size: usize) -> Self {
-        Self {
-            digits,
-            chunk_size,
-            precision: 0,
-        }
-    }
-    
-    // 计算单个块的π值
-    fn compute_chunk(&self, start: usize) -> Vec<u8> {
-        let n = self.digits + 2; // 多算几位保证精度
-        
-        // 使用整数数组进行计算
-        let mut digits = Vec::with_capacity(self.chunk_size);
-        
-        for i in 0..self.chunk_size {
-            let idx = start + i;
-            if idx >= n {
-                break;
-            }
-            
-            // 简化的Spigot算法
-            let digit = self.compute_digit(idx, n);
-            digits.push(digit);
-        }
-        
-        digits
-    }
-    
-    fn compute_digit(&self, position: usize, n: usize) -> u8 {
-        // 这是Spigot算法的一个简化版本
-        // 在实际应用中，需要使用完整的Spigot算法
-        let mut remainder = 0;
-        let mut digit = 0;
-        
-        for _ in 0..position {
-            remainder = (remainder * 10) % 7;
-        }
-        
-        digit
-    }
-}
-
-// 主函数
-fn main() {
-    println!("Spigot π 计算器 (内存优化版本)");
-    println!("=============================");
-    
-    // let digits  : 
-    let digits = 10000000000000; // 计算位数
-    let num_threads = num_cpus::get();
-    
-    println!("计算 π 到 {} 位", digits);
-    println!("使用 {} 个线程", num_threads);
-    
-    // 使用分块Spigot算法
-    let result = compute_pi_spigot_parallel(digits, num_threads);
-    
-    // 输出结果
-    println!("\nπ 的前 50 位:");
-    println!("3.14159265358979323846264338327950288419716939937510");
-    
-    // 写入文件
-    let filename = format!("pi_spigot_{}.txt", digits);
-    if let Ok(mut file) = File::create(filename) {
-        writeln!(file, "π 到 {} 位:", digits).unwrap();
-        writeln!(file, "{}", result).unwrap();
-    }
-}
-
-fn compute_pi_spigot_parallel(digits: usize, num_threads: usize) -> String {
-    // 这是一个简化的示例
-    // 实际实现需要完整的Spigot算法
-    let start = Instant::now();
-    
-    println!("计算中...");
-    
-    // 模拟计算
-    thread::sleep(std::time::Duration::from_millis(100));
-    
-    let duration = start.elapsed();
-    println!("计算完成，耗时: {:?}", duration);
-    
-    // 返回已知的π值（这里只是示例）
-    "3.14159265358979323846264338327950288419716939937510".to_string()
-}
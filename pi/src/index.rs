@@ -0,0 +1,176 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::output::is_ruler_line;
+use crate::verify::HEADER_PREFIX;
+
+// 每隔多少位记录一次字节偏移
+const INDEX_STRIDE: usize = 4096;
+
+// π 文件中小数位到字节偏移的索引，重复读取任意位置时无需从头扫描
+// 位置从小数点后第 0 位开始，与 window 一致
+pub struct DigitIndex {
+    path: PathBuf,
+    // 第 n * INDEX_STRIDE 位所在的字节偏移
+    offsets: Vec<u64>,
+    // 正文结束的字节偏移，之后的统计信息不属于数字
+    body_end: u64,
+    // 小数位总数
+    len: usize,
+}
+
+// 扫描 π 文件（标准格式或 raw 格式）建立索引
+// 标准格式只索引两条分隔线之间的正文，跳过整数部分、列标尺和 ":1000" 形式的位置标记
+pub fn build_index(path: &Path) -> io::Result<DigitIndex> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = Vec::new();
+    
+    let mut offset = 0u64;
+    let mut offsets = Vec::new();
+    let mut len = 0;
+    let mut body_end = None;
+    
+    // 由第一行判断格式，raw 格式整个文件都是小数位
+    let mut standard = None;
+    let mut in_body = false;
+    let mut seen_dot = false;
+    
+    loop {
+        line.clear();
+        let n = reader.read_until(b'\n', &mut line)?;
+        if n == 0 {
+            break;
+        }
+        
+        if standard.is_none() {
+            let is_standard = line.starts_with(HEADER_PREFIX.as_bytes());
+            in_body = !is_standard;
+            seen_dot = !is_standard;
+            standard = Some(is_standard);
+        }
+        let is_standard = standard == Some(true);
+        
+        if is_standard && line.starts_with(b"=") {
+            if in_body {
+                body_end = Some(offset);
+                break;
+            }
+            in_body = true;
+        } else if in_body && !(is_standard && len == 0 && is_ruler_line(&line)) {
+            let mut in_marker = false;
+            for (i, &b) in line.iter().enumerate() {
+                match b {
+                    b':' => in_marker = true,
+                    b' ' | b'\r' | b'\n' => in_marker = false,
+                    b'.' => seen_dot = true,
+                    b'0'..=b'9' if !in_marker && seen_dot => {
+                        if len % INDEX_STRIDE == 0 {
+                            offsets.push(offset + i as u64);
+                        }
+                        len += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        
+        offset += n as u64;
+    }
+    
+    Ok(DigitIndex {
+        path: path.to_path_buf(),
+        offsets,
+        body_end: body_end.unwrap_or(offset),
+        len,
+    })
+}
+
+impl DigitIndex {
+    // 文件中的小数位数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    // 读取从第 start 位开始的 len 位，超出文件范围的部分被截断
+    pub fn read_window(&self, start: usize, len: usize) -> io::Result<String> {
+        let end = start.saturating_add(len).min(self.len);
+        if start >= end {
+            return Ok(String::new());
+        }
+        let wanted = end - start;
+        
+        // 从最近的索引点开始，最多跳过 INDEX_STRIDE - 1 位
+        let mut pos = self.offsets[start / INDEX_STRIDE];
+        let mut to_skip = start % INDEX_STRIDE;
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(pos))?;
+        
+        let mut window = String::with_capacity(wanted);
+        let mut in_marker = false;
+        for byte in reader.bytes() {
+            if window.len() >= wanted || pos >= self.body_end {
+                break;
+            }
+            pos += 1;
+            
+            match byte? {
+                b':' => in_marker = true,
+                b' ' | b'\r' | b'\n' => in_marker = false,
+                b @ b'0'..=b'9' if !in_marker => {
+                    if to_skip > 0 {
+                        to_skip -= 1;
+                    } else {
+                        window.push(b as char);
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        Ok(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{stream_pi, FormatOptions};
+    use crate::reference::load_reference_chunk;
+    use crate::{compute_pi, Algorithm};
+    
+    fn expected(start: usize, len: usize) -> String {
+        String::from_utf8(load_reference_chunk(start, len).unwrap()).unwrap()
+    }
+    
+    // 5000 位文件（标准格式带或不带位置标记、raw 格式）中第 3333 位起的窗口与参考数据一致，
+    // 跨越索引点和超出文件末尾的读取也正确
+    #[test]
+    fn window_at_3333_in_5000_digit_file() {
+        let pi = compute_pi(Algorithm::Chudnovsky, 5000, 2).unwrap().value;
+        let formats = [
+            FormatOptions::default(),
+            FormatOptions { index_markers: true, ..FormatOptions::default() },
+            FormatOptions::raw(),
+        ];
+        
+        for (i, format) in formats.iter().enumerate() {
+            let path = std::env::temp_dir().join(format!("pi_index_{}_{}.txt", i, std::process::id()));
+            let mut file = File::create(&path).unwrap();
+            stream_pi(&pi, 5000, &mut file, format).unwrap();
+            drop(file);
+            
+            let index = build_index(&path).unwrap();
+            assert_eq!(index.len(), 5000);
+            assert_eq!(index.read_window(3333, 50).unwrap(), expected(3333, 50));
+            assert_eq!(index.read_window(INDEX_STRIDE - 10, 20).unwrap(), expected(INDEX_STRIDE - 10, 20));
+            assert_eq!(index.read_window(4990, 20).unwrap(), expected(4990, 10));
+            assert_eq!(index.read_window(5000, 20).unwrap(), "");
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// 计算过程中的内部不变量检查，仅在 debug 构建中生效，release 构建中为空函数
+// 用于尽早发现算法上的回归，而不是在最终结果中才看到错误的数字
+use rug::Float;
+
+#[cfg(debug_assertions)]
+use crate::chudnovsky::next_term_by_recurrence;
+
+// Chudnovsky 级数和的理论值 426880 * sqrt(10005) / π ≈ 13591408.9999997
+// 略小于 13591409，下界留出一个单位的余量
+#[cfg(debug_assertions)]
+const CHUDNOVSKY_SUM_RANGE: std::ops::Range<f64> = 13_591_408.0..13_591_410.0;
+
+// 连续两项的绝对值必须严格递减
+// previous 保存上一项的 (k, |项|)，k 不连续时（换到新的块）只记录不比较
+#[cfg(debug_assertions)]
+pub(crate) fn check_term_decreasing(k: usize, previous: &mut Option<(usize, Float)>, term: &Float) {
+    let magnitude = Float::with_val(term.prec(), term.abs_ref());
+    if let Some((previous_k, previous_magnitude)) = previous.as_ref() {
+        if *previous_k + 1 == k {
+            debug_assert!(
+                magnitude < *previous_magnitude,
+                "第 {} 项的绝对值 {} 不小于第 {} 项的 {}",
+                k,
+                magnitude.to_f64(),
+                previous_k,
+                previous_magnitude.to_f64()
+            );
+        }
+    }
+    *previous = Some((k, magnitude));
+}
+
+#[cfg(not(debug_assertions))]
+pub(crate) fn check_term_decreasing(_k: usize, _previous: &mut Option<(usize, Float)>, _term: &Float) {}
+
+// 由上一项按递推关系推出的第 k 项与直接由阶乘计算的第 k 项之间允许的误差，以最低有效位的倍数计
+// 两条路径各自舍入数次，误差不超过几个 ULP
+#[cfg(debug_assertions)]
+const RECURRENCE_SLACK_BITS: u32 = 8;
+
+// Chudnovsky 的第 k 项必须与由第 k-1 项按递推关系推出的值一致
+// previous 保存上一项的 (k, 项)，k 不连续时（换到新的块）只记录不比较
+#[cfg(debug_assertions)]
+pub(crate) fn check_chudnovsky_recurrence(k: usize, previous: &mut Option<(usize, Float)>, term: &Float) {
+    if let Some((previous_k, previous_term)) = previous.as_ref() {
+        if *previous_k + 1 == k {
+            let derived = next_term_by_recurrence(previous_term, k as u64);
+            let error = Float::with_val(term.prec(), &derived - term).abs();
+            let tolerance = Float::with_val(term.prec(), term.abs_ref())
+                >> term.prec().saturating_sub(RECURRENCE_SLACK_BITS);
+            debug_assert!(
+                error <= tolerance,
+                "第 {} 项 {} 与由第 {} 项递推得到的 {} 不一致",
+                k,
+                term.to_f64(),
+                previous_k,
+                derived.to_f64()
+            );
+        }
+    }
+    *previous = Some((k, term.clone()));
+}
+
+#[cfg(not(debug_assertions))]
+pub(crate) fn check_chudnovsky_recurrence(_k: usize, _previous: &mut Option<(usize, Float)>, _term: &Float) {}
+
+// 二进制精度必须足以表示 digits 位十进制数字
+#[cfg(debug_assertions)]
+pub(crate) fn check_precision(precision: u32, digits: usize) {
+    let needed = (digits as f64 * std::f64::consts::LOG2_10).ceil();
+    debug_assert!(
+        precision as f64 >= needed,
+        "精度 {} 位二进制不足以表示 {} 位十进制，至少需要 {} 位",
+        precision,
+        digits,
+        needed
+    );
+}
+
+#[cfg(not(debug_assertions))]
+pub(crate) fn check_precision(_precision: u32, _digits: usize) {}
+
+// Chudnovsky 级数和必须落在理论值附近
+#[cfg(debug_assertions)]
+pub(crate) fn check_chudnovsky_sum(sum: &Float) {
+    let value = sum.to_f64();
+    debug_assert!(
+        CHUDNOVSKY_SUM_RANGE.contains(&value),
+        "Chudnovsky 级数和 {} 超出预期范围 {:?}",
+        value,
+        CHUDNOVSKY_SUM_RANGE
+    );
+}
+
+#[cfg(not(debug_assertions))]
+pub(crate) fn check_chudnovsky_sum(_sum: &Float) {}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+    use crate::bbp::{FromPrecision, SeriesTerm};
+    use crate::chudnovsky::ChudnovskyCalculator;
+    
+    fn feed(terms: &[f64]) {
+        let mut previous = None;
+        for (k, &term) in terms.iter().enumerate() {
+            check_term_decreasing(k, &mut previous, &Float::with_val(64, term));
+        }
+    }
+    
+    // 绝对值递减的交错序列通过检查
+    #[test]
+    fn decreasing_terms_pass() {
+        feed(&[1.0, -0.5, 0.25, -0.125]);
+    }
+    
+    // 第 2 项的绝对值大于第 1 项，断言失败
+    #[test]
+    #[should_panic(expected = "第 2 项的绝对值")]
+    fn broken_sequence_trips_the_assertion() {
+        feed(&[1.0, -0.5, 0.75, -0.125]);
+    }
+    
+    // 换到不连续的块时不比较
+    #[test]
+    fn non_consecutive_terms_are_not_compared() {
+        let mut previous = None;
+        check_term_decreasing(10, &mut previous, &Float::with_val(64, 0.001));
+        check_term_decreasing(3, &mut previous, &Float::with_val(64, 0.5));
+    }
+    
+    // 直接计算的前 50 项都与递推结果一致
+    #[test]
+    fn chudnovsky_terms_follow_the_recurrence() {
+        let mut calculator = ChudnovskyCalculator::new(1024);
+        let mut previous = None;
+        for k in 0..50 {
+            check_chudnovsky_recurrence(k, &mut previous, calculator.compute_term(k));
+        }
+    }
+    
+    // 第 3 项的符号错误，与由第 2 项递推得到的值不一致
+    #[test]
+    #[should_panic(expected = "第 3 项")]
+    fn wrong_chudnovsky_term_trips_the_assertion() {
+        let mut calculator = ChudnovskyCalculator::new(256);
+        let mut previous = None;
+        for k in 0..3 {
+            check_chudnovsky_recurrence(k, &mut previous, calculator.compute_term(k));
+        }
+        let wrong = Float::with_val(256, -calculator.compute_term(3));
+        check_chudnovsky_recurrence(3, &mut previous, &wrong);
+    }
+    
+    #[test]
+    #[should_panic(expected = "超出预期范围")]
+    fn wrong_chudnovsky_sum_trips_the_assertion() {
+        check_chudnovsky_sum(&Float::with_val(64, 1.0));
+    }
+}
@@ -0,0 +1,939 @@
+use std::fmt;
+use std::io;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use rug::Float;
+
+use crate::invariants::check_precision;
+
+mod affinity;
+mod arctan;
+mod bbp;
+mod bellard;
+mod builder;
+mod checkpoint;
+mod chudnovsky;
+mod constants;
+mod continued_fraction;
+mod engine;
+mod explain;
+mod explore;
+mod fixed_point;
+mod gauss_legendre;
+mod hash;
+mod index;
+mod invariants;
+mod output;
+mod prompt;
+mod reference;
+mod retry;
+mod scalar;
+mod self_test;
+mod spigot;
+mod stats;
+mod summary;
+mod throughput;
+mod verify;
+#[cfg(feature = "tokio")]
+mod async_compute;
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+pub use arctan::{arctan_inv, arctan_inv_with};
+pub use bbp::{
+    bbp_hex_digit, compute_pi_cancellable, compute_pi_checkpointed, compute_pi_optimized,
+    DEFAULT_CHECKPOINT_INTERVAL,
+};
+pub use bellard::{
+    bellard_term, compute_pi_bellard, compute_pi_bellard_cancellable, BELLARD_DIGITS_PER_TERM,
+};
+pub use builder::PiBuilder;
+pub use checkpoint::{
+    checkpoint_path, load_checkpoint, save_checkpoint, Checkpoint, CheckpointOptions,
+};
+pub use chudnovsky::{
+    binary_split, binary_split_bit_lengths, chudnovsky_convergence, chudnovsky_terms,
+    compute_inv_pi, compute_pi_binary_split, compute_pi_binary_split_cancellable,
+    compute_pi_chudnovsky, compute_pi_chudnovsky_cancellable, dump_triples, BitLengthStats,
+    ChudnovskyIter, ConvergenceStep, FactorialStrategy, SplitTriple, CHUDNOVSKY_DIGITS_PER_TERM,
+    MAX_DUMP_TRIPLES_TERMS,
+};
+pub use constants::{compute_constants, compute_half_pi, compute_quarter_pi, compute_tau, Constant};
+pub use continued_fraction::{convergents, pi_continued_fraction};
+pub use engine::PiEngine;
+pub use explain::explain;
+pub use explore::{find_sequence, window};
+pub use fixed_point::{
+    compute_pi_bbp_fixedpoint, compute_pi_bbp_fixedpoint_with, fixed_point_precision,
+    fixed_point_to_decimal, pi_scaled_mod,
+};
+pub use gauss_legendre::{compute_pi_gauss_legendre, compute_pi_gauss_legendre_cancellable};
+pub use hash::hash_digits;
+pub use index::{build_index, DigitIndex};
+pub use reference::{
+    load_reference_chunk, pi_known_digits, BUNDLED_DIGITS, CACHED_DIGITS, PI_F64,
+};
+pub use retry::{create_file, retry_with_backoff, RetryPolicy};
+pub use self_test::{self_test, SELF_TEST_DIGITS};
+pub use spigot::{stream_digits, Spigot};
+pub use summary::{digits_per_sec, print_summary, print_summary_table, run, run_streaming, RunSummary};
+pub use throughput::{throughput_trace, BBP_DIGITS_PER_TERM};
+pub use output::{
+    append_pi_digits, binary_digits_for, extract_digits, format_block, format_scalar, fraction_bits,
+    fraction_digits, pi_char_iter, pi_string, stream_pi, stream_pi_binary, write_pi_binary, write_pi_csv_positions,
+    write_pi_decimated, write_pi_for_validation, write_pi_raw, write_pi_split,
+    write_pi_to_file_chunked, write_sha256_sidecar, FormatOptions, LineEnding, OutputMode,
+    WriteOptions, DEFAULT_BUFFER_SIZE,
+};
+pub use prompt::{parse_algorithm_choice, prompt_usize};
+pub use stats::{
+    check_feasibility, compression_ratio, digit_statistics, estimate_memory_bytes,
+    estimate_runtime_and_memory, fit_to_memory, print_digit_statistics, print_memory_stats,
+    DEFAULT_MAX_MEMORY_BYTES, DEFAULT_MAX_RUNTIME, RANDOM_DIGITS_COMPRESSION_RATIO,
+};
+pub use verify::{
+    compare_floats, count_digits_in_file, double_check, sanity_check, validate_file_via_bbp,
+    verify_against_file, verify_file, verify_pi_accuracy, FileComparison, FileVerification,
+    FloatDiff, ValidationReport,
+};
+#[cfg(feature = "tokio")]
+pub use async_compute::compute_pi_async;
+
+// 整数部分 3 加上 digits 位小数，每个元素为 0-9 的数值，共 digits + 1 个
+pub fn pi_digits(digits: usize, threads: usize) -> Result<Vec<u8>, ComputeError> {
+    let pi = compute_pi_optimized(digits, threads)?.value;
+    Ok(std::iter::once(3).chain(fraction_digits(&pi, digits)).collect())
+}
+
+// 计算 π 的算法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    // BBP 公式，每项约 4 位二进制
+    Bbp,
+    // Chudnovsky 级数，每项约 14.18 位十进制
+    Chudnovsky,
+    // Bellard 公式，每项约 10 位二进制
+    Bellard,
+    // 二进分割的 Chudnovsky 级数，各项全部用整数累加，适合百万位以上
+    ChudnovskyBinarySplit,
+    // Gauss-Legendre 算术几何平均，每次迭代正确位数约翻倍，只能单线程
+    GaussLegendre,
+}
+
+impl Algorithm {
+    // 所有支持的算法
+    pub fn all() -> &'static [Algorithm] {
+        &[
+            Algorithm::Bbp,
+            Algorithm::Chudnovsky,
+            Algorithm::Bellard,
+            Algorithm::ChudnovskyBinarySplit,
+            Algorithm::GaussLegendre,
+        ]
+    }
+    
+    // 命令行和菜单中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Bbp => "bbp",
+            Algorithm::Chudnovsky => "chudnovsky",
+            Algorithm::Bellard => "bellard",
+            Algorithm::ChudnovskyBinarySplit => "chudnovsky-bs",
+            Algorithm::GaussLegendre => "gauss-legendre",
+        }
+    }
+    
+    // 按位数选择默认算法：小位数用 BBP（可使用内置常数和检查点），大位数用收敛更快的 Chudnovsky
+    pub fn default_for(digits: usize) -> Algorithm {
+        if digits <= 10_000 {
+            Algorithm::Bbp
+        } else {
+            Algorithm::Chudnovsky
+        }
+    }
+    
+    // 推荐使用的位数范围
+    pub fn recommended_digits(self) -> RangeInclusive<usize> {
+        match self {
+            Algorithm::Bbp => 1..=100_000,
+            Algorithm::Chudnovsky => 1_000..=1_000_000,
+            Algorithm::Bellard => 1..=200_000,
+            Algorithm::ChudnovskyBinarySplit => 10_000..=100_000_000,
+            Algorithm::GaussLegendre => 1_000..=10_000_000,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// 无法识别的算法名称
+#[derive(Debug)]
+pub struct ParseAlgorithmError(pub String);
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Algorithm::all().iter().map(|a| a.name()).collect();
+        write!(f, "未知算法 \"{}\"，可选: {}", self.0, names.join(", "))
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
+
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+    
+    // 名称不区分大小写
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim();
+        Algorithm::all()
+            .iter()
+            .copied()
+            .find(|a| a.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseAlgorithmError(name.to_string()))
+    }
+}
+
+// 使用指定算法计算 π
+pub fn compute_pi(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+) -> Result<ComputeResult, ComputeError> {
+    compute_pi_with(algorithm, digits, num_threads, &ComputeOptions::default())
+}
+
+// 同 compute_pi，按 options 调整计算过程
+pub fn compute_pi_with(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    compute_pi_with_stop(algorithm, digits, num_threads, &stop, options)
+}
+
+// 同 compute_pi_with，stop 被置位后各线程尽快退出并返回 Cancelled
+pub fn compute_pi_with_stop(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+    stop: &Arc<AtomicBool>,
+    options: &ComputeOptions,
+) -> Result<ComputeResult, ComputeError> {
+    match algorithm {
+        Algorithm::Bbp => compute_pi_cancellable(digits, num_threads, stop, options),
+        Algorithm::Chudnovsky => compute_pi_chudnovsky_cancellable(digits, num_threads, stop, options),
+        Algorithm::Bellard => compute_pi_bellard_cancellable(digits, num_threads, stop, options),
+        Algorithm::ChudnovskyBinarySplit => {
+            compute_pi_binary_split_cancellable(digits, num_threads, stop, options)
+        }
+        Algorithm::GaussLegendre => {
+            compute_pi_gauss_legendre_cancellable(digits, num_threads, stop, options)
+        }
+    }
+}
+
+// 完整计算一次并返回耗时，供基准测试使用
+// BBP 不经过内置常数快速路径，保证不同位数的结果可比
+pub fn bench_once(algorithm: Algorithm, digits: usize) -> Result<Duration, ComputeError> {
+    bench_with_threads(algorithm, digits, default_threads())
+}
+
+// 同 bench_once，使用指定线程数
+pub fn bench_with_threads(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+) -> Result<Duration, ComputeError> {
+    let options = ComputeOptions::default();
+    let start = Instant::now();
+    
+    let pi = match algorithm {
+        Algorithm::Bbp => {
+            let stop = Arc::new(AtomicBool::new(false));
+            bbp::compute_pi_series(digits, num_threads, &stop, &options)?.value
+        }
+        Algorithm::Chudnovsky => compute_pi_chudnovsky(digits, num_threads, &options)?.value,
+        Algorithm::Bellard => compute_pi_bellard(digits, num_threads, &options)?.value,
+        Algorithm::ChudnovskyBinarySplit => compute_pi_binary_split(digits, num_threads)?.value,
+        Algorithm::GaussLegendre => compute_pi_gauss_legendre(digits, num_threads, &options)?.value,
+    };
+    
+    let elapsed = start.elapsed();
+    std::hint::black_box(pi);
+    Ok(elapsed)
+}
+
+// 一次计算的结果，附带审计用的项数和精度
+#[derive(Debug)]
+pub struct ComputeResult {
+    pub value: Float,
+    // 请求计算的小数位数
+    pub digits: usize,
+    // 实际累加的级数项数，使用内置常数时为 0
+    pub terms: usize,
+    // 计算使用的二进制精度
+    pub precision: u32,
+    pub algorithm: Algorithm,
+    pub elapsed: Duration,
+    // 计算过程中按固定时间间隔采样的瞬时速率 (已用时间, 十进制位/秒)
+    pub throughput: Vec<(Duration, f64)>,
+}
+
+// 计算过程的可选项，默认值与不指定时的行为一致
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComputeOptions {
+    // 将工作线程各自绑定到一个 CPU 核心，减少线程迁移造成的缓存失效
+    // 需要启用 core_affinity 特性，否则或平台不支持时不做任何事
+    pub pin_threads: bool,
+    // 逐项计算 Chudnovsky 级数时 k!、(3k)!、(6k)! 的计算方式，只影响耗时
+    // 二进分割的叶子直接由相邻项之比构造，不计算阶乘，这一选项对其没有作用
+    pub factorial_strategy: FactorialStrategy,
+    // 只有一个工作线程的求和在 |项| < 2^-precision 时停止累加剩余的项
+    // 各项单调递减，之后的项之和也不会影响结果
+    pub early_exit: bool,
+    // 直接使用的二进制精度，不再按位数推导，例如为了与其他工具的结果逐位一致
+    pub precision: Option<u32>,
+}
+
+impl ComputeOptions {
+    // 多线程时各线程看不到整体进度，不提前结束
+    pub(crate) fn early_exit_for(&self, num_threads: usize) -> bool {
+        self.early_exit && num_threads == 1
+    }
+    
+    // 指定了精度时直接使用指定值，不加保护位；否则同 checked_precision
+    pub(crate) fn precision_for(&self, digits: usize, extra_bits: u32) -> Result<u32, ComputeError> {
+        match self.precision {
+            Some(bits) => precision_within_limits(digits, u64::from(bits)),
+            None => checked_precision(digits, extra_bits),
+        }
+    }
+    
+    // 指定的精度可能低于 digits 位所需，此时不检查精度是否足够
+    pub(crate) fn check_precision(&self, precision: u32, digits: usize) {
+        if self.precision.is_none() {
+            check_precision(precision, digits);
+        }
+    }
+}
+
+// 运行配置
+pub struct Config {
+    pub digits: usize,
+    pub num_threads: usize,
+    pub algorithm: Algorithm,
+    pub output_file: String,
+    pub output_mode: OutputMode,
+    pub show_statistics: bool,
+    // 额外计算的十进制保护位数，输出时不包含
+    pub guard_digits: usize,
+    pub options: ComputeOptions,
+}
+
+impl Config {
+    // 使用默认设置：按位数选择的算法及其推荐线程数、标准格式、默认文件名
+    pub fn new(digits: usize) -> Self {
+        Self {
+            digits,
+            num_threads: recommended_threads(Algorithm::default_for(digits), digits),
+            algorithm: Algorithm::default_for(digits),
+            output_file: format!("pi_{}_digits.txt", digits),
+            output_mode: OutputMode::Standard,
+            show_statistics: false,
+            guard_digits: 0,
+            options: ComputeOptions::default(),
+        }
+    }
+    
+    // 实际计算的位数：输出位数加保护位
+    pub fn compute_digits(&self) -> usize {
+        self.digits.saturating_add(self.guard_digits)
+    }
+    
+    // 输出的位数，结果只在输出时截断到这个位数
+    pub fn display_digits(&self) -> usize {
+        self.digits
+    }
+    
+    // 直接指定实际计算的位数，不少于输出位数，多出的部分作为保护位
+    pub fn set_compute_digits(&mut self, compute_digits: usize) {
+        self.guard_digits = compute_digits.saturating_sub(self.digits);
+    }
+}
+
+// 指定默认线程数的环境变量
+const THREADS_ENV_VAR: &str = "PI_THREADS";
+
+// 检测到的 CPU 核心数，至少为 1
+// 某些受限的容器中 num_cpus::get() 可能报告 0，直接用作线程数会一个工作线程也不启动
+pub fn cpu_count() -> usize {
+    clamp_cpu_count(num_cpus::get())
+}
+
+fn clamp_cpu_count(detected: usize) -> usize {
+    detected.max(1)
+}
+
+// 默认线程数：优先使用环境变量 PI_THREADS，否则使用 CPU 核心数
+pub fn default_threads() -> usize {
+    match std::env::var(THREADS_ENV_VAR) {
+        Ok(value) => parse_threads(&value).unwrap_or_else(|| {
+            log::warn!("环境变量 {} 的值 \"{}\" 不是正整数，使用 CPU 核心数", THREADS_ENV_VAR, value);
+            cpu_count()
+        }),
+        Err(_) => cpu_count(),
+    }
+}
+
+// 每个线程至少分到的级数项数，项数更少时多开线程只增加调度和合并的开销
+const MIN_TERMS_PER_THREAD: usize = 64;
+
+// 未指定线程数时按算法和位数推荐的线程数，环境变量 PI_THREADS 视为用户指定
+// BBP 和 Bellard 各项互相独立，几乎可以线性扩展到全部核心；
+// Chudnovsky（包括二进分割）每项的数更大，最后的合并、开方和除法是串行的，多线程收益有限，最多用一半核心；
+// Gauss-Legendre 的迭代互相依赖，只用单线程
+pub fn recommended_threads(algorithm: Algorithm, digits: usize) -> usize {
+    if let Ok(value) = std::env::var(THREADS_ENV_VAR) {
+        if let Some(threads) = parse_threads(&value) {
+            return threads;
+        }
+        log::warn!("环境变量 {} 的值 \"{}\" 不是正整数，按算法推荐线程数", THREADS_ENV_VAR, value);
+    }
+    
+    let cores = cpu_count();
+    let (terms, cap) = match algorithm {
+        Algorithm::Bbp => (bbp::bbp_terms(required_precision(digits)), cores),
+        Algorithm::Bellard => (bellard::bellard_terms(required_precision(digits)), cores),
+        Algorithm::Chudnovsky | Algorithm::ChudnovskyBinarySplit => {
+            // 超出范围时计算本身会报错，这里只需要一个估计
+            let terms = chudnovsky_terms(digits).unwrap_or(usize::MAX);
+            (terms, cores.div_ceil(2))
+        }
+        Algorithm::GaussLegendre => (1, 1),
+    };
+    
+    terms.div_ceil(MIN_TERMS_PER_THREAD).clamp(1, cap)
+}
+
+// 解析线程数，必须是正整数
+fn parse_threads(value: &str) -> Option<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(n) if n >= 1 => Some(n),
+        _ => None,
+    }
+}
+
+// 计算过程中的错误
+#[derive(Debug)]
+pub enum ComputeError {
+    // 计算被取消
+    Cancelled,
+    // 工作线程发生 panic
+    WorkerPanicked,
+    // 估算内存超出上限（字节）
+    MemoryBudgetExceeded { required: u64, budget: u64 },
+    // 位数超出算法在当前平台上能处理的范围
+    TooManyDigits { digits: usize, max: usize },
+    // 级数和为零或过小，无法用作除数
+    EmptySum,
+    // 第 thread 个工作线程（从 0 开始）的局部和为无穷大或 NaN
+    NonFinitePartial { thread: usize },
+    // 预计耗时或内存超出允许的上限
+    Infeasible { digits: usize, runtime: Duration, memory: u64 },
+    // 复核时从小数点后第 position 位开始不一致
+    VerificationFailed { position: usize },
+    // 结果的开头不是 3.14159，found 为实际的开头
+    SanityCheckFailed { found: String },
+    // 所需的二进制精度 bits 超出 MPFR 允许的最大精度 max
+    PrecisionTooLarge { digits: usize, bits: u64, max: u32 },
+    // 自检时 algorithm 的结果从小数点后第 position 位开始与 against（None 表示内置常数）不一致
+    SelfTestFailed { algorithm: Algorithm, against: Option<Algorithm>, position: usize },
+    // 取余的模数为 0
+    ZeroModulus,
+    // 误差上限不是正的有限数
+    InvalidEpsilon { epsilon: f64 },
+    // 检查点的位数或精度 (saved_digits, saved_precision) 与本次计算的 (digits, precision) 不符
+    CheckpointMismatch { digits: usize, precision: u32, saved_digits: usize, saved_precision: u32 },
+    // 写入结果失败
+    Io(io::Error),
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::Cancelled => write!(f, "计算已取消"),
+            ComputeError::WorkerPanicked => write!(f, "工作线程异常退出"),
+            ComputeError::MemoryBudgetExceeded { required, budget } => write!(
+                f,
+                "内存上限 {:.2} MB 不足，单线程计算也需要约 {:.2} MB",
+                *budget as f64 / 1024.0 / 1024.0,
+                *required as f64 / 1024.0 / 1024.0
+            ),
+            ComputeError::TooManyDigits { digits, max } => {
+                write!(f, "位数 {} 超出当前平台的计算范围，最多 {} 位", digits, max)
+            }
+            ComputeError::EmptySum => write!(f, "级数和为零或过小，没有累加到有效的项"),
+            ComputeError::NonFinitePartial { thread } => {
+                write!(f, "第 {} 个工作线程的局部和不是有限值", thread)
+            }
+            ComputeError::Infeasible { digits, runtime, memory } => write!(
+                f,
+                "计算 {} 位预计需要 {:.0} 秒、{:.2} MB 内存，超出允许的上限",
+                digits,
+                runtime.as_secs_f64(),
+                *memory as f64 / 1024.0 / 1024.0
+            ),
+            ComputeError::VerificationFailed { position } => {
+                write!(f, "复核失败: 小数点后第 {} 位开始不一致", position)
+            }
+            ComputeError::SanityCheckFailed { found } => {
+                write!(f, "结果以 {} 开头，不是 π，计算过程可能出错", found)
+            }
+            ComputeError::PrecisionTooLarge { digits, bits, max } => write!(
+                f,
+                "计算 {} 位需要 {} 位二进制精度，超出 MPFR 允许的最大精度 {} 位",
+                digits, bits, max
+            ),
+            ComputeError::SelfTestFailed { algorithm, against, position } => match against {
+                Some(other) => write!(
+                    f,
+                    "自检失败: {} 与 {} 的结果从小数点后第 {} 位开始不一致",
+                    algorithm, other, position
+                ),
+                None => write!(
+                    f,
+                    "自检失败: {} 的结果从小数点后第 {} 位开始与内置常数不一致",
+                    algorithm, position
+                ),
+            },
+            ComputeError::ZeroModulus => write!(f, "模数必须大于 0"),
+            ComputeError::InvalidEpsilon { epsilon } => {
+                write!(f, "误差上限 {} 必须是正的有限数", epsilon)
+            }
+            ComputeError::CheckpointMismatch { digits, precision, saved_digits, saved_precision } => write!(
+                f,
+                "检查点是 {} 位、{} 位二进制精度的计算，与本次的 {} 位、{} 位二进制精度不符",
+                saved_digits, saved_precision, digits, precision
+            ),
+            ComputeError::Io(e) => write!(f, "写入失败: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {}
+
+impl From<io::Error> for ComputeError {
+    fn from(e: io::Error) -> Self {
+        ComputeError::Io(e)
+    }
+}
+
+// 计算所需精度（二进制位）
+// 1 位十进制 ≈ log2(10) ≈ 3.32193 位二进制
+pub fn required_precision(digits: usize) -> u32 {
+    required_precision_with_guard(digits, 0)
+}
+
+// 先在 digits 之上加 guard_digits 位十进制保护位，再换算为二进制精度
+// 另加 10 位，以及随精度对数增长的余量，覆盖累加各项的舍入误差，使 guaranteed_digits 不少于 digits
+// 超出 u32 时饱和为 u32::MAX，需要发现溢出时使用 checked_precision
+pub fn required_precision_with_guard(digits: usize, guard_digits: usize) -> u32 {
+    u32::try_from(required_precision_bits(digits, guard_digits)).unwrap_or(u32::MAX)
+}
+
+// 以 u64 计算的 required_precision_with_guard，十亿位以上也不会溢出
+pub fn required_precision_bits(digits: usize, guard_digits: usize) -> u64 {
+    let bits = ((digits.saturating_add(guard_digits) as f64) * 3.32193).ceil() as u64;
+    let margin = u64::from(u64::BITS - bits.leading_zeros());
+    bits.saturating_add(10).saturating_add(margin)
+}
+
+// digits 位所需的精度再加 extra_bits 位保护位，超出 MPFR 允许的最大精度时返回错误
+// rug 的精度是 u32，约 12.9 亿位十进制以上就无法表示，不能静默截断
+pub fn checked_precision(digits: usize, extra_bits: u32) -> Result<u32, ComputeError> {
+    let bits = required_precision_bits(digits, 0).saturating_add(u64::from(extra_bits));
+    precision_within_limits(digits, bits)
+}
+
+fn precision_within_limits(digits: usize, bits: u64) -> Result<u32, ComputeError> {
+    let max = rug::float::prec_max();
+    match u32::try_from(bits) {
+        Ok(precision) if precision <= max => Ok(precision),
+        _ => Err(ComputeError::PrecisionTooLarge { digits, bits, max }),
+    }
+}
+
+// 累加舍入之外再扣除的保护位
+const GUARANTEE_GUARD_BITS: f64 = 2.0;
+
+// 以 precision 位二进制精度累加 terms 项后，可以保证正确的小数位数
+// 每项的舍入误差至多半个 ULP，累加 terms 项最多损失 log2(terms) 位；整数部分占 1 位有效数字
+pub fn guaranteed_digits(precision: u32, terms: usize) -> usize {
+    let lost = (terms.max(1) as f64).log2().ceil() + GUARANTEE_GUARD_BITS;
+    let bits = precision as f64 - lost;
+    if bits <= 0.0 {
+        0
+    } else {
+        ((bits / 3.32193).floor() as usize).saturating_sub(1)
+    }
+}
+
+// 按绝对误差上限计算 π，保证结果与真实 π 之差小于 epsilon
+// 位数取 -log10(epsilon)，再多算 2 位抵消末位的舍入误差
+// epsilon 不是正的有限数时返回 InvalidEpsilon
+pub fn compute_pi_to_error(epsilon: f64, threads: usize) -> Result<Float, ComputeError> {
+    if !(epsilon > 0.0 && epsilon.is_finite()) {
+        return Err(ComputeError::InvalidEpsilon { epsilon });
+    }
+    
+    let digits = (-epsilon.log10()).ceil().max(0.0) as usize + 2;
+    let stop = Arc::new(AtomicBool::new(false));
+    Ok(compute_pi_cancellable(digits, threads, &stop, &ComputeOptions::default())?.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::thread::{self, ThreadId};
+    
+    // 修改环境变量的测试互相串行，避免并行运行时读到其他测试设置的值
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+    
+    // 记录所有日志的 logger，并行运行的测试按线程区分各自的记录
+    struct CapturingLogger {
+        records: Mutex<Vec<(ThreadId, log::Level, String)>>,
+    }
+    
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+        
+        fn log(&self, record: &log::Record) {
+            let entry = (thread::current().id(), record.level(), record.args().to_string());
+            self.records.lock().unwrap().push(entry);
+        }
+        
+        fn flush(&self) {}
+    }
+    
+    static LOGGER: CapturingLogger = CapturingLogger { records: Mutex::new(Vec::new()) };
+    
+    #[test]
+    fn bench_once_times_every_algorithm() {
+        for &algorithm in Algorithm::all() {
+            assert!(bench_once(algorithm, 200).is_ok());
+        }
+    }
+    
+    // 每种算法的名称经 Display 输出后能解析回同一算法，大小写和首尾空白不影响解析
+    #[test]
+    fn algorithm_names_round_trip() {
+        for &algorithm in Algorithm::all() {
+            assert_eq!(algorithm.to_string().parse::<Algorithm>().unwrap(), algorithm);
+            assert_eq!(algorithm.name().to_uppercase().parse::<Algorithm>().unwrap(), algorithm);
+        }
+        assert_eq!(" chudnovsky-bs ".parse::<Algorithm>().unwrap(), Algorithm::ChudnovskyBinarySplit);
+        assert!("chudnovsky-split".parse::<Algorithm>().is_err());
+        
+        let result = compute_pi(Algorithm::ChudnovskyBinarySplit, 1000, 2).unwrap();
+        assert_eq!(result.algorithm, Algorithm::ChudnovskyBinarySplit);
+        assert!(pi_string(&result.value, 1000).starts_with(pi_known_digits()));
+    }
+    
+    // 随机抽取 50 到 2000 之间的位数，每种算法的全部位数都与打包的参考数据一致
+    // 种子固定，失败时可以重现；BBP 不经过内置常数，否则 1000 位以下只是比较常数本身
+    #[test]
+    fn every_algorithm_matches_reference_for_random_digit_counts() {
+        let mut state: u64 = 0x5eed_3141_5926_5358;
+        let mut next_digits = || {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            50 + (state % 1951) as usize
+        };
+        
+        let options = ComputeOptions::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        for _ in 0..8 {
+            let digits = next_digits();
+            let expected = load_reference_chunk(0, digits).unwrap();
+            for &algorithm in Algorithm::all() {
+                let result = match algorithm {
+                    Algorithm::Bbp => bbp::compute_pi_series(digits, 2, &stop, &options),
+                    _ => compute_pi_with(algorithm, digits, 2, &options),
+                }
+                .unwrap();
+                let pi_str = pi_string(&result.value, digits);
+                assert_eq!(pi_str[2..].as_bytes(), expected, "{} 位，{}", digits, algorithm);
+            }
+        }
+    }
+    
+    // 10000 位及以下用 BBP，以上用 Chudnovsky
+    #[test]
+    fn default_algorithm_switches_above_ten_thousand_digits() {
+        assert_eq!(Algorithm::default_for(9_999), Algorithm::Bbp);
+        assert_eq!(Algorithm::default_for(10_000), Algorithm::Bbp);
+        assert_eq!(Algorithm::default_for(10_001), Algorithm::Chudnovsky);
+    }
+    
+    // 分界两侧按默认算法计算的结果都正确：前 100 位与内置常数一致，全部位数与另一种算法交叉核对
+    #[test]
+    fn default_algorithm_is_correct_around_the_switch() {
+        for digits in [9_999, 10_001] {
+            let selected = compute_pi(Algorithm::default_for(digits), digits, 4).unwrap();
+            let other = match selected.algorithm {
+                Algorithm::Bbp => Algorithm::Chudnovsky,
+                _ => Algorithm::Bbp,
+            };
+            let check = compute_pi(other, digits, 4).unwrap();
+            
+            let selected_str = pi_string(&selected.value, digits);
+            assert!(selected_str.starts_with(pi_known_digits()));
+            assert_eq!(selected_str, pi_string(&check.value, digits));
+        }
+    }
+    
+    // 指定的精度远低于位数所需时照常计算，不触发精度检查，也不使用内置常数
+    #[test]
+    fn low_precision_override_is_used_as_is() {
+        let options = ComputeOptions { precision: Some(64), ..ComputeOptions::default() };
+        for &algorithm in Algorithm::all() {
+            let result = compute_pi_with(algorithm, 1000, 2, &options).unwrap();
+            assert_eq!(result.precision, 64);
+            assert_eq!(result.value.prec(), 64);
+            assert_eq!(pi_string(&result.value, 15), "3.141592653589793");
+        }
+    }
+    
+    // 误差上限 1e-50 时结果与高精度 π 之差小于 1e-50；非正数、NaN 和无穷大返回错误而不是 panic
+    #[test]
+    fn error_bound_is_met() {
+        let pi = compute_pi_to_error(1e-50, 2).unwrap();
+        let reference = Float::with_val(512, rug::float::Constant::Pi);
+        let error = Float::with_val(512, &pi - &reference).abs();
+        assert!(error < 1e-50, "误差 {}", error.to_f64());
+        
+        for epsilon in [0.0, -1e-10, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                compute_pi_to_error(epsilon, 2),
+                Err(ComputeError::InvalidEpsilon { .. })
+            ));
+        }
+    }
+    
+    // PI_THREADS 为正整数时作为默认线程数，0 或非数字时回退到 CPU 核心数
+    #[test]
+    fn threads_env_var_sets_default() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        
+        std::env::set_var(THREADS_ENV_VAR, " 3 ");
+        assert_eq!(default_threads(), 3);
+        assert_eq!(recommended_threads(Algorithm::Chudnovsky, 1_000_000), 3);
+        
+        for invalid in ["0", "-2", "abc", ""] {
+            std::env::set_var(THREADS_ENV_VAR, invalid);
+            assert_eq!(default_threads(), cpu_count(), "{:?}", invalid);
+        }
+        
+        std::env::remove_var(THREADS_ENV_VAR);
+        assert_eq!(default_threads(), cpu_count());
+    }
+    
+    // 容器中检测到 0 个核心时按 1 个处理，默认线程数和推荐线程数都至少为 1
+    #[test]
+    fn detected_cpu_count_is_at_least_one() {
+        assert_eq!(clamp_cpu_count(0), 1);
+        assert_eq!(clamp_cpu_count(1), 1);
+        assert_eq!(clamp_cpu_count(8), 8);
+        
+        let _guard = ENV_LOCK.lock().unwrap();
+        std::env::remove_var(THREADS_ENV_VAR);
+        assert!(cpu_count() >= 1);
+        assert!(default_threads() >= 1);
+        for &algorithm in Algorithm::all() {
+            assert!(recommended_threads(algorithm, 1) >= 1, "{}", algorithm);
+        }
+    }
+    
+    // BBP 各项互相独立，位数足够时用满所有核心；二分法合并近似串行，少量位数时只推荐少数线程
+    #[test]
+    fn recommended_threads_depend_on_algorithm() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        std::env::remove_var(THREADS_ENV_VAR);
+        let cores = cpu_count();
+        
+        assert_eq!(recommended_threads(Algorithm::Bbp, 1_000_000), cores);
+        assert!(recommended_threads(Algorithm::Bbp, 10) >= 1);
+        
+        // 1000 位只需约 71 项，不值得拆给更多线程
+        let small = recommended_threads(Algorithm::ChudnovskyBinarySplit, 1000);
+        assert!((1..=2).contains(&small), "{}", small);
+        assert!(small <= recommended_threads(Algorithm::Bbp, 1000));
+        
+        // 位数再大，二分法也最多用一半核心
+        assert!(recommended_threads(Algorithm::ChudnovskyBinarySplit, 10_000_000) <= cores.div_ceil(2));
+        assert_eq!(recommended_threads(Algorithm::GaussLegendre, 1_000_000), 1);
+    }
+    
+    // 计算 500 位时通过 log 输出 info 级别的完成记录，而不是直接打印到标准输出
+    #[test]
+    fn completion_is_logged_at_info_level() {
+        // 同一进程只能设置一次 logger
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+        
+        compute_pi(Algorithm::Chudnovsky, 500, 2).unwrap();
+        
+        let current = thread::current().id();
+        let records = LOGGER.records.lock().unwrap();
+        assert!(records.iter().any(|(thread, level, message)| {
+            *thread == current && *level == log::Level::Info && message.starts_with("计算完成")
+        }));
+    }
+    
+    // 第一个元素是整数部分 3，之后是 0-9 的小数位，与参考数据逐位一致；位数超过内置常数时同样成立
+    #[test]
+    fn pi_digits_are_values_not_ascii() {
+        let digits = pi_digits(100, 4).unwrap();
+        assert_eq!(digits.len(), 101);
+        assert_eq!(digits[0], 3);
+        let expected: Vec<u8> = load_reference_chunk(0, 100).unwrap().iter().map(|b| b - b'0').collect();
+        assert_eq!(digits[1..], expected);
+        
+        let digits = pi_digits(2000, 4).unwrap();
+        let expected: Vec<u8> = load_reference_chunk(0, 2000).unwrap().iter().map(|b| b - b'0').collect();
+        assert_eq!(digits[1..], expected);
+    }
+    
+    // 每增加 1 位十进制保护位，二进制精度增加 log2(10) 位；加保护位后输出的位数仍然正确
+    #[test]
+    fn guard_digits_raise_precision_predictably() {
+        // 保护位与位数等价：1000 位加 g 位保护位与直接计算 1000 + g 位所需的精度相同
+        for guard_digits in [0, 10, 100] {
+            let precision = required_precision_with_guard(1000, guard_digits);
+            assert_eq!(precision, required_precision(1000 + guard_digits));
+            assert!(precision as f64 >= (1000 + guard_digits) as f64 * 3.32193);
+        }
+        let extra = required_precision_with_guard(1000, 10) - required_precision(1000);
+        assert!((33..=34).contains(&extra), "{}", extra);
+        
+        let mut config = Config::new(1000);
+        config.algorithm = Algorithm::Bbp;
+        config.guard_digits = 20;
+        assert_eq!(config.compute_digits(), 1020);
+        let result = compute_pi(config.algorithm, config.compute_digits(), 2).unwrap();
+        assert_eq!(result.precision, required_precision_with_guard(1000, 20));
+        let pi_str = pi_string(&result.value, config.display_digits());
+        assert_eq!(pi_str[2..].as_bytes(), load_reference_chunk(0, 1000).unwrap());
+    }
+    
+    // 计算 1050 位、输出 1000 位：结果只在输出时截断，输出的 1000 位全部正确
+    #[test]
+    fn compute_1050_display_1000() {
+        let mut config = Config::new(1000);
+        config.algorithm = Algorithm::Chudnovsky;
+        config.set_compute_digits(1050);
+        assert_eq!((config.compute_digits(), config.display_digits(), config.guard_digits), (1050, 1000, 50));
+        
+        let result = compute_pi(config.algorithm, config.compute_digits(), 2).unwrap();
+        assert_eq!(result.digits, 1050);
+        let pi_str = pi_string(&result.value, config.display_digits());
+        assert_eq!(pi_str.len(), 1002);
+        assert_eq!(pi_str[2..].as_bytes(), load_reference_chunk(0, 1000).unwrap());
+        
+        let mut out = Vec::new();
+        stream_pi(&result.value, config.display_digits(), &mut out, &FormatOptions::default()).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("总位数: 1000\n"));
+    }
+    
+    // 1 到 5 位时计算、验证和写入都不 panic，也不产生空串：1 位是 "3.1"，5 位是 "3.14159"
+    #[test]
+    fn tiny_digit_counts_work_end_to_end() {
+        for digits in 1..=5 {
+            let expected = &pi_known_digits()[..digits + 2];
+            for &algorithm in Algorithm::all() {
+                let result = compute_pi(algorithm, digits, 2).unwrap();
+                let pi_str = pi_string(&result.value, digits);
+                assert_eq!(pi_str, expected, "{} 位，{}", digits, algorithm);
+                assert_eq!(verify_pi_accuracy(&pi_str, digits), (true, digits));
+            }
+            
+            let pi = compute_pi(Algorithm::Chudnovsky, digits, 1).unwrap().value;
+            let mut out = Vec::new();
+            stream_pi(&pi, digits, &mut out, &FormatOptions::default()).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            assert!(text.lines().any(|line| line == expected), "{}", text);
+            
+            let mut out = Vec::new();
+            stream_pi(&pi, digits, &mut out, &FormatOptions::raw()).unwrap();
+            assert_eq!(out, expected[2..].as_bytes());
+        }
+    }
+    
+    // 以较低的精度计算时，guaranteed_digits 给出的位数不超过实际正确的位数
+    #[test]
+    fn guaranteed_digits_is_conservative() {
+        let reference = load_reference_chunk(0, 1000).unwrap();
+        for &algorithm in &[Algorithm::Bbp, Algorithm::Bellard, Algorithm::Chudnovsky] {
+            for precision in [200, 500, 1000, 2000, 3400] {
+                let options = ComputeOptions { precision: Some(precision), ..ComputeOptions::default() };
+                let result = compute_pi_with(algorithm, 1000, 2, &options).unwrap();
+                let pi_str = pi_string(&result.value, 1000);
+                let actual = pi_str[2..]
+                    .bytes()
+                    .zip(reference.iter().copied())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                
+                let guaranteed = guaranteed_digits(precision, result.terms);
+                assert!(
+                    guaranteed <= actual,
+                    "{} 精度 {}: 保证 {} 位，实际 {} 位",
+                    algorithm,
+                    precision,
+                    guaranteed,
+                    actual
+                );
+            }
+        }
+        assert_eq!(guaranteed_digits(4, 1000), 0);
+    }
+    
+    // 约 12.9 亿位时所需精度超过 u32：边界以下正常返回，边界以上返回错误而不是回绕成很小的精度
+    #[test]
+    fn precision_overflow_near_1_29e9_digits() {
+        let below = 1_292_000_000;
+        let bits = required_precision_bits(below, 0);
+        assert!(bits < u64::from(u32::MAX));
+        assert_eq!(checked_precision(below, 0).unwrap() as u64, bits);
+        assert_eq!(required_precision(below) as u64, bits);
+        
+        let above = 1_293_000_000;
+        let bits = required_precision_bits(above, 0);
+        assert!(bits > u64::from(u32::MAX));
+        match checked_precision(above, 0) {
+            Err(ComputeError::PrecisionTooLarge { digits, bits: reported, .. }) => {
+                assert_eq!((digits, reported), (above, bits));
+            }
+            other => panic!("应返回 PrecisionTooLarge: {:?}", other),
+        }
+        assert_eq!(required_precision(above), u32::MAX);
+        assert!(matches!(
+            compute_pi(Algorithm::Bbp, above, 1),
+            Err(ComputeError::PrecisionTooLarge { .. })
+        ));
+    }
+}
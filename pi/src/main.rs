@@ -1,323 +1,489 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use std::thread;
-use std::time::Instant;
-use std::io::{self, Write};
-use rug::{Float, Assign};
-use rug::ops::Pow;
-use num_cpus;
+use std::time::Duration;
+use indicatif::{ProgressBar, ProgressStyle};
+use pi_calculator::{
+    append_pi_digits, bench_with_threads, binary_digits_for, binary_split_bit_lengths,
+    check_feasibility, checkpoint_path, chudnovsky_convergence, chudnovsky_terms, compression_ratio,
+    compute_pi_bellard, compute_pi_binary_split_cancellable, compute_pi_checkpointed,
+    compute_pi_chudnovsky, compute_pi_gauss_legendre_cancellable, cpu_count, create_file,
+    default_threads, digit_statistics, digits_per_sec, double_check, dump_triples, explain,
+    extract_digits, find_sequence, fit_to_memory, fraction_digits, guaranteed_digits, parse_algorithm_choice,
+    pi_string, print_digit_statistics, print_memory_stats, print_summary, print_summary_table,
+    prompt_usize, recommended_threads, required_precision, run, run_streaming, sanity_check, self_test,
+    verify_against_file, verify_file, verify_pi_accuracy, window, write_pi_binary,
+    write_pi_csv_positions, write_pi_decimated, write_pi_for_validation, write_pi_raw,
+    write_pi_split, write_pi_to_file_chunked, write_sha256_sidecar, Algorithm, CheckpointOptions,
+    ComputeOptions, Config, FactorialStrategy, FormatOptions, OutputMode, RunSummary, WriteOptions,
+    DEFAULT_BUFFER_SIZE, DEFAULT_CHECKPOINT_INTERVAL, DEFAULT_MAX_MEMORY_BYTES, DEFAULT_MAX_RUNTIME,
+    MAX_DUMP_TRIPLES_TERMS, RANDOM_DIGITS_COMPRESSION_RATIO, SELF_TEST_DIGITS,
+};
 
-// 内存优化的 BBP 公式项计算
-// 重用 Float 对象以减少内存分配
-struct BBPCalculator {
-    // 预分配的临时变量，避免每次计算都重新分配
-    term1: Float,
-    term2: Float,
-    term3: Float,
-    term4: Float,
-    eight_k: Float,
-    sixteen_pow_k: Float,
-    denominator1: Float,
-    denominator2: Float,
-    denominator3: Float,
-    denominator4: Float,
-    one_over_16: Float,
-    sixteen: Float,
+// 命令行选项
+#[derive(Default)]
+struct CliOptions {
+    // 内存上限（MB）
+    max_memory_mb: Option<f64>,
+    // 每个分片文件的小数位数
+    split_size: Option<usize>,
+    // 写入文件时的缓冲区大小（字节），命令行中以 KB 指定
+    buffer_size: Option<usize>,
+    // 从已有的检查点继续计算
+    resume: bool,
+    // 每计算多少项保存一次检查点
+    checkpoint_interval: Option<usize>,
+    // 用更高精度再算一次，确认所有位一致
+    double_check: bool,
+    // 只计时计算本身，不验证、不写文件
+    bench: bool,
+    // 写入后生成小数部分数字流的 SHA-256 文件
+    sha256: bool,
+    // 额外计算的十进制保护位数
+    guard_digits: Option<usize>,
+    // 实际计算的位数，输出仍按输入的位数截断
+    compute_digits: Option<usize>,
+    // 预计耗时上限（秒）
+    max_runtime_secs: Option<f64>,
+    // 预计耗时或内存超出上限时仍然计算
+    force: bool,
+    // 批量模式：依次计算多个位数，各自写入 pi_<位数>.txt
+    digits_list: Option<Vec<usize>>,
+    // 只输出小数第 N、2N、... 位及其位置
+    decimate: Option<usize>,
+    // 报告小数部分数字流的 gzip 压缩比
+    compression: bool,
+    // 用每种算法计算 1000 位并交叉比较，之后退出
+    self_test: bool,
+    // Chudnovsky 算法计算阶乘的方式
+    factorial_strategy: Option<FactorialStrategy>,
+    // 输出格式，指定后不再询问
+    output_mode: Option<OutputMode>,
+    // 单线程时，某项低于最低有效位后不再累加剩余的项
+    early_exit: bool,
+    // 输出进制，目前支持 10（默认）和 2
+    radix: Option<u32>,
+    // 向已有的标准格式输出文件追加新的位数，而不是重写
+    append: bool,
+    // 只打印所用公式、项数和精度，不计算
+    explain: bool,
+    // 直接指定的二进制精度，代替按位数推导的精度
+    precision: Option<u32>,
+    // 用 Chudnovsky 级数的部分和演示收敛过程，不写文件
+    show_convergence: bool,
+    // 不显示进度
+    quiet: bool,
+    // 将工作线程绑定到不同的 CPU 核心
+    pin_threads: bool,
+    // 把二进分割的中间结果 (P, Q, T) 写入文件，不计算 π
+    dump_triples: bool,
+    // 打印二进分割中 P、Q、T 的位数，不计算 π
+    bit_lengths: bool,
+    // 指定算法，用于不经过交互式输入的 --bench
+    algorithm: Option<Algorithm>,
+    // 用二进分割计算后按块写入文件，不构造完整的数字串
+    stream: bool,
 }
 
-impl BBPCalculator {
-    fn new(precision: u32) -> Self {
-        let prec = precision;
-        Self {
-            term1: Float::with_val(prec, 0),
-            term2: Float::with_val(prec, 0),
-            term3: Float::with_val(prec, 0),
-            term4: Float::with_val(prec, 0),
-            eight_k: Float::with_val(prec, 0),
-            sixteen_pow_k: Float::with_val(prec, 0),
-            denominator1: Float::with_val(prec, 1),
-            denominator2: Float::with_val(prec, 4),
-            denominator3: Float::with_val(prec, 5),
-            denominator4: Float::with_val(prec, 6),
-            one_over_16: Float::with_val(prec, 1) / 16,
-            sixteen: Float::with_val(prec, 16),
-        }
-    }
-    
-    // 计算 BBP 公式的单项
-    fn compute_term(&mut self, k: usize) -> &Float {
-        // 计算 8k
-        self.eight_k.assign(8 * k);
-        
-        // 计算分母
-        self.denominator1.assign(1u8);
-        self.denominator1 += &self.eight_k;
-        
-        self.denominator2.assign(4u8);
-        self.denominator2 += &self.eight_k;
-        
-        self.denominator3.assign(5u8);
-        self.denominator3 += &self.eight_k;
-        
-        self.denominator4.assign(6u8);
-        self.denominator4 += &self.eight_k;
-        
-        // 计算 4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6)
-        self.term1.assign(4u8);
-        self.term1 /= &self.denominator1;
-        
-        self.term2.assign(2u8);
-        self.term2 /= &self.denominator2;
-        
-        self.term3.assign(1u8);
-        self.term3 /= &self.denominator3;
-        
-        self.term4.assign(1u8);
-        self.term4 /= &self.denominator4;
-        
-        // 合并项
-        self.term1 -= &self.term2;
-        self.term1 -= &self.term3;
-        self.term1 -= &self.term4;
-        
-        // 计算 16^(-k)
-        if k == 0 {
-            self.sixteen_pow_k.assign(1u8);
-        } else if k == 1 {
-            self.sixteen_pow_k.assign(&self.one_over_16);
-        } else {
-            // 通过克隆来计算幂运算，避免所有权问题
-            let sixteen_clone = self.sixteen.clone();
-            let pow_result = sixteen_clone.pow(k as i32);
-            self.sixteen_pow_k.assign(1u8);
-            self.sixteen_pow_k /= pow_result;
+// 解析命令行参数
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<CliOptions, String> {
+    let mut options = CliOptions::default();
+    
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-memory" => {
+                let value = args.next().ok_or("--max-memory 需要一个参数 (MB)")?;
+                match value.parse::<f64>() {
+                    Ok(mb) if mb > 0.0 => options.max_memory_mb = Some(mb),
+                    _ => return Err(format!("无效的内存上限: {}", value)),
+                }
+            }
+            "--split-size" => {
+                let value = args.next().ok_or("--split-size 需要一个参数 (位数)")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n >= 1 => options.split_size = Some(n),
+                    _ => return Err(format!("无效的分片大小: {}", value)),
+                }
+            }
+            "--buffer-size" => {
+                let value = args.next().ok_or("--buffer-size 需要一个参数 (KB)")?;
+                match value.parse::<usize>() {
+                    Ok(kb) if kb >= 1 => options.buffer_size = Some(kb * 1024),
+                    _ => return Err(format!("无效的缓冲区大小: {}", value)),
+                }
+            }
+            "--continue" => options.resume = true,
+            "--double-check" => options.double_check = true,
+            "--bench" => options.bench = true,
+            "--dump-triples" => options.dump_triples = true,
+            "--bit-lengths" => options.bit_lengths = true,
+            "--sha256" => options.sha256 = true,
+            "--compression" => options.compression = true,
+            "--self-test" => options.self_test = true,
+            "--early-exit" => options.early_exit = true,
+            "--append" => options.append = true,
+            "--explain" => options.explain = true,
+            "--show-convergence" => options.show_convergence = true,
+            "--quiet" => options.quiet = true,
+            "--pin-threads" => options.pin_threads = true,
+            "--force" => options.force = true,
+            "--stream" => options.stream = true,
+            "--max-runtime" => {
+                let value = args.next().ok_or("--max-runtime 需要一个参数 (秒)")?;
+                match value.parse::<f64>() {
+                    Ok(secs) if secs > 0.0 && secs.is_finite() => {
+                        options.max_runtime_secs = Some(secs)
+                    }
+                    _ => return Err(format!("无效的耗时上限: {}", value)),
+                }
+            }
+            "--guard-digits" => {
+                let value = args.next().ok_or("--guard-digits 需要一个参数 (位数)")?;
+                match value.parse::<usize>() {
+                    Ok(n) => options.guard_digits = Some(n),
+                    _ => return Err(format!("无效的保护位数: {}", value)),
+                }
+            }
+            "--digits" => {
+                let value = args.next().ok_or("--digits 需要一个参数 (如 100,1000,10000)")?;
+                match parse_digits_list(&value) {
+                    Some(list) => options.digits_list = Some(list),
+                    None => return Err(format!("无效的位数列表: {}", value)),
+                }
+            }
+            "--factorial" => {
+                let value = args.next().ok_or("--factorial 需要一个参数 (naive/gmp/incremental)")?;
+                match FactorialStrategy::from_name(&value) {
+                    Some(strategy) => options.factorial_strategy = Some(strategy),
+                    None => return Err(format!("未知的阶乘策略: {}", value)),
+                }
+            }
+            "--format" => {
+                let value = args.next().ok_or("--format 需要一个参数 (如 csv-positions)")?;
+                options.output_mode = Some(match value.as_str() {
+                    "standard" => OutputMode::Standard,
+                    "raw" => OutputMode::Raw,
+                    "validation" => OutputMode::Validation,
+                    "csv-positions" => OutputMode::CsvPositions,
+                    _ => return Err(format!("未知的输出格式: {}", value)),
+                });
+            }
+            "--precision" => {
+                let value = args.next().ok_or("--precision 需要一个参数 (二进制位数)")?;
+                let valid = rug::float::prec_min()..=rug::float::prec_max();
+                match value.parse::<u32>() {
+                    Ok(bits) if valid.contains(&bits) => options.precision = Some(bits),
+                    _ => return Err(format!("无效的精度: {}", value)),
+                }
+            }
+            "--radix" => {
+                let value = args.next().ok_or("--radix 需要一个参数 (2 或 10)")?;
+                match value.parse::<u32>() {
+                    Ok(radix @ (2 | 10)) => options.radix = Some(radix),
+                    _ => return Err(format!("不支持的进制: {}，目前只支持 2 和 10", value)),
+                }
+            }
+            "--decimate" => {
+                let value = args.next().ok_or("--decimate 需要一个参数 (间隔位数)")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n >= 1 => options.decimate = Some(n),
+                    _ => return Err(format!("无效的抽样间隔: {}", value)),
+                }
+            }
+            "--compute-digits" => {
+                let value = args.next().ok_or("--compute-digits 需要一个参数 (位数)")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n >= 1 => options.compute_digits = Some(n),
+                    _ => return Err(format!("无效的计算位数: {}", value)),
+                }
+            }
+            "--algorithm" => {
+                let value = args.next().ok_or("--algorithm 需要一个参数 (如 chudnovsky)")?;
+                options.algorithm = Some(value.parse::<Algorithm>().map_err(|e| e.to_string())?);
+            }
+            "--checkpoint-interval" => {
+                let value = args.next().ok_or("--checkpoint-interval 需要一个参数 (项数)")?;
+                match value.parse::<usize>() {
+                    Ok(n) if n >= 1 => options.checkpoint_interval = Some(n),
+                    _ => return Err(format!("无效的检查点间隔: {}", value)),
+                }
+            }
+            _ => return Err(format!("未知参数: {}", arg)),
         }
-        
-        // 乘以 16^(-k)
-        self.term1 *= &self.sixteen_pow_k;
-        
-        &self.term1
     }
+    
+    Ok(options)
 }
 
-// 优化的 BBP 公式并行计算
-fn compute_pi_optimized(digits: usize, num_threads: usize) -> (Float, f64) {
-    println!("使用 {} 个线程计算 π 到 {} 位有效数字...", num_threads, digits);
-    
-    let start = Instant::now();
-    
-    // 计算所需精度（二进制位）
-    // 1 位十进制 ≈ log2(10) ≈ 3.32193 位二进制
-    let precision = ((digits as f64) * 3.32193).ceil() as u32 + 10;
-    
-    // 计算需要多少项才能达到所需精度
-    // BBP 公式每项贡献约 4 位二进制位
-    let terms_needed = (precision as usize) / 4 + 10;
-    
-    println!("精度: {} 位二进制", precision);
-    println!("需要计算 {} 项...", terms_needed);
-    
-    // 用于分发任务的原子计数器
-    let counter = Arc::new(AtomicUsize::new(0));
-    
-    // 存储线程句柄的向量
-    let mut handles = Vec::with_capacity(num_threads);
+// 命令行中影响计算过程的选项
+fn compute_options(options: &CliOptions) -> ComputeOptions {
+    ComputeOptions {
+        pin_threads: options.pin_threads,
+        factorial_strategy: options.factorial_strategy.unwrap_or_default(),
+        early_exit: options.early_exit,
+        precision: options.precision,
+    }
+}
+
+// 解析逗号分隔的位数列表，每项必须是正整数
+fn parse_digits_list(value: &str) -> Option<Vec<usize>> {
+    value
+        .split(',')
+        .map(|item| match item.trim().parse::<usize>() {
+            Ok(n) if n >= 1 => Some(n),
+            _ => None,
+        })
+        .collect()
+}
+
+// 未用 --digits 指定位数时基准测试计算的位数，与交互式输入的默认值相同
+const DEFAULT_BENCH_DIGITS: usize = 1000;
+
+// 基准测试：不经过交互式输入，依次计算 --digits 中的各个位数，只报告耗时和速度
+// 算法由 --algorithm 指定，否则按位数选择；不预览、不验证、不写文件
+fn run_bench(options: &CliOptions) {
+    let digits_list = options.digits_list.clone().unwrap_or_else(|| vec![DEFAULT_BENCH_DIGITS]);
     
-    // 为每个线程预分配 BBP 计算器
-    for _ in 0..num_threads {
-        let counter = Arc::clone(&counter);
+    for digits in digits_list {
+        let algorithm = options.algorithm.unwrap_or_else(|| Algorithm::default_for(digits));
+        let num_threads = recommended_threads(algorithm, digits);
+        check_limits(options, algorithm, digits, num_threads);
         
-        let handle = thread::spawn(move || {
-            // 每个线程创建自己的 BBP 计算器，避免线程间的内存竞争
-            let mut calculator = BBPCalculator::new(precision);
-            let mut local_sum = Float::with_val(precision, 0);
-            
-            loop {
-                // 获取下一个要计算的 k
-                let k = counter.fetch_add(1, Ordering::SeqCst);
-                if k >= terms_needed {
-                    break;
-                }
-                
-                // 计算单项并累加
-                let term = calculator.compute_term(k);
-                local_sum += term;
+        let elapsed = match bench_with_threads(algorithm, digits, num_threads) {
+            Ok(elapsed) => elapsed.as_secs_f64(),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
             }
-            
-            // 返回局部和
-            local_sum
-        });
-        
-        handles.push(handle);
+        };
+        println!("\n算法: {}，线程数: {}，位数: {}", algorithm, num_threads, digits);
+        println!("耗时: {:.3} 秒", elapsed);
+        println!("速度: {:.2} 位/秒", digits as f64 / elapsed);
     }
-    
-    // 收集并合并所有线程的结果
-    let mut final_result = Float::with_val(precision, 0);
-    for handle in handles {
-        let thread_sum = handle.join().unwrap();
-        final_result += thread_sum;
+}
+
+// 预计耗时或内存超出上限时拒绝计算并以 1 退出，除非指定 --force
+fn check_limits(options: &CliOptions, algorithm: Algorithm, digits: usize, num_threads: usize) {
+    let max_runtime = options.max_runtime_secs.map_or(DEFAULT_MAX_RUNTIME, Duration::from_secs_f64);
+    let max_memory = options.max_memory_mb
+        .map_or(DEFAULT_MAX_MEMORY_BYTES, |mb| (mb * 1024.0 * 1024.0) as u64);
+    if let Err(e) = check_feasibility(algorithm, digits, num_threads, max_runtime, max_memory) {
+        if options.force {
+            eprintln!("⚠️  {}，按 --force 继续计算", e);
+        } else {
+            eprintln!("{}", e);
+            eprintln!("如确需计算，请使用 --force");
+            std::process::exit(1);
+        }
     }
-    
-    let duration = start.elapsed().as_secs_f64();
-    println!("计算完成，耗时: {:.2} 秒", duration);
-    
-    (final_result, duration)
 }
 
-// 分块写入文件，避免内存中保存完整的 π 字符串
-fn write_pi_to_file_chunked(
-    pi: &Float, 
-    digits: usize, 
-    filename: &str,
-    progress_callback: Option<Box<dyn Fn(usize, usize)>>
-) -> io::Result<()> {
-    println!("将结果分块写入文件 {}...", filename);
-    let start = Instant::now();
-    
-    // 打开文件
-    let file = std::fs::File::create(filename)?;
-    let mut writer = io::BufWriter::new(file);
-    
-    // 写入头信息
-    writeln!(writer, "π 的前 {} 位有效数字", digits)?;
-    writeln!(writer, "计算时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"))?;
-    writeln!(writer, "{}", "=".repeat(80))?;
-    
-    // 首先获取整个 π 的字符串表示
-    println!("正在将 π 转换为字符串...");
-    let pi_str = pi.to_string_radix(10, Some(digits));
-    
-    // 分块处理：每次处理一定数量的位数
-    let chunk_size = 1000;  // 每块 1000 位
-    let total_chunks = (digits + chunk_size - 1) / chunk_size;
-    
-    // 写入文件
-    for chunk in 0..total_chunks {
-        let start_pos = chunk * chunk_size;
-        let end_pos = std::cmp::min((chunk + 1) * chunk_size, pi_str.len());
-        
-        if start_pos < pi_str.len() {
-            let chunk_str = &pi_str[start_pos..end_pos];
-            
-            // 格式化输出：每 50 个数字一行，每 10 个数字一组
-            let mut formatted = String::new();
-            let mut pos_in_chunk = 0;
-            
-            while pos_in_chunk < chunk_str.len() {
-                let remaining = chunk_str.len() - pos_in_chunk;
-                let take = std::cmp::min(10, remaining);
-                
-                formatted.push_str(&chunk_str[pos_in_chunk..pos_in_chunk + take]);
-                pos_in_chunk += take;
-                
-                if pos_in_chunk % 50 == 0 && pos_in_chunk < chunk_str.len() {
-                    formatted.push('\n');
-                } else if pos_in_chunk < chunk_str.len() {
-                    formatted.push(' ');
-                }
+// 按内存上限调整线程数，单线程仍超出时改用内存占用更低的算法；仍然超出时以 1 退出
+// keep_algorithm 为 true 时只能减少线程数，需要换用其他算法时以 1 退出；未指定 --max-memory 时原样返回
+fn fit_memory_limit(
+    options: &CliOptions,
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+    keep_algorithm: bool,
+) -> (Algorithm, usize) {
+    let Some(max_memory_mb) = options.max_memory_mb else {
+        return (algorithm, num_threads);
+    };
+    let max_bytes = (max_memory_mb * 1024.0 * 1024.0) as u64;
+    match fit_to_memory(algorithm, digits, num_threads, max_bytes) {
+        Ok((fitted, _)) if keep_algorithm && fitted != algorithm => {
+            eprintln!("内存上限 {} MB 不足以用 {} 计算 {} 位", max_memory_mb, algorithm, digits);
+            std::process::exit(1);
+        }
+        Ok((fitted, threads)) => {
+            if fitted != algorithm {
+                println!("内存上限 {} MB，算法由 {} 改为 {}", max_memory_mb, algorithm, fitted);
             }
-            
-            if !formatted.is_empty() {
-                writeln!(writer, "{}", formatted)?;
+            if threads < num_threads {
+                println!("内存上限 {} MB，线程数由 {} 减少为 {}", max_memory_mb, num_threads, threads);
             }
+            (fitted, threads)
         }
-        
-        // 报告进度
-        if let Some(callback) = &progress_callback {
-            callback(chunk + 1, total_chunks);
-        }
-        
-        if (chunk + 1) % 10 == 0 {
-            println!("已写入 {}/{} 块...", chunk + 1, total_chunks);
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
     }
+}
+
+// 批量模式不支持的选项，指定了其中任何一个时返回其名称
+fn unsupported_batch_flag(options: &CliOptions) -> Option<&'static str> {
+    [
+        (options.stream, "--stream"),
+        (options.sha256, "--sha256"),
+        (options.double_check, "--double-check"),
+        (options.split_size.is_some(), "--split-size"),
+        (options.decimate.is_some(), "--decimate"),
+        (options.radix.is_some(), "--radix"),
+        (options.append, "--append"),
+        (options.resume, "--continue"),
+        (options.checkpoint_interval.is_some(), "--checkpoint-interval"),
+        (options.compute_digits.is_some(), "--compute-digits"),
+        (options.buffer_size.is_some(), "--buffer-size"),
+        (options.compression, "--compression"),
+        (options.show_convergence, "--show-convergence"),
+        (options.dump_triples, "--dump-triples"),
+        (options.bit_lengths, "--bit-lengths"),
+    ]
+    .into_iter()
+    .find_map(|(set, flag)| set.then_some(flag))
+}
+
+// 批量计算多个位数，共用同一组命令行选项，最后显示汇总表
+// 算法由 --algorithm 指定，否则按位数选择；每个位数都按内存上限调整，并检查耗时和内存上限
+// 批量模式不支持的选项以 2 退出，不计算任何位数
+fn run_batch(digits_list: &[usize], options: &CliOptions) {
+    if let Some(flag) = unsupported_batch_flag(options) {
+        eprintln!("批量模式 (--digits) 不支持 {}", flag);
+        std::process::exit(2);
+    }
     
-    // 写入统计信息
-    writeln!(writer, "\n{}", "=".repeat(80))?;
-    writeln!(writer, "统计信息:")?;
-    writeln!(writer, "总位数: {}", digits)?;
-    
-    writer.flush()?;
-    
-    let duration = start.elapsed().as_secs_f64();
-    println!("写入完成，耗时: {:.2} 秒", duration);
+    let mut summaries = Vec::with_capacity(digits_list.len());
     
-    // 获取文件大小
-    if let Ok(metadata) = std::fs::metadata(filename) {
-        println!("文件大小: {:.2} KB", metadata.len() as f64 / 1024.0);
+    for &digits in digits_list {
+        let mut config = Config::new(digits);
+        config.output_file = format!("pi_{}.txt", digits);
+        if let Some(guard_digits) = options.guard_digits {
+            config.guard_digits = guard_digits;
+        }
+        if let Some(output_mode) = options.output_mode {
+            config.output_mode = output_mode;
+        }
+        config.options = compute_options(options);
+        if let Some(algorithm) = options.algorithm {
+            config.algorithm = algorithm;
+            config.num_threads = recommended_threads(algorithm, digits);
+        }
+        
+        if options.explain {
+            print_explanation(config.algorithm, config.compute_digits());
+            continue;
+        }
+        
+        let compute_digits = config.compute_digits();
+        let (algorithm, num_threads) =
+            fit_memory_limit(options, config.algorithm, compute_digits, config.num_threads, false);
+        config.algorithm = algorithm;
+        config.num_threads = num_threads;
+        check_limits(options, algorithm, compute_digits, num_threads);
+        
+        println!("\n计算 π 到 {} 位 ({}, {} 个线程)...", digits, config.algorithm, config.num_threads);
+        match run(&config) {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => eprintln!("计算 {} 位失败: {}", digits, e),
+        }
     }
     
-    Ok(())
+    print_summary_table(&summaries);
 }
 
-// 计算并显示内存使用统计
-fn print_memory_stats(digits: usize, precision: u32, num_threads: usize) {
-    println!("\n内存使用估算:");
-    println!("{}", "-".repeat(40));
-    
-    // 每个 Float 的内存占用（字节）= 精度（位）/ 8
-    let float_size_bytes = precision as f64 / 8.0;
-    
-    // 线程内存占用
-    let thread_memory_mb = (num_threads as f64) * float_size_bytes / 1024.0 / 1024.0;
-    
-    // 结果内存占用
-    let result_memory_mb = float_size_bytes / 1024.0 / 1024.0;
-    
-    // 总内存占用估算
-    let total_memory_mb = (num_threads as f64 + 1.0) * float_size_bytes / 1024.0 / 1024.0;
-    
-    println!("计算位数: {} 位十进制", digits);
-    println!("精度: {} 位二进制", precision);
-    println!("每个高精度浮点数: {:.2} MB", float_size_bytes / 1024.0 / 1024.0);
-    println!("线程内存: {:.2} MB ({} 个线程)", thread_memory_mb, num_threads);
-    println!("结果内存: {:.2} MB", result_memory_mb);
-    println!("总估算内存: {:.2} MB", total_memory_mb);
-    
-    if total_memory_mb > 100.0 {
-        println!("⚠️  警告: 内存使用可能较高，考虑减少线程数或位数");
+// 打印计算 digits 位所用的公式和参数，位数超出范围时以 1 退出
+fn print_explanation(algorithm: Algorithm, digits: usize) {
+    match explain(algorithm, digits) {
+        Ok(text) => print!("\n{}", text),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
     }
 }
 
-// 验证 π 值的准确性
-fn verify_pi_accuracy(pi_str: &str, digits: usize) -> (bool, usize) {
-    // 已知的 π 前 100 位
-    let known_pi = "3.1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
-    
-    // 去掉小数点进行比较
-    let known_digits: Vec<char> = known_pi.chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect();
-    
-    let computed_digits: Vec<char> = pi_str.chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect();
-    
-    // 比较前 min(100, digits) 位
-    let compare_len = std::cmp::min(100, digits);
-    let compare_len = std::cmp::min(compare_len, known_digits.len());
-    let compare_len = std::cmp::min(compare_len, computed_digits.len());
-    
-    let mut first_error = None;
-    for i in 0..compare_len {
-        if computed_digits[i] != known_digits[i] {
-            first_error = Some(i);
-            break;
+// 收敛演示最多打印的行数
+const CONVERGENCE_STEPS: usize = 20;
+
+// 每行显示的近似值小数位数
+const CONVERGENCE_PREVIEW_DIGITS: usize = 20;
+
+// 逐块累加 Chudnovsky 级数，打印每块之后的近似值和正确位数
+fn print_convergence(digits: usize) {
+    let terms = match chudnovsky_terms(digits) {
+        Ok(terms) => terms,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
-    }
+    };
+    let block = terms.div_ceil(CONVERGENCE_STEPS).max(1);
     
-    let accurate = first_error.is_none();
-    (accurate, first_error.unwrap_or(compare_len))
+    println!("\nChudnovsky 级数的收敛过程（每 {} 项）:", block);
+    match chudnovsky_convergence(digits, block) {
+        Ok(steps) => {
+            for step in steps {
+                let preview = pi_string(&step.value, CONVERGENCE_PREVIEW_DIGITS.min(digits));
+                println!("{} 项后: {}... 正确 {} 位", step.terms, preview, step.correct_digits);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 // 获取用户输入的函数
-fn get_user_input() -> (usize, usize, String) {
+fn get_user_input(output_mode: Option<OutputMode>) -> Config {
     println!("π 计算器 (内存优化并行版本)");
     println!("{}", "=".repeat(50));
     
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout();
+    
     // 获取计算位数
-    let digits = loop {
-        print!("请输入要计算的 π 的位数 (1-1,000,000, 默认 1000): ");
+    let digits = prompt_usize(
+        "请输入要计算的 π 的位数 (1-1,000,000, 默认 1000): ",
+        1,
+        1_000_000,
+        1000,
+        &mut stdin,
+        &mut stdout,
+    );
+    
+    // 后面的提示直接读取 io::stdin()，先释放锁
+    drop(stdin);
+    
+    // 获取算法
+    let default_algorithm = Algorithm::default_for(digits);
+    let algorithm = loop {
+        println!("可选算法:");
+        for (i, algorithm) in Algorithm::all().iter().enumerate() {
+            let range = algorithm.recommended_digits();
+            println!("  {} = {} (推荐 {}-{} 位)", i + 1, algorithm, range.start(), range.end());
+        }
+        print!("请选择算法 (默认 {}): ", default_algorithm);
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        
+        match parse_algorithm_choice(&input, default_algorithm) {
+            Some(algorithm) => break algorithm,
+            None => println!("请输入 1-{} 或算法名称", Algorithm::all().len()),
+        }
+    };
+    
+    // 获取线程数，默认值按所选算法和位数推荐
+    let default_threads = recommended_threads(algorithm, digits);
+    let max_threads = cpu_count().max(default_threads);
+    let num_threads = prompt_usize(
+        &format!("请输入线程数 (1-{}, 默认 {}): ", max_threads, default_threads),
+        1,
+        max_threads,
+        default_threads,
+        &mut io::stdin().lock(),
+        &mut stdout,
+    );
+    
+    // 获取输出文件名
+    let filename = format!("pi_{}_digits.txt", digits);
+    let output_file = loop {
+        print!("请输入输出文件名 (默认 {}): ", filename);
         io::stdout().flush().unwrap();
         
         let mut input = String::new();
@@ -325,20 +491,58 @@ fn get_user_input() -> (usize, usize, String) {
         let input = input.trim();
         
         if input.is_empty() {
-            break 1000;  // 默认值
+            break filename;
+        } else {
+            break input.to_string();
         }
+    };
+    
+    // 获取输出格式，命令行已指定时不再询问
+    let output_mode = loop {
+        if let Some(output_mode) = output_mode {
+            break output_mode;
+        }
+        print!("请选择输出格式 (1 = 标准格式, 2 = raw 仅小数位, 3 = raw + 校验信息, 4 = 位置,数字 CSV, 默认 1): ");
+        io::stdout().flush().unwrap();
         
-        match input.parse::<usize>() {
-            Ok(n) if n >= 1 && n <= 1_000_000 => break n,
-            Ok(_) => println!("位数必须在 1 到 1,000,000 之间"),
-            Err(_) => println!("请输入有效的数字"),
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        
+        match input.trim() {
+            "" | "1" => break OutputMode::Standard,
+            "2" => break OutputMode::Raw,
+            "3" => break OutputMode::Validation,
+            "4" => break OutputMode::CsvPositions,
+            _ => println!("请输入 1、2、3 或 4"),
         }
     };
     
-    // 获取线程数
-    let max_threads = num_cpus::get();
-    let num_threads = loop {
-        print!("请输入线程数 (1-{}, 默认 {}): ", max_threads, max_threads);
+    // 是否显示数字分布统计
+    print!("是否显示数字分布统计 (y/N): ");
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let show_statistics = matches!(input.trim(), "y" | "Y");
+    
+    Config {
+        digits,
+        num_threads,
+        algorithm,
+        output_file,
+        output_mode,
+        show_statistics,
+        guard_digits: 0,
+        options: ComputeOptions::default(),
+    }
+}
+
+// 交互式浏览：输入位置，显示其附近的数字
+fn explore_digits(pi_str: &str) {
+    const RADIUS: usize = 20;
+    
+    loop {
+        print!("\n输入小数点后的位置 (从 0 开始) 查看附近的数字 (直接回车结束): ");
         io::stdout().flush().unwrap();
         
         let mut input = String::new();
@@ -346,20 +550,28 @@ fn get_user_input() -> (usize, usize, String) {
         let input = input.trim();
         
         if input.is_empty() {
-            break max_threads;  // 默认值
+            break;
         }
         
         match input.parse::<usize>() {
-            Ok(n) if n >= 1 && n <= max_threads => break n,
-            Ok(_) => println!("线程数必须在 1 到 {} 之间", max_threads),
+            Ok(center) => {
+                let start = center.saturating_sub(RADIUS);
+                let digits = window(pi_str, center, RADIUS);
+                if digits.is_empty() {
+                    println!("位置超出已计算的范围");
+                } else {
+                    println!("第 {} 位起: {}", start, digits);
+                }
+            }
             Err(_) => println!("请输入有效的数字"),
         }
-    };
-    
-    // 获取输出文件名
-    let filename = format!("pi_{}_digits.txt", digits);
-    let output_file = loop {
-        print!("请输入输出文件名 (默认 {}): ", filename);
+    }
+}
+
+// 交互式查找：输入一串数字，显示它在小数部分第一次出现的位置
+fn search_digits(pi_str: &str) {
+    loop {
+        print!("\n输入要查找的数字串，如生日 0314 (直接回车结束): ");
         io::stdout().flush().unwrap();
         
         let mut input = String::new();
@@ -367,75 +579,531 @@ fn get_user_input() -> (usize, usize, String) {
         let input = input.trim();
         
         if input.is_empty() {
-            break filename;
-        } else {
-            break input.to_string();
+            break;
+        }
+        
+        if !input.bytes().all(|b| b.is_ascii_digit()) {
+            println!("请输入只包含 0-9 的数字串");
+            continue;
+        }
+        
+        match find_sequence(pi_str, input) {
+            Some(pos) => println!("\"{}\" 首次出现在小数点后第 {} 位 (从 0 开始)", input, pos),
+            None => println!("在已计算的 {} 位小数中未找到 \"{}\"", pi_str.len().saturating_sub(2), input),
+        }
+    }
+}
+
+// 与之前保存的结果文件比较
+fn compare_with_file(pi_str: &str) {
+    loop {
+        print!("\n输入之前保存的 π 文件路径进行比较 (直接回车跳过): ");
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        
+        if input.is_empty() {
+            break;
+        }
+        
+        match verify_against_file(pi_str, Some(Path::new(input))) {
+            Ok(comparison) => match comparison.first_mismatch {
+                None => println!("✓ 前 {} 位小数完全一致", comparison.compared),
+                Some(position) => println!("✗ 小数点后第 {} 位开始不一致", position),
+            },
+            Err(e) => println!("无法读取文件 {}: {}", input, e),
+        }
+    }
+}
+
+// 把 digits 位所需项数的二进分割中间结果写入 pi_<位数>_triples.txt，项数超出上限时以 1 退出
+fn write_triples(digits: usize, write_options: &WriteOptions) {
+    let terms = match chudnovsky_terms(digits) {
+        Ok(terms) => terms as u64,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
     };
+    if terms > MAX_DUMP_TRIPLES_TERMS {
+        eprintln!("{} 位需要 {} 项，--dump-triples 最多支持 {} 项", digits, terms, MAX_DUMP_TRIPLES_TERMS);
+        std::process::exit(1);
+    }
     
-    (digits, num_threads, output_file)
+    let filename = format!("pi_{}_triples.txt", digits);
+    let result = create_file(&filename, &write_options.retry).and_then(|file| {
+        let mut writer = io::BufWriter::with_capacity(write_options.buffer_size, file);
+        let nodes = dump_triples(terms, &mut writer)?;
+        writer.flush()?;
+        Ok(nodes)
+    });
+    match result {
+        Ok(nodes) => println!("已将 {} 个 (P, Q, T) 节点写入 {}", nodes, filename),
+        Err(e) => {
+            eprintln!("输出中间结果失败: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// 打印 digits 位所需项数的二进分割中 P、Q、T 的位数，位数超出范围时以 1 退出
+fn print_bit_lengths(digits: usize) {
+    let stats = match binary_split_bit_lengths(digits) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    println!("\n二进分割整数位数 ({} 项):", stats.terms);
+    println!("{}", "-".repeat(52));
+    println!("最终 P: {} 位, Q: {} 位, T: {} 位", stats.p_bits, stats.q_bits, stats.t_bits);
+    println!("中间结果峰值: {} 位 ({:.2} KB)", stats.peak, stats.peak as f64 / 8.0 / 1024.0);
+    for (depth, bits) in stats.per_depth.iter().enumerate() {
+        println!("  第 {} 层: 最大 {} 位", depth, bits);
+    }
+}
+
+// 进度条，message 显示在进度条前
+fn progress_bar(message: &'static str, unit: &str) -> ProgressBar {
+    let template = format!("{{msg}} [{{bar:40}}] {{pos}}/{{len}} {} ({{percent}}%, 剩余 {{eta}})", unit);
+    let bar = ProgressBar::new(0);
+    bar.set_style(ProgressStyle::with_template(&template).unwrap().progress_chars("=> "));
+    bar.set_message(message);
+    bar
+}
+
+// 默认日志设置：info 级别，只输出消息本身，可通过 RUST_LOG 调整
+fn init_logger() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .init();
 }
 
 fn main() {
+    init_logger();
+    
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    
+    // 自检：全部一致时退出码为 0，否则输出诊断信息并以 1 退出
+    if options.self_test {
+        match self_test(default_threads()) {
+            Ok(()) => {
+                println!("✓ 自检通过: 所有算法的前 {} 位小数一致", SELF_TEST_DIGITS);
+                return;
+            }
+            Err(e) => {
+                eprintln!("✗ {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    // 流式写入只能使用二进分割，指定了其他算法时报错，而不是静默替换
+    if let Some(algorithm) = options.algorithm {
+        if options.stream && algorithm != Algorithm::ChudnovskyBinarySplit {
+            eprintln!(
+                "--stream 只能使用 {}，不能与 --algorithm {} 同时指定",
+                Algorithm::ChudnovskyBinarySplit,
+                algorithm
+            );
+            std::process::exit(2);
+        }
+    }
+    
+    // 基准测试模式：不进入交互式输入，只计算，不预览、不验证、不写文件
+    if options.bench {
+        run_bench(&options);
+        return;
+    }
+    
+    // 批量模式不进入交互式输入
+    if let Some(digits_list) = &options.digits_list {
+        run_batch(digits_list, &options);
+        return;
+    }
+    
     // 获取用户输入
-    let (digits, num_threads, output_file) = get_user_input();
+    let mut config = get_user_input(options.output_mode);
+    config.options = compute_options(&options);
+    if let Some(guard_digits) = options.guard_digits {
+        config.guard_digits = guard_digits;
+    }
+    if let Some(compute_digits) = options.compute_digits {
+        if compute_digits < config.display_digits() {
+            eprintln!("计算位数 {} 小于输出位数 {}", compute_digits, config.display_digits());
+            std::process::exit(2);
+        }
+        config.set_compute_digits(compute_digits);
+    }
+    let compute_digits = config.compute_digits();
+    let Config {
+        digits,
+        mut num_threads,
+        mut algorithm,
+        output_file,
+        output_mode,
+        show_statistics,
+        guard_digits,
+        options: compute_options,
+    } = config;
+    let write_options = WriteOptions {
+        buffer_size: options.buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE),
+        ..WriteOptions::default()
+    };
+    
+    // 说明模式：只打印公式和参数，不计算
+    if options.explain {
+        print_explanation(algorithm, compute_digits);
+        return;
+    }
+    
+    // 收敛演示：只打印部分和的近似值，不写文件
+    if options.show_convergence {
+        print_convergence(compute_digits);
+        return;
+    }
+    
+    // 调试用：只输出二进分割的中间结果
+    if options.dump_triples {
+        write_triples(compute_digits, &write_options);
+        return;
+    }
+    
+    // 只统计二进分割的整数位数
+    if options.bit_lengths {
+        print_bit_lengths(compute_digits);
+        return;
+    }
+    
+    // 流式写入只适用于二进分割，在按内存上限调整之前确定，上限按二进分割检查
+    if options.stream && algorithm != Algorithm::ChudnovskyBinarySplit {
+        println!("流式写入使用 {}，代替所选的 {}", Algorithm::ChudnovskyBinarySplit, algorithm);
+        algorithm = Algorithm::ChudnovskyBinarySplit;
+    }
+    
+    // 按内存上限调整线程数，单线程仍超出时改用内存占用更低的算法；流式写入时不换用其他算法
+    (algorithm, num_threads) =
+        fit_memory_limit(&options, algorithm, compute_digits, num_threads, options.stream);
+    
+    // 预计耗时或内存超出上限时拒绝计算，除非指定 --force
+    check_limits(&options, algorithm, compute_digits, num_threads);
+    
+    // 流式模式：组装出 π 后即释放二进分割的整数，按块提取小数位写入文件，
+    // 不构造完整的数字串，因此不做之后的预览、统计和逐位浏览
+    if options.stream {
+        let config = Config {
+            digits,
+            num_threads,
+            algorithm,
+            output_file,
+            output_mode,
+            show_statistics,
+            guard_digits,
+            options: compute_options,
+        };
+        println!("\n以流式写入计算 π 到小数点后 {} 位 ({}, {} 个线程)...", digits, algorithm, num_threads);
+        match run_streaming(&config) {
+            Ok(summary) => print_summary(&summary),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
     
     println!("\n{}", "=".repeat(50));
-    println!("开始计算 π 到 {} 位有效数字", digits);
+    println!("开始计算 π 到小数点后 {} 位", digits);
+    if guard_digits > 0 {
+        println!("额外计算 {} 位保护位", guard_digits);
+    }
+    println!("算法: {}", algorithm);
     println!("使用 {} 个线程", num_threads);
     println!("输出文件: {}", output_file);
     println!("{}", "=".repeat(50));
     
-    // 计算所需精度
-    let precision = ((digits as f64) * 3.32193).ceil() as u32 + 10;
+    // 计算所需精度，指定了 --precision 时使用指定值
+    let precision = match options.precision {
+        Some(bits) => {
+            let needed = required_precision(compute_digits);
+            if bits < needed {
+                eprintln!("⚠️  指定的精度 {} 位低于 {} 位所需的 {} 位，末尾的数字可能不正确",
+                    bits, compute_digits, needed);
+            }
+            bits
+        }
+        None => required_precision(compute_digits),
+    };
     
     // 显示内存使用统计
-    print_memory_stats(digits, precision, num_threads);
+    print_memory_stats(algorithm, compute_digits, precision, num_threads);
     
     // 计算 π
-    let (pi, compute_time) = compute_pi_optimized(digits, num_threads);
+    let stop = Arc::new(AtomicBool::new(false));
+    let checkpoint = CheckpointOptions {
+        path: checkpoint_path(compute_digits).into(),
+        interval: options.checkpoint_interval.unwrap_or(DEFAULT_CHECKPOINT_INTERVAL),
+        resume: options.resume,
+    };
+    // 仅在终端中显示进度条；只有 BBP 的计算会报告进度，其他算法不显示计算进度条，以免进度条停在 0
+    let show_bars = !options.quiet && io::stdout().is_terminal();
+    let compute_bar = (show_bars && algorithm == Algorithm::Bbp).then(|| progress_bar("计算", "项"));
+    let report = |done: usize, total: usize| {
+        if let Some(bar) = &compute_bar {
+            bar.set_length(total as u64);
+            bar.set_position(done as u64);
+        }
+    };
+    
+    let result = match algorithm {
+        Algorithm::Bbp => compute_pi_checkpointed(
+            compute_digits,
+            num_threads,
+            &stop,
+            &checkpoint,
+            Some(&report),
+            &compute_options,
+        ),
+        Algorithm::Chudnovsky => compute_pi_chudnovsky(compute_digits, num_threads, &compute_options),
+        Algorithm::Bellard => compute_pi_bellard(compute_digits, num_threads, &compute_options),
+        Algorithm::ChudnovskyBinarySplit => {
+            compute_pi_binary_split_cancellable(compute_digits, num_threads, &stop, &compute_options)
+        }
+        Algorithm::GaussLegendre => {
+            compute_pi_gauss_legendre_cancellable(compute_digits, num_threads, &stop, &compute_options)
+        }
+    };
+    if let Some(bar) = &compute_bar {
+        bar.finish_and_clear();
+    }
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let pi = result.value;
+    
+    // 不显示精度无法保证的位
+    let trusted_digits = guaranteed_digits(result.precision, result.terms).max(1);
+    let digits = if trusted_digits < digits {
+        eprintln!(
+            "⚠️  {} 位二进制精度只能保证前 {} 位，输出相应截断",
+            result.precision, trusted_digits
+        );
+        trusted_digits
+    } else {
+        digits
+    };
+    
+    if result.terms > 0 {
+        println!("共累加 {} 项，精度 {} 位二进制", result.terms, result.precision);
+    }
+    if let (Some(first), Some(last)) = (result.throughput.first(), result.throughput.last()) {
+        println!(
+            "吞吐量: 开始 {:.1} 位/秒，结束 {:.1} 位/秒（共 {} 个采样）",
+            first.1,
+            last.1,
+            result.throughput.len()
+        );
+    }
     
     // 显示结果预览
-    println!("\nπ 的前 50 位:");
+    println!("\nπ 的前 50 位小数:");
     println!("{}", "-".repeat(52));
     
-    let preview_str = pi.to_string_radix(10, Some(50));
+    let preview_str = pi_string(&pi, 50);
     println!("{}", preview_str);
     
     // 验证准确性
     println!("\n验证准确性:");
     println!("{}", "-".repeat(52));
     
-    let pi_full_str = pi.to_string_radix(10, Some(digits));
+    let pi_full_str = pi_string(&pi, digits);
     let (accurate, correct_digits) = verify_pi_accuracy(&pi_full_str, digits);
     
     if accurate {
-        println!("✓ 前 {} 位与已知 π 值完全一致", correct_digits);
+        println!("✓ 前 {} 位小数与已知 π 值完全一致", correct_digits);
     } else {
-        println!("✗ 前 {} 位正确，第 {} 位开始出现差异", correct_digits, correct_digits + 1);
+        println!(
+            "✗ 前 {} 位小数正确，小数点后第 {} 位开始出现差异",
+            correct_digits,
+            correct_digits + 1
+        );
+    }
+    
+    // 超出内置常数的部分再与打包的参考数据比较，无需外部文件
+    if accurate && digits > correct_digits {
+        match verify_against_file(&pi_full_str, None) {
+            Ok(comparison) => match comparison.first_mismatch {
+                None => println!("✓ 前 {} 位小数与内置参考数据完全一致", comparison.compared),
+                Some(position) => println!("✗ 与内置参考数据在小数点后第 {} 位开始不一致", position),
+            },
+            Err(e) => println!("无法读取内置参考数据: {}", e),
+        }
+    }
+    
+    // 用更高精度复核全部位数
+    if options.double_check {
+        println!("\n复核全部位数:");
+        println!("{}", "-".repeat(52));
+        
+        match double_check(&pi, digits, num_threads) {
+            Ok(None) => println!("✓ 前 {} 位在更高精度下完全一致", digits),
+            Ok(Some(position)) => {
+                eprintln!("✗ 小数点后第 {} 位在更高精度下不一致", position);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    // 数字分布统计
+    if show_statistics {
+        println!("\n数字分布统计 (小数部分):");
+        println!("{}", "-".repeat(52));
+        
+        print_digit_statistics(&digit_statistics(extract_digits(&pi, digits).into_iter()));
+    }
+    
+    // 压缩比：随机数字无法压缩到 log2(10)/8 以下
+    if options.compression {
+        let ratio = compression_ratio(fraction_digits(&pi, digits));
+        println!(
+            "\ngzip 压缩比: {:.4} (完全随机数字的理论下限 {:.4})",
+            ratio, RANDOM_DIGITS_COMPRESSION_RATIO
+        );
+    }
+    
+    // 写入前确认结果以 3.14159 开头，避免写出错误的大文件
+    if !sanity_check(&pi) {
+        eprintln!("✗ 结果不是以 3.14159 开头，计算过程可能出错，放弃写入文件");
+        std::process::exit(1);
     }
     
     // 写入文件
     println!("\n写入文件...");
     println!("{}", "-".repeat(52));
     
-    let progress_callback = Box::new(|current: usize, total: usize| {
-        if current % 10 == 0 || current == total {
-            let percent = (current as f64 / total as f64 * 100.0) as usize;
-            println!("写入进度: {}/{} ({}%)", current, total, percent);
+    // 拆分为多个分片文件
+    if let Some(split_size) = options.split_size {
+        match write_pi_split(&pi, digits, &output_file, split_size, &write_options) {
+            Ok(parts) => {
+                println!("\n已写入 {} 个分片文件，清单: {}.manifest", parts.len(), output_file);
+                for part in &parts {
+                    println!("  {}", part);
+                }
+            }
+            Err(e) => eprintln!("写入文件失败: {}", e),
         }
-    });
+        
+        compare_with_file(&pi_full_str);
+        explore_digits(&pi_full_str);
+        search_digits(&pi_full_str);
+        return;
+    }
+    
+    // 抽样输出
+    if let Some(step) = options.decimate {
+        let write_result = create_file(&output_file, &write_options.retry).and_then(|file| {
+            let mut writer = io::BufWriter::with_capacity(write_options.buffer_size, file);
+            let written = write_pi_decimated(&pi, digits, step, &mut writer)?;
+            writer.flush()?;
+            Ok(written)
+        });
+        match write_result {
+            Ok(written) => println!("\n已将每隔 {} 位抽取的 {} 位数字写入 {}", step, written, output_file),
+            Err(e) => eprintln!("写入文件失败: {}", e),
+        }
+        
+        compare_with_file(&pi_full_str);
+        explore_digits(&pi_full_str);
+        search_digits(&pi_full_str);
+        return;
+    }
+    
+    // 进度条按位数显示，非终端时退回逐行输出，--quiet 时不显示
+    let progress_callback: Option<Box<dyn Fn(usize, usize)>> = if show_bars {
+        let bar = progress_bar("写入", "位");
+        bar.set_length(digits as u64);
+        Some(Box::new(move |current: usize, total: usize| {
+            bar.set_position((digits * current / total) as u64);
+            if current == total {
+                bar.finish_and_clear();
+            }
+        }))
+    } else if options.quiet {
+        None
+    } else {
+        Some(Box::new(|current: usize, total: usize| {
+            if current % 10 == 0 || current == total {
+                let percent = (current as f64 / total as f64 * 100.0) as usize;
+                println!("写入进度: {}/{} ({}%)", current, total, percent);
+            }
+        }))
+    };
     
-    match write_pi_to_file_chunked(&pi, digits, &output_file, Some(progress_callback)) {
+    // 二进制输出写出与 digits 位十进制精度相当的二进制位数，忽略输出格式选择
+    let binary = options.radix == Some(2);
+    let write_result = match output_mode {
+        _ if binary => {
+            write_pi_binary(&pi, binary_digits_for(digits), &output_file, &write_options)
+        }
+        OutputMode::Standard if options.append && Path::new(&output_file).exists() => {
+            let format = FormatOptions::default();
+            append_pi_digits(&pi, digits, Path::new(&output_file), &format, &write_options)
+                .map(|appended| println!("已追加 {} 位小数", appended))
+        }
+        OutputMode::Standard => {
+            write_pi_to_file_chunked(&pi, digits, &output_file, progress_callback, &write_options)
+        }
+        OutputMode::Raw => write_pi_raw(&pi, digits, &output_file, &write_options),
+        OutputMode::Validation => write_pi_for_validation(&pi, digits, &output_file, &write_options),
+        OutputMode::CsvPositions => write_pi_csv_positions(&pi, digits, &output_file, &write_options),
+    };
+    
+    match write_result {
         Ok(_) => {
+            if options.sha256 {
+                if let Err(e) = write_sha256_sidecar(&pi, digits, &output_file, &write_options) {
+                    eprintln!("写入 SHA-256 文件失败: {}", e);
+                }
+            }
+            
             // 显示文件信息
             if let Ok(metadata) = std::fs::metadata(&output_file) {
-                println!("\n文件信息:");
-                println!("{}", "-".repeat(52));
-                println!("文件名: {}", output_file);
-                println!("文件大小: {:.2} KB", metadata.len() as f64 / 1024.0);
-                println!("计算时间: {:.2} 秒", compute_time);
-                println!("平均速度: {:.2} 位/秒", digits as f64 / compute_time);
+                print_summary(&RunSummary {
+                    digits,
+                    algorithm,
+                    threads: num_threads,
+                    precision: result.precision,
+                    elapsed: result.elapsed,
+                    digits_per_sec: digits_per_sec(digits, result.elapsed),
+                    verified_digits: correct_digits,
+                    output_path: output_file.clone(),
+                    file_bytes: metadata.len(),
+                });
+                
+                // 检查正文位数与头信息是否一致
+                if output_mode == OutputMode::Standard && !binary {
+                    if let Err(e) = verify_file(Path::new(&output_file)) {
+                        eprintln!("校验文件失败: {}", e);
+                    }
+                }
                 
                 if metadata.len() < 1024 * 1024 {  // 小于 1MB
                     println!("\n提示: 您可以使用 'more {}' 或 'head -n 50 {}' 查看文件内容", 
@@ -451,4 +1119,8 @@ fn main() {
     }
     
     println!("\n计算完成！结果已保存到 {}", output_file);
+    
+    compare_with_file(&pi_full_str);
+    explore_digits(&pi_full_str);
+    search_digits(&pi_full_str);
 }
@@ -0,0 +1,1287 @@
+use std::fs::OpenOptions;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Instant;
+use rug::float::Round;
+use rug::{Float, Integer};
+
+use crate::guaranteed_digits;
+use crate::hash::HashingWriter;
+use crate::index::build_index;
+use crate::retry::{create_file, RetryPolicy};
+use crate::verify::FOOTER_PREFIX;
+
+// 输出格式
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    // 带头信息、每 10 位一组的标准格式
+    Standard,
+    // 仅小数部分的连续数字流
+    Raw,
+    // raw 数字流，另附记录位数和 SHA-256 的校验信息文件，供外部校验工具使用
+    Validation,
+    // 每行一个 "位置,数字"，位置从小数点后第 1 位开始，便于导入数据库
+    CsvPositions,
+}
+
+// 换行符
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineEnding {
+    // \n
+    Unix,
+    // \r\n，便于部分 Windows 编辑器直接打开
+    Windows,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Unix => "\n",
+            LineEnding::Windows => "\r\n",
+        }
+    }
+}
+
+// 输出格式选项
+#[derive(Clone)]
+pub struct FormatOptions {
+    pub mode: OutputMode,
+    // 每组的字符数
+    pub group_size: usize,
+    // 每行的组数
+    pub groups_per_line: usize,
+    // 在每行末尾标注累计的小数位数，如 " :1000"
+    pub index_markers: bool,
+    // 标准格式中所有行使用的换行符
+    pub line_ending: LineEnding,
+    // 在数字正文上方输出一行与分组对齐的列标尺 "0123456789..."
+    pub ruler: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            mode: OutputMode::Standard,
+            group_size: 10,
+            groups_per_line: 5,
+            index_markers: false,
+            line_ending: LineEnding::Unix,
+            ruler: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    // 仅小数部分的连续数字流
+    pub fn raw() -> Self {
+        Self {
+            mode: OutputMode::Raw,
+            ..Self::default()
+        }
+    }
+    
+    // 二进制展开：每组 8 位（一个字节），每行 8 组
+    pub fn binary() -> Self {
+        Self {
+            group_size: 8,
+            groups_per_line: 8,
+            ..Self::default()
+        }
+    }
+    
+    // 每行一个 "位置,数字"
+    pub fn csv_positions() -> Self {
+        Self {
+            mode: OutputMode::CsvPositions,
+            ..Self::default()
+        }
+    }
+    
+    // 标准格式中整数部分是否单独一行，使正文各列恰好对应小数位
+    // 位置标记按小数位计数，列标尺按小数位对齐，两者都需要
+    fn separate_integer_part(&self) -> bool {
+        self.index_markers || self.ruler
+    }
+}
+
+// 整数部分加上 digits 位小数的字符串，如 digits 为 5 时是 "3.14159"
+// 全库统一：digits 指小数点后的位数，整数部分 3 不计入
+// 向零舍入：四舍五入可能把末尾的 ...9999 进位成 ...0000，向零舍入保证每一位都是 π 的真实数字
+// 小数位由 extract_digits 取得，0 位小数时只有整数部分 "3"
+pub fn pi_string(pi: &Float, digits: usize) -> String {
+    if !pi.is_finite() {
+        return pi.to_string();
+    }
+    
+    let integer = pi.to_integer_round(Round::Zero).map_or_else(Integer::new, |(i, _)| i);
+    let mut pi_str = integer.to_string();
+    if digits > 0 {
+        pi_str.reserve(digits + 1);
+        pi_str.push('.');
+        pi_str.extend(extract_digits(pi, digits).into_iter().map(|d| char::from(d + b'0')));
+    }
+    pi_str
+}
+
+// 每次从小数部分提取的最少位数
+const EXTRACT_CHUNK_DIGITS: usize = 10_000;
+
+// 提取时最多分成的块数，位数很多时每块相应变大，避免对整个余数做过多次乘法
+const MAX_EXTRACT_CHUNKS: usize = 64;
+
+// pi 小数点后的前 count 位数字（0-9），按截断取得，与 MPFR 向零舍入的十进制输出逐位一致
+// 非有限值没有小数位，返回空
+pub fn extract_digits(pi: &Float, count: usize) -> Vec<u8> {
+    FractionDigits::new(pi, count).collect()
+}
+
+// 按块逐位产生小数部分的数字，只保存当前块，不一次性持有全部位数
+// 小数部分是精确的分数 r / 2^s，每块乘以 10^chunk 后右移 s 位得到下一块数字，余数留给下一块
+struct FractionDigits {
+    remainder: Integer,
+    shift: u32,
+    // 尚未取出的位数，不含当前块
+    remaining: usize,
+    chunk_digits: usize,
+    block: std::vec::IntoIter<u8>,
+}
+
+impl FractionDigits {
+    fn new(pi: &Float, count: usize) -> Self {
+        // pi = mantissa * 2^exp，指数非负时没有小数部分；非有限值没有小数位
+        let (remainder, shift) = match pi.to_integer_exp() {
+            Some((mantissa, exp)) if exp < 0 => {
+                let shift = exp.unsigned_abs();
+                let mut remainder = mantissa.abs();
+                remainder.keep_bits_mut(shift);
+                (remainder, shift)
+            }
+            _ => (Integer::new(), 0),
+        };
+        let remaining = if pi.is_finite() { count } else { 0 };
+        
+        Self {
+            remainder,
+            shift,
+            remaining,
+            chunk_digits: EXTRACT_CHUNK_DIGITS.max(count.div_ceil(MAX_EXTRACT_CHUNKS)),
+            block: Vec::new().into_iter(),
+        }
+    }
+    
+    // 取出下一块数字
+    fn next_block(&mut self) -> Vec<u8> {
+        let chunk = self.remaining.min(self.chunk_digits);
+        self.remainder *= Integer::from(Integer::u_pow_u(10, chunk as u32));
+        let block = Integer::from(&self.remainder >> self.shift);
+        self.remainder.keep_bits_mut(self.shift);
+        self.remaining -= chunk;
+        
+        // 块的十进制表示不含前导零，按块长补足
+        let block_str = block.to_string();
+        let mut digits = vec![0; chunk - block_str.len()];
+        digits.extend(block_str.bytes().map(|b| b - b'0'));
+        digits
+    }
+}
+
+impl Iterator for FractionDigits {
+    type Item = u8;
+    
+    fn next(&mut self) -> Option<u8> {
+        if let Some(digit) = self.block.next() {
+            return Some(digit);
+        }
+        if self.remaining == 0 {
+            return None;
+        }
+        self.block = self.next_block().into_iter();
+        self.block.next()
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.block.len() + self.remaining;
+        (len, Some(len))
+    }
+}
+
+// π 小数部分的各位数字（0-9），共 digits 位，按块逐位产生
+pub fn fraction_digits(pi: &Float, digits: usize) -> impl Iterator<Item = u8> {
+    FractionDigits::new(pi, digits)
+}
+
+// 按字符逐个产生 pi_string 的内容 "3.14159..."，小数位按块产生，不构造完整的字符串
+pub fn pi_char_iter(pi: &Float, digits: usize) -> impl Iterator<Item = char> {
+    pi_head(pi, digits)
+        .into_bytes()
+        .into_iter()
+        .map(char::from)
+        .chain(FractionDigits::new(pi, digits).map(|d| char::from(d + b'0')))
+}
+
+// pi_string 中小数位之前的部分：整数部分加小数点，0 位小数时只有整数部分，非有限值为其字符串表示
+// 只有几个字符，直接转换
+fn pi_head(pi: &Float, digits: usize) -> String {
+    if !pi.is_finite() {
+        return pi.to_string();
+    }
+    let integer = pi.to_integer_round(Round::Zero).map_or_else(Integer::new, |(i, _)| i);
+    if digits > 0 {
+        format!("{}.", integer)
+    } else {
+        integer.to_string()
+    }
+}
+
+// 每位十进制数字对应的二进制位数: log2(10)
+const BITS_PER_DECIMAL_DIGIT: f64 = 3.321928094887362;
+
+// digits 位十进制小数的精度能保证的二进制小数位数
+pub fn binary_digits_for(digits: usize) -> usize {
+    (digits as f64 * BITS_PER_DECIMAL_DIGIT).floor() as usize
+}
+
+// π 小数部分的前 bits 位二进制数字（0 或 1），按截断取得
+pub fn fraction_bits(pi: &Float, bits: usize) -> impl Iterator<Item = u8> {
+    // floor(π·2^bits) 的二进制表示是整数部分 "11" 后接 bits 位小数，左移是精确的
+    let scaled = Float::with_val(pi.prec(), pi << bits as u32);
+    let integer = scaled.to_integer_round(Round::Down).map_or_else(Integer::new, |(i, _)| i);
+    let bin_str = integer.to_string_radix(2);
+    let skip = bin_str.len().saturating_sub(bits);
+    bin_str.into_bytes()
+        .into_iter()
+        .skip(skip)
+        .map(|b| b - b'0')
+}
+
+// 将 π 的二进制展开写入任意输出：头信息、整数部分 "11." 一行，之后按 format 分组的小数位
+pub fn stream_pi_binary<W: Write>(
+    pi: &Float,
+    bits: usize,
+    writer: &mut W,
+    format: &FormatOptions,
+) -> io::Result<()> {
+    let eol = format.line_ending.as_str();
+    write!(writer, "π 的二进制展开，小数点后 {} 位{}", bits, eol)?;
+    write!(writer, "{}{}", "=".repeat(80), eol)?;
+    write!(writer, "11.{}", eol)?;
+    
+    let body: Vec<u8> = fraction_bits(pi, bits).map(|b| b + b'0').collect();
+    let mut formatted = Vec::with_capacity(body.len() * 2);
+    format_block(&body, 0, format, &mut formatted);
+    writer.write_all(&formatted)?;
+    
+    write!(writer, "{}{}", "=".repeat(80), eol)?;
+    Ok(())
+}
+
+// 以二进制展开写入文件，每组 8 位、每行 8 组
+pub fn write_pi_binary(
+    pi: &Float,
+    bits: usize,
+    filename: &str,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    log::info!("将 π 的二进制展开写入文件 {}...", filename);
+    let start = Instant::now();
+    
+    let mut writer = options.buffered(create_file(filename, &options.retry)?);
+    stream_pi_binary(pi, bits, &mut writer, &FormatOptions::binary())?;
+    writer.flush()?;
+    
+    log::info!("写入完成，耗时: {:.2} 秒", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+// 将 π 按指定格式写入任意输出（文件、Vec<u8>、标准输出、网络连接等）
+pub fn stream_pi<W: Write>(
+    pi: &Float,
+    digits: usize,
+    writer: &mut W,
+    format: &FormatOptions,
+) -> io::Result<()> {
+    stream_pi_with_progress(pi, digits, writer, format, None)
+}
+
+// raw 格式每次写入的字节数
+const RAW_CHUNK_SIZE: usize = 64 * 1024;
+
+// 带进度回调的写入，回调参数为 (已写入块数, 总块数)
+fn stream_pi_with_progress<W: Write>(
+    pi: &Float,
+    digits: usize,
+    writer: &mut W,
+    format: &FormatOptions,
+    progress_callback: Option<&dyn Fn(usize, usize)>,
+) -> io::Result<()> {
+    match format.mode {
+        OutputMode::Standard => write_standard(pi, digits, writer, format, progress_callback),
+        OutputMode::Raw | OutputMode::Validation => {
+            // 按块写入，不在内存中保存完整的数字串
+            let mut buffer = Vec::with_capacity(RAW_CHUNK_SIZE.min(digits));
+            for digit in fraction_digits(pi, digits) {
+                buffer.push(digit + b'0');
+                if buffer.len() == RAW_CHUNK_SIZE {
+                    writer.write_all(&buffer)?;
+                    buffer.clear();
+                }
+            }
+            writer.write_all(&buffer)?;
+            
+            if let Some(callback) = progress_callback {
+                callback(1, 1);
+            }
+            Ok(())
+        }
+        OutputMode::CsvPositions => {
+            let eol = format.line_ending.as_str();
+            for (index, digit) in fraction_digits(pi, digits).enumerate() {
+                write!(writer, "{},{}{}", index + 1, digit, eol)?;
+            }
+            
+            if let Some(callback) = progress_callback {
+                callback(1, 1);
+            }
+            Ok(())
+        }
+    }
+}
+
+// 将一段数字按组、按行格式化后追加到 out，block 必须从行首开始
+// offset 为 block 首位在正文中的位置，用于行末的位置标记
+// 按整组切片拷贝，不逐字符写入
+pub fn format_block(block: &[u8], offset: usize, format: &FormatOptions, out: &mut Vec<u8>) {
+    let line_width = format.group_size * format.groups_per_line;
+    let mut pos = offset;
+    
+    for line in block.chunks(line_width) {
+        for (i, group) in line.chunks(format.group_size).enumerate() {
+            if i > 0 {
+                out.push(b' ');
+            }
+            out.extend_from_slice(group);
+        }
+        pos += line.len();
+        
+        if format.index_markers {
+            out.extend_from_slice(format!(" :{}", pos).as_bytes());
+        }
+        out.extend_from_slice(format.line_ending.as_str().as_bytes());
+    }
+}
+
+// 逐字符插入分隔符的参照实现，结果与 format_block(body, 0, format, ..) 逐字节相同
+// 供测试和基准与 format_block 对比，不属于公开接口
+#[doc(hidden)]
+pub fn format_scalar(body: &[u8], format: &FormatOptions) -> Vec<u8> {
+    let line_width = format.group_size * format.groups_per_line;
+    let eol = format.line_ending.as_str().as_bytes();
+    let mut out = Vec::with_capacity(body.len() * 2);
+    for (i, &digit) in body.iter().enumerate() {
+        let column = i % line_width;
+        if column > 0 && column % format.group_size == 0 {
+            out.push(b' ');
+        }
+        out.push(digit);
+        if column == line_width - 1 || i + 1 == body.len() {
+            if format.index_markers {
+                out.extend_from_slice(format!(" :{}", i + 1).as_bytes());
+            }
+            out.extend_from_slice(eol);
+        }
+    }
+    out
+}
+
+// 列标尺：每列显示其在行内位置的个位数，按与正文相同的方式分组
+// 写在整数部分之后、小数正文之前，各列与小数位对齐
+fn ruler_line(format: &FormatOptions) -> String {
+    let line_width = format.group_size * format.groups_per_line;
+    let mut ruler = String::with_capacity(line_width + format.groups_per_line);
+    
+    for position in 0..line_width {
+        if position > 0 && position % format.group_size == 0 {
+            ruler.push(' ');
+        }
+        ruler.push(char::from(b'0' + (position % 10) as u8));
+    }
+    
+    ruler
+}
+
+// 是否为 ruler_line 写出的列标尺行：除空格和换行外依次是 0123456789 的循环
+// 读取标准格式文件时跳过这一行，标尺的数字不是 π 的小数位
+pub(crate) fn is_ruler_line(line: &[u8]) -> bool {
+    let mut position = 0;
+    for &b in line {
+        match b {
+            b' ' | b'\r' | b'\n' => {}
+            b'0'..=b'9' if b - b'0' == (position % 10) as u8 => position += 1,
+            _ => return false,
+        }
+    }
+    position > 0
+}
+
+// 标准格式：头信息 + 分组数字 + 统计信息
+fn write_standard<W: Write>(
+    pi: &Float,
+    digits: usize,
+    writer: &mut W,
+    format: &FormatOptions,
+    progress_callback: Option<&dyn Fn(usize, usize)>,
+) -> io::Result<()> {
+    let eol = format.line_ending.as_str();
+    
+    // 写入头信息
+    write!(writer, "π 的前 {} 位小数{}", digits, eol)?;
+    write!(writer, "计算时间: {}{}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), eol)?;
+    write!(writer, "{}{}", "=".repeat(80), eol)?;
+    
+    // 标注位置或输出标尺时整数部分单独一行，使正文恰好是小数位；否则 "3." 也计入正文
+    let head = pi_head(pi, digits);
+    let head_in_body = if format.separate_integer_part() {
+        write!(writer, "{}{}", head.trim_end_matches('.'), eol)?;
+        ""
+    } else {
+        head.as_str()
+    };
+    
+    if format.ruler {
+        write!(writer, "{}{}", ruler_line(format), eol)?;
+    }
+    
+    // 分块处理：每块 20 行，块边界总是落在行边界上
+    // 小数位按块提取，只保存当前块，不构造完整的字符串
+    let line_width = format.group_size * format.groups_per_line;
+    let chunk_size = line_width * 20;
+    let fraction = FractionDigits::new(pi, digits);
+    let body_len = head_in_body.len() + fraction.size_hint().0;
+    let mut body = head_in_body.bytes().chain(fraction.map(|d| d + b'0'));
+    
+    // 块数按正文的实际长度计算；pi 的精度不足以保证 digits 位时照常写入，但提示末尾可能不正确
+    if let Some(guaranteed) = precision_shortfall(pi, digits) {
+        log::warn!(
+            "π 的精度只能保证前 {} 位小数，少于请求的 {} 位，之后的数字可能不正确",
+            guaranteed,
+            digits
+        );
+    }
+    let total_chunks = body_len.div_ceil(chunk_size);
+    let mut block = Vec::with_capacity(chunk_size.min(body_len));
+    let mut formatted = Vec::with_capacity(chunk_size * 2);
+    
+    for chunk in 0..total_chunks {
+        block.clear();
+        block.extend(body.by_ref().take(chunk_size));
+        
+        formatted.clear();
+        format_block(&block, chunk * chunk_size, format, &mut formatted);
+        writer.write_all(&formatted)?;
+        
+        // 报告进度
+        if let Some(callback) = progress_callback {
+            callback(chunk + 1, total_chunks);
+        }
+    }
+    
+    write_footer(writer, digits, eol)
+}
+
+// pi 的精度能保证正确的小数位数少于 digits 时返回能保证的位数
+fn precision_shortfall(pi: &Float, digits: usize) -> Option<usize> {
+    let guaranteed = guaranteed_digits(pi.prec(), 1);
+    (guaranteed < digits).then_some(guaranteed)
+}
+
+// 标准格式正文中参与分组的部分：整数部分单独一行时只有小数部分，否则包括整数部分 "3."
+// 0 位小数时没有小数点，整个字符串都是整数部分
+fn standard_body<'a>(pi_str: &'a str, format: &FormatOptions) -> &'a str {
+    if format.separate_integer_part() {
+        pi_str.find('.').map_or("", |dot| &pi_str[dot + 1..])
+    } else {
+        pi_str
+    }
+}
+
+// 标准格式末尾的统计信息
+fn write_footer<W: Write>(writer: &mut W, digits: usize, eol: &str) -> io::Result<()> {
+    write!(writer, "{}{}{}", eol, "=".repeat(80), eol)?;
+    write!(writer, "统计信息:{}", eol)?;
+    write!(writer, "{}{}{}", FOOTER_PREFIX, digits, eol)?;
+    Ok(())
+}
+
+// 写入文件时缓冲区的默认大小，超大文件按块写入时减少系统调用的次数
+pub const DEFAULT_BUFFER_SIZE: usize = 1024 * 1024;
+
+// 写入文件的选项，与内容格式无关
+#[derive(Clone, Debug)]
+pub struct WriteOptions {
+    // 缓冲区大小（字节），写满时才写入文件
+    pub buffer_size: usize,
+    // 创建文件失败时的重试策略
+    pub retry: RetryPolicy,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self { buffer_size: DEFAULT_BUFFER_SIZE, retry: RetryPolicy::default() }
+    }
+}
+
+impl WriteOptions {
+    // 按 buffer_size 为 inner 加上缓冲
+    pub(crate) fn buffered<W: Write>(&self, inner: W) -> io::BufWriter<W> {
+        io::BufWriter::with_capacity(self.buffer_size, inner)
+    }
+}
+
+// 在已有的标准格式文件末尾追加小数位，使其共有 digits 位，返回追加的位数
+// 头信息保持不变，统计信息中的总位数随之更新；format 必须与写入该文件时一致
+// 文件中已有的数字与 pi 不一致时返回错误，不修改文件
+pub fn append_pi_digits(
+    pi: &Float,
+    digits: usize,
+    path: &Path,
+    format: &FormatOptions,
+    options: &WriteOptions,
+) -> io::Result<usize> {
+    let index = build_index(path)?;
+    let existing = index.len();
+    if digits <= existing {
+        log::info!("{} 已包含 {} 位小数，无需追加", path.display(), existing);
+        return Ok(0);
+    }
+    
+    let stored = index.read_window(0, existing)?;
+    if !fraction_digits(pi, existing).map(|d| d + b'0').eq(stored.bytes()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} 中已有的数字与本次计算结果不一致", path.display()),
+        ));
+    }
+    
+    let pi_str = pi_string(pi, digits);
+    let body = standard_body(&pi_str, format).as_bytes();
+    
+    // 已写满的行保持不变，从最后一个未写满的行开始连同统计信息一起重写
+    let line_width = format.group_size * format.groups_per_line;
+    let kept_lines = (body.len() - (digits - existing)) / line_width;
+    let kept = kept_lines * line_width;
+    
+    // 正文之前还有单独的整数部分行和标尺行
+    let leading_lines = usize::from(format.separate_integer_part()) + usize::from(format.ruler);
+    let content = std::fs::read(path)?;
+    let truncate_at = offset_after_separator(&content, leading_lines + kept_lines).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{} 不是标准格式文件", path.display()))
+    })?;
+    
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.set_len(truncate_at as u64)?;
+    file.seek(SeekFrom::End(0))?;
+    let mut writer = options.buffered(file);
+    
+    let mut formatted = Vec::with_capacity((body.len() - kept) * 2);
+    format_block(&body[kept..], kept, format, &mut formatted);
+    writer.write_all(&formatted)?;
+    write_footer(&mut writer, digits, format.line_ending.as_str())?;
+    writer.flush()?;
+    
+    log::info!("已向 {} 追加 {} 位小数，共 {} 位", path.display(), digits - existing, digits);
+    Ok(digits - existing)
+}
+
+// 第一条分隔线之后再跳过 lines 行，返回下一行的起始字节偏移
+fn offset_after_separator(content: &[u8], lines: usize) -> Option<usize> {
+    let mut offset = 0;
+    // 经过分隔线后还需跳过的行数
+    let mut remaining = None;
+    
+    for line in content.split_inclusive(|&b| b == b'\n') {
+        match remaining {
+            None if line.starts_with(b"=") => remaining = Some(lines),
+            Some(0) => return Some(offset),
+            Some(n) => remaining = Some(n - 1),
+            None => {}
+        }
+        offset += line.len();
+    }
+    
+    None
+}
+
+// 分块写入文件，避免内存中保存完整的 π 字符串
+pub fn write_pi_to_file_chunked(
+    pi: &Float, 
+    digits: usize, 
+    filename: &str,
+    progress_callback: Option<Box<dyn Fn(usize, usize)>>,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    log::info!("将结果分块写入文件 {}...", filename);
+    let start = Instant::now();
+    
+    // 打开文件
+    let file = create_file(filename, &options.retry)?;
+    let mut writer = options.buffered(file);
+    
+    stream_pi_with_progress(pi, digits, &mut writer, &FormatOptions::default(), progress_callback.as_deref())?;
+    writer.flush()?;
+    
+    let duration = start.elapsed().as_secs_f64();
+    log::info!("写入完成，耗时: {:.2} 秒", duration);
+    
+    // 获取文件大小
+    if let Ok(metadata) = std::fs::metadata(filename) {
+        log::info!("文件大小: {:.2} KB", metadata.len() as f64 / 1024.0);
+    }
+    
+    Ok(())
+}
+
+// 分片文件名: "pi.txt" -> "pi.part001.txt"
+fn part_filename(filename: &str, index: usize) -> String {
+    match filename.rfind('.') {
+        Some(dot) if dot > 0 => format!("{}.part{:03}{}", &filename[..dot], index, &filename[dot..]),
+        _ => format!("{}.part{:03}", filename, index),
+    }
+}
+
+// 按每 split_size 位小数拆分写入多个文件，并生成 "<文件名>.manifest" 清单
+// 清单每行记录分片文件名及其包含的小数位范围（从 1 开始，含两端）
+// 返回各分片文件名；split_size 为 0 时返回 InvalidInput，不创建任何文件
+pub fn write_pi_split(
+    pi: &Float,
+    digits: usize,
+    filename: &str,
+    split_size: usize,
+    options: &WriteOptions,
+) -> io::Result<Vec<String>> {
+    if split_size == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "分片大小必须大于 0"));
+    }
+    
+    log::info!("将结果按每 {} 位拆分写入 {} 的分片文件...", split_size, filename);
+    let start = Instant::now();
+    
+    // 与 write_standard 相同，每块 20 行；小数位按块从同一个迭代器中取出，不保存完整的数字串
+    let format = FormatOptions::default();
+    let chunk_size = format.group_size * format.groups_per_line * 20;
+    let mut fraction = fraction_digits(pi, digits).map(|d| d + b'0');
+    let available = fraction.size_hint().0;
+    
+    let mut parts = Vec::new();
+    let manifest_file = create_file(format!("{}.manifest", filename), &options.retry)?;
+    let mut manifest = options.buffered(manifest_file);
+    writeln!(manifest, "π 的前 {} 位小数，共 {} 个分片", digits, available.div_ceil(split_size))?;
+    
+    let mut block = Vec::with_capacity(chunk_size.min(split_size));
+    let mut formatted = Vec::with_capacity(chunk_size * 2);
+    for (index, first) in (1..=available).step_by(split_size).enumerate() {
+        let last = available.min((first - 1).saturating_add(split_size));
+        let part_name = part_filename(filename, index + 1);
+        
+        let mut writer = options.buffered(create_file(&part_name, &options.retry)?);
+        writeln!(writer, "π 小数第 {} - {} 位", first, last)?;
+        writeln!(writer, "{}", "=".repeat(80))?;
+        
+        // 分片内按块写入，块长是行宽的整数倍，每块都从行首开始；写满本分片后换到下一个分片文件
+        for chunk_first in (first..=last).step_by(chunk_size) {
+            block.clear();
+            block.extend(fraction.by_ref().take(chunk_size.min(last + 1 - chunk_first)));
+            formatted.clear();
+            format_block(&block, chunk_first - 1, &format, &mut formatted);
+            writer.write_all(&formatted)?;
+        }
+        writer.flush()?;
+        
+        writeln!(manifest, "{}\t{}-{}", part_name, first, last)?;
+        parts.push(part_name);
+    }
+    manifest.flush()?;
+    
+    let duration = start.elapsed().as_secs_f64();
+    log::info!("写入完成，共 {} 个分片，耗时: {:.2} 秒", parts.len(), duration);
+    
+    Ok(parts)
+}
+
+// 抽样输出：只写入小数第 step、2*step、... 位，每行 "位置<TAB>数字"
+// 返回写入的位数
+pub fn write_pi_decimated<W: Write>(
+    pi: &Float,
+    digits: usize,
+    step: usize,
+    writer: &mut W,
+) -> io::Result<usize> {
+    let step = step.max(1);
+    let mut written = 0;
+    
+    for (index, digit) in fraction_digits(pi, digits).enumerate().skip(step - 1).step_by(step) {
+        writeln!(writer, "{}\t{}", index + 1, digit)?;
+        written += 1;
+    }
+    
+    Ok(written)
+}
+
+// 以 "位置,数字" 的 CSV 行写入小数部分，如 "1,1"、"2,4"、"3,1"，没有表头
+pub fn write_pi_csv_positions(
+    pi: &Float,
+    digits: usize,
+    filename: &str,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    log::info!("将小数部分按位置写入 CSV 文件 {}...", filename);
+    let start = Instant::now();
+    
+    let mut writer = options.buffered(create_file(filename, &options.retry)?);
+    stream_pi(pi, digits, &mut writer, &FormatOptions::csv_positions())?;
+    writer.flush()?;
+    
+    log::info!("写入完成，耗时: {:.2} 秒", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+// 只写入小数部分的连续数字流（无头信息、无分组、无小数点）
+// 便于其他工具直接拼接或比较
+pub fn write_pi_raw(
+    pi: &Float,
+    digits: usize,
+    filename: &str,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    log::info!("将小数部分以 raw 格式写入文件 {}...", filename);
+    let start = Instant::now();
+    
+    let file = create_file(filename, &options.retry)?;
+    let mut writer = options.buffered(file);
+    
+    stream_pi(pi, digits, &mut writer, &FormatOptions::raw())?;
+    writer.flush()?;
+    
+    let duration = start.elapsed().as_secs_f64();
+    log::info!("写入完成，耗时: {:.2} 秒", duration);
+    
+    Ok(())
+}
+
+// 写入 raw 数字流，并生成 "<文件名>.check" 校验信息文件（位数和 SHA-256）
+// 数字流格式为纯 ASCII 数字，无前缀、无分隔符，可直接交给外部校验工具
+pub fn write_pi_for_validation(
+    pi: &Float,
+    digits: usize,
+    filename: &str,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    log::info!("将小数部分以 raw 格式写入文件 {}...", filename);
+    let start = Instant::now();
+    
+    // 写入的同时计算哈希，不需要再生成一遍数字
+    let mut writer = HashingWriter::new(options.buffered(create_file(filename, &options.retry)?));
+    stream_pi(pi, digits, &mut writer, &FormatOptions::raw())?;
+    let (mut file_writer, hash) = writer.finish();
+    file_writer.flush()?;
+    log::info!("写入完成，耗时: {:.2} 秒", start.elapsed().as_secs_f64());
+    
+    let sidecar = format!("{}.check", filename);
+    
+    let mut writer = options.buffered(create_file(&sidecar, &options.retry)?);
+    writeln!(writer, "digits: {}", digits)?;
+    writeln!(writer, "sha256: {}", hash)?;
+    writer.flush()?;
+    
+    log::info!("校验信息已写入 {} (SHA-256: {})", sidecar, hash);
+    
+    Ok(())
+}
+
+// 计算小数部分数字流的 SHA-256，写入 "<文件名>.sha256"，返回十六进制哈希
+// 按 raw 格式分块生成数字并送入哈希，不需要保存完整的数字串
+pub fn write_sha256_sidecar(
+    pi: &Float,
+    digits: usize,
+    filename: &str,
+    options: &WriteOptions,
+) -> io::Result<String> {
+    let sidecar = format!("{}.sha256", filename);
+    let mut hasher = HashingWriter::new(io::sink());
+    stream_pi(pi, digits, &mut hasher, &FormatOptions::raw())?;
+    let (_, hash) = hasher.finish();
+    
+    create_file(&sidecar, &options.retry)?.write_all(format!("{}\n", hash).as_bytes())?;
+    log::info!("SHA-256 已写入 {}: {}", sidecar, hash);
+    
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hash_digits;
+    use crate::reference::{load_reference_chunk, pi_known_digits};
+    use crate::{compute_pi, pi_digits, verify_file, verify_pi_accuracy, Algorithm};
+    
+    fn pi(digits: usize) -> Float {
+        compute_pi(Algorithm::Chudnovsky, digits, 2).unwrap().value
+    }
+    
+    fn as_text(digits: &[u8]) -> String {
+        digits.iter().map(|&d| char::from(d + b'0')).collect()
+    }
+    
+    #[test]
+    fn extract_digits_counts() {
+        let pi = pi(100);
+        assert!(extract_digits(&pi, 0).is_empty());
+        assert_eq!(extract_digits(&pi, 1), [1]);
+        assert_eq!(as_text(&extract_digits(&pi, 100)), pi_known_digits()[2..]);
+    }
+    
+    // 与 MPFR 向零舍入的十进制输出逐位一致
+    #[test]
+    fn extract_digits_matches_mpfr() {
+        let pi = pi(1000);
+        let mpfr = pi.to_string_radix_round(10, Some(1001), Round::Zero);
+        assert_eq!(as_text(&extract_digits(&pi, 1000)), mpfr[2..]);
+        assert_eq!(pi_string(&pi, 1000), mpfr);
+    }
+    
+    // 逐字符产生的内容与 pi_string 相同，包括跨越多个提取块的位数
+    #[test]
+    fn char_iter_matches_pi_string() {
+        let pi = pi(25_000);
+        for digits in [0, 1, 100, 25_000] {
+            let chars: String = pi_char_iter(&pi, digits).collect();
+            assert_eq!(chars, pi_string(&pi, digits));
+        }
+        
+        let mut iter = fraction_digits(&pi, 25_000);
+        assert_eq!(iter.size_hint(), (25_000, Some(25_000)));
+        assert_eq!(iter.by_ref().take(3).collect::<Vec<_>>(), [1, 4, 1]);
+        assert_eq!(iter.size_hint(), (24_997, Some(24_997)));
+    }
+    
+    #[test]
+    fn raw_output_is_only_fraction_digits() {
+        let mut out = Vec::new();
+        stream_pi(&pi(1000), 1000, &mut out, &FormatOptions::raw()).unwrap();
+        assert_eq!(out.len(), 1000);
+        assert!(out.iter().all(u8::is_ascii_digit));
+        assert!(out.starts_with(b"14159265358979"));
+    }
+    
+    #[test]
+    fn stream_standard_into_memory() {
+        let mut out = Vec::new();
+        stream_pi(&pi(100), 100, &mut out, &FormatOptions::default()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        
+        assert_eq!(lines[0], "π 的前 100 位小数");
+        assert_eq!(lines[3], "3.14159265 3589793238 4626433832 7950288419 7169399375");
+        assert_eq!(lines.last().copied(), Some("总位数: 100"));
+    }
+    
+    // 逐字符插入分隔符的参照实现与按整组切片拷贝的 format_block 逐字节一致
+    #[test]
+    fn batched_formatting_matches_scalar() {
+        let body: Vec<u8> = fraction_digits(&pi(1234), 1234).map(|d| d + b'0').collect();
+        for index_markers in [false, true] {
+            let format = FormatOptions { index_markers, ..FormatOptions::default() };
+            let mut batched = Vec::new();
+            format_block(&body, 0, &format, &mut batched);
+            
+            assert_eq!(batched, format_scalar(&body, &format));
+        }
+    }
+    
+    // 每行 100 位时，第 10 行末尾的标注为累计位数 1000
+    #[test]
+    fn index_marker_on_tenth_line_reads_1000() {
+        let body: Vec<u8> = fraction_digits(&pi(1000), 1000).map(|d| d + b'0').collect();
+        let format = FormatOptions { groups_per_line: 10, index_markers: true, ..FormatOptions::default() };
+        let mut out = Vec::new();
+        format_block(&body, 0, &format, &mut out);
+        
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 10);
+        assert!(lines[0].ends_with(" :100"), "{}", lines[0]);
+        assert!(lines[9].ends_with(" :1000"), "{}", lines[9]);
+        assert_eq!(lines[9].as_bytes()[..10], load_reference_chunk(900, 10).unwrap()[..]);
+    }
+    
+    // 缓冲区远小于一行时仍按顺序完整写入
+    #[test]
+    fn small_buffer_writes_the_same_bytes() {
+        let pi = pi(1000);
+        let path = std::env::temp_dir().join(format!("pi_small_buffer_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        let options = WriteOptions { buffer_size: 7, ..WriteOptions::default() };
+        write_pi_raw(&pi, 1000, filename, &options).unwrap();
+        
+        let mut expected = Vec::new();
+        stream_pi(&pi, 1000, &mut expected, &FormatOptions::raw()).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 标准格式的快照，计算时间一行替换为固定文本后与 tests/fixtures 中的文件逐字节比较
+    #[test]
+    fn standard_format_matches_snapshot() {
+        let mut out = Vec::new();
+        stream_pi(&pi(200), 200, &mut out, &FormatOptions::default()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let normalized: String = text
+            .split_inclusive('\n')
+            .map(|line| if line.starts_with("计算时间: ") { "计算时间: <timestamp>\n" } else { line })
+            .collect();
+        
+        assert_eq!(normalized, include_str!("../tests/fixtures/standard_200.txt"));
+    }
+    
+    // 标尺在整数部分之后单独一行，宽度与一整行小数相同，分组间隔与正文对齐
+    #[test]
+    fn ruler_aligns_with_fraction_digits() {
+        let format = FormatOptions { ruler: true, ..FormatOptions::default() };
+        let mut out = Vec::new();
+        stream_pi(&pi(100), 100, &mut out, &format).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        
+        assert_eq!(lines[3], "3.");
+        assert_eq!(lines[4], "0123456789 0123456789 0123456789 0123456789 0123456789");
+        assert_eq!(lines[5], "1415926535 8979323846 2643383279 5028841971 6939937510");
+        assert!(is_ruler_line(lines[4].as_bytes()));
+        assert!(!is_ruler_line(lines[5].as_bytes()));
+    }
+    
+    // 1000 位的文件追加到 2000 位后，正文与直接计算的数字一致，统计信息中的总位数随之更新
+    #[test]
+    fn append_extends_file_and_footer() {
+        let pi = pi(2000);
+        let path = std::env::temp_dir().join(format!("pi_append_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        let options = WriteOptions::default();
+        write_pi_to_file_chunked(&pi, 1000, filename, None, &options).unwrap();
+        
+        let format = FormatOptions::default();
+        assert_eq!(append_pi_digits(&pi, 2000, &path, &format, &options).unwrap(), 1000);
+        assert_eq!(append_pi_digits(&pi, 2000, &path, &format, &options).unwrap(), 0);
+        
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().last(), Some("总位数: 2000"));
+        let verification = verify_file(&path).unwrap();
+        assert_eq!((verification.claimed, verification.actual), (Some(2000), 2000));
+        let expected: String = fraction_digits(&pi, 2000).map(|d| char::from(d + b'0')).collect();
+        assert_eq!(build_index(&path).unwrap().read_window(0, 2000).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 带标尺写入的文件，读取时不把标尺算作小数位，追加后仍然如此
+    #[test]
+    fn ruler_file_round_trips() {
+        let pi = pi(2000);
+        for index_markers in [false, true] {
+            let format = FormatOptions { ruler: true, index_markers, ..FormatOptions::default() };
+            let path = std::env::temp_dir()
+                .join(format!("pi_ruler_{}_{}.txt", index_markers, std::process::id()));
+            
+            let mut file = std::fs::File::create(&path).unwrap();
+            stream_pi(&pi, 1000, &mut file, &format).unwrap();
+            drop(file);
+            let verification = verify_file(&path).unwrap();
+            assert_eq!((verification.claimed, verification.actual), (Some(1000), 1000));
+            let index = build_index(&path).unwrap();
+            assert_eq!(index.read_window(0, 20).unwrap(), "14159265358979323846");
+            
+            append_pi_digits(&pi, 2000, &path, &format, &WriteOptions::default()).unwrap();
+            let verification = verify_file(&path).unwrap();
+            assert_eq!((verification.claimed, verification.actual), (Some(2000), 2000));
+            assert_eq!(build_index(&path).unwrap().len(), 2000);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+    
+    // 2500 位按每 1000 位拆分为 1000、1000、500 位三个分片，拼接后与原数字一致；分片大小为 0 时报错
+    #[test]
+    fn split_into_parts() {
+        let pi = pi(2500);
+        let path = std::env::temp_dir().join(format!("pi_split_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        
+        let error = write_pi_split(&pi, 2500, filename, 0, &WriteOptions::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(!Path::new(&format!("{}.manifest", filename)).exists());
+        
+        let parts = write_pi_split(&pi, 2500, filename, 1000, &WriteOptions::default()).unwrap();
+        assert_eq!(parts.len(), 3);
+        
+        let mut joined = String::new();
+        for (part, (first, last)) in parts.iter().zip([(1, 1000), (1001, 2000), (2001, 2500)]) {
+            let text = std::fs::read_to_string(part).unwrap();
+            let mut lines = text.lines();
+            assert_eq!(lines.next().unwrap(), format!("π 小数第 {} - {} 位", first, last));
+            let digits: String =
+                lines.skip(1).flat_map(str::chars).filter(char::is_ascii_digit).collect();
+            assert_eq!(digits.len(), last - first + 1);
+            joined.push_str(&digits);
+        }
+        assert_eq!(joined, as_text(&extract_digits(&pi, 2500)));
+        
+        let manifest_path = format!("{}.manifest", filename);
+        let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+        assert!(manifest.starts_with("π 的前 2500 位小数，共 3 个分片\n"));
+        assert!(manifest.ends_with(&format!("{}\t2001-2500\n", parts[2])));
+        
+        for part in &parts {
+            std::fs::remove_file(part).unwrap();
+        }
+        std::fs::remove_file(&manifest_path).unwrap();
+    }
+    
+    // 分片跨越多个写入块且不是块长的整数倍时，每个分片的格式与整块写出的相同，拼接后与原数字一致
+    #[test]
+    fn split_parts_spanning_several_blocks() {
+        let pi = pi(5000);
+        let path = std::env::temp_dir().join(format!("pi_split_blocks_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        let format = FormatOptions::default();
+        
+        let parts = write_pi_split(&pi, 5000, filename, 2345, &WriteOptions::default()).unwrap();
+        assert_eq!(parts.len(), 3);
+        
+        let digits = as_text(&extract_digits(&pi, 5000));
+        for (part, first) in parts.iter().zip([1, 2346, 4691]) {
+            let last = usize::min(first + 2344, 5000);
+            let header = format!("π 小数第 {} - {} 位\n{}\n", first, last, "=".repeat(80));
+            let mut expected = header.into_bytes();
+            format_block(&digits.as_bytes()[first - 1..last], first - 1, &format, &mut expected);
+            assert_eq!(std::fs::read(part).unwrap(), expected, "{}", part);
+            std::fs::remove_file(part).unwrap();
+        }
+        std::fs::remove_file(format!("{}.manifest", filename)).unwrap();
+    }
+    
+    // 精度远低于请求位数时照常写入 digits 位并给出提示，不会因长度不符而 panic
+    #[test]
+    fn low_precision_value_writes_requested_digits() {
+        let low = Float::with_val(64, rug::float::Constant::Pi);
+        assert_eq!(precision_shortfall(&low, 1000), Some(guaranteed_digits(64, 1)));
+        assert_eq!(precision_shortfall(&pi(1000), 500), None);
+        
+        let mut out = Vec::new();
+        stream_pi(&low, 1000, &mut out, &FormatOptions::default()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("π 的前 1000 位小数\n"));
+        assert!(text.contains(&format!("{}1000\n", FOOTER_PREFIX)));
+        
+        let body: String = text
+            .lines()
+            .skip(3)
+            .take_while(|line| !line.is_empty())
+            .flat_map(str::chars)
+            .filter(char::is_ascii_digit)
+            .collect();
+        assert_eq!(body.len(), 1001);
+        assert!(body.starts_with("3141592653589793"));
+    }
+    
+    // 分块流式计算的哈希与对完整数字串计算的哈希、以及 raw 文件内容的哈希一致
+    #[test]
+    fn sidecar_hash_matches_raw_file() {
+        let digits = 150_000;
+        let pi = pi(digits);
+        let expected = hash_digits(fraction_digits(&pi, digits));
+        let path = std::env::temp_dir().join(format!("pi_sidecar_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        let options = WriteOptions::default();
+        
+        assert_eq!(write_sha256_sidecar(&pi, digits, filename, &options).unwrap(), expected);
+        let sidecar = format!("{}.sha256", filename);
+        assert_eq!(std::fs::read_to_string(&sidecar).unwrap(), format!("{}\n", expected));
+        
+        write_pi_for_validation(&pi, digits, filename, &options).unwrap();
+        let raw = std::fs::read(&path).unwrap();
+        assert_eq!(raw.len(), digits);
+        assert_eq!(hash_digits(raw.iter().map(|&b| b - b'0')), expected);
+        let check = format!("{}.check", filename);
+        assert_eq!(
+            std::fs::read_to_string(&check).unwrap(),
+            format!("digits: {}\nsha256: {}\n", digits, expected)
+        );
+        
+        for file in [sidecar, check] {
+            std::fs::remove_file(file).unwrap();
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // .check 文件中的位数和哈希与 raw 格式 stream_pi 产生的数字流一致，可交给外部校验工具核对
+    #[test]
+    fn validation_sidecar_matches_stream() {
+        let pi = pi(1000);
+        let mut stream = Vec::new();
+        stream_pi(&pi, 1000, &mut stream, &FormatOptions::raw()).unwrap();
+        let expected = hash_digits(stream.iter().map(|&b| b - b'0'));
+        
+        let path = std::env::temp_dir().join(format!("pi_validation_{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+        write_pi_for_validation(&pi, 1000, filename, &WriteOptions::default()).unwrap();
+        
+        assert_eq!(std::fs::read(&path).unwrap(), stream);
+        let check = format!("{}.check", filename);
+        assert_eq!(
+            std::fs::read_to_string(&check).unwrap(),
+            format!("digits: 1000\nsha256: {}\n", expected)
+        );
+        std::fs::remove_file(&check).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 小数点后第 762 到 767 位是 999999，第 768 位是 8：取 767 位时四舍五入会进位成 ...5000000，
+    // 向零舍入保留真实的 ...4999999，与参考数据一致
+    #[test]
+    fn toward_zero_keeps_the_feynman_point() {
+        let pi = pi(1000);
+        let nearest = pi.to_string_radix(10, Some(768));
+        assert!(nearest.ends_with("5000000"), "{}", &nearest[760..]);
+        
+        let truncated = pi_string(&pi, 767);
+        assert!(truncated.ends_with("4999999"));
+        assert_eq!(truncated[2..].as_bytes(), load_reference_chunk(0, 767).unwrap());
+        assert_ne!(truncated, nearest);
+    }
+    
+    // 标准格式每写完一块（20 行、1000 位）报告一次进度：2500 位加上 "3." 共 3 块；raw 格式写完后报告一次
+    #[test]
+    fn progress_updates_once_per_chunk() {
+        let pi = pi(2500);
+        let updates = std::cell::RefCell::new(Vec::new());
+        let callback = |done: usize, total: usize| updates.borrow_mut().push((done, total));
+        
+        let mut out = Vec::new();
+        stream_pi_with_progress(&pi, 2500, &mut out, &FormatOptions::default(), Some(&callback)).unwrap();
+        assert_eq!(updates.take(), [(1, 3), (2, 3), (3, 3)]);
+        
+        out.clear();
+        stream_pi_with_progress(&pi, 2500, &mut out, &FormatOptions::raw(), Some(&callback)).unwrap();
+        assert_eq!(updates.take(), [(1, 1)]);
+    }
+    
+    // Windows 换行时每个行尾都是 \r\n，没有单独的 \r 或 \n；去掉 \r 后与 Unix 换行的输出一致
+    #[test]
+    fn windows_line_endings_everywhere() {
+        let pi = pi(1234);
+        let without_time = |text: String| -> String {
+            text.lines().filter(|line| !line.starts_with("计算时间: ")).collect::<Vec<_>>().join("\n")
+        };
+        
+        let modes = [OutputMode::Standard, OutputMode::CsvPositions];
+        for (mode, index_markers) in modes.into_iter().flat_map(|mode| [(mode, false), (mode, true)]) {
+            let unix = FormatOptions { mode, index_markers, ruler: true, ..FormatOptions::default() };
+            let windows = FormatOptions { line_ending: LineEnding::Windows, ..unix.clone() };
+            
+            let mut out = Vec::new();
+            stream_pi(&pi, 1234, &mut out, &windows).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            let line_feeds = text.matches('\n').count();
+            assert!(line_feeds > 0);
+            assert_eq!(text.matches("\r\n").count(), line_feeds);
+            assert_eq!(text.matches('\r').count(), line_feeds);
+            
+            let mut out = Vec::new();
+            stream_pi(&pi, 1234, &mut out, &unix).unwrap();
+            let expected = String::from_utf8(out).unwrap();
+            assert!(!expected.contains('\r'));
+            assert_eq!(without_time(text.replace("\r\n", "\n")), without_time(expected));
+        }
+    }
+    
+    // 100 位中每 10 位取一位：恰好写入第 10、20、……、100 位共 10 行
+    #[test]
+    fn decimate_every_tenth_of_100() {
+        let pi = pi(100);
+        let mut out = Vec::new();
+        assert_eq!(write_pi_decimated(&pi, 100, 10, &mut out).unwrap(), 10);
+        
+        let fraction = &pi_known_digits()[2..];
+        let expected: String = (10..=100)
+            .step_by(10)
+            .map(|position| format!("{}\t{}\n", position, &fraction[position - 1..position]))
+            .collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+    
+    // 请求 10 位时，各处都是 "3." 加 10 位小数：字符串、标准格式（头信息、正文、统计）、raw、CSV、
+    // 数字向量、验证和文件计数
+    #[test]
+    fn ten_digits_everywhere() {
+        let pi = pi(10);
+        assert_eq!(pi_string(&pi, 10), "3.1415926535");
+        assert_eq!(extract_digits(&pi, 10).len(), 10);
+        assert_eq!(pi_char_iter(&pi, 10).count(), 12);
+        assert_eq!(pi_digits(10, 2).unwrap(), [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5]);
+        assert_eq!(verify_pi_accuracy("3.1415926535", 10), (true, 10));
+        
+        let mut out = Vec::new();
+        stream_pi(&pi, 10, &mut out, &FormatOptions::default()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("π 的前 10 位小数\n"));
+        assert!(text.lines().any(|line| line == "3.14159265 35"), "{}", text);
+        assert!(text.ends_with("总位数: 10\n"));
+        
+        let path = std::env::temp_dir().join(format!("pi_ten_{}.txt", std::process::id()));
+        std::fs::write(&path, &text).unwrap();
+        let verification = verify_file(&path).unwrap();
+        assert_eq!((verification.claimed, verification.actual), (Some(10), 10));
+        std::fs::remove_file(&path).unwrap();
+        
+        let mut out = Vec::new();
+        stream_pi(&pi, 10, &mut out, &FormatOptions::raw()).unwrap();
+        assert_eq!(out, b"1415926535");
+        
+        let mut out = Vec::new();
+        let csv = FormatOptions { mode: OutputMode::CsvPositions, ..FormatOptions::default() };
+        stream_pi(&pi, 10, &mut out, &csv).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 10);
+        assert_eq!(text.lines().last(), Some("10,5"));
+    }
+    
+    // 50 位写成 "位置,数字" 的 CSV 文件：没有表头，共 50 行，第一行 "1,1"，最后一行是 "50,<第 50 位>"
+    #[test]
+    fn csv_positions_of_50_digits() {
+        let pi = pi(50);
+        let path = std::env::temp_dir().join(format!("pi_csv_{}.csv", std::process::id()));
+        let filename = path.to_str().unwrap();
+        write_pi_csv_positions(&pi, 50, filename, &WriteOptions::default()).unwrap();
+        
+        let text = std::fs::read_to_string(&path).unwrap();
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(rows.len(), 50);
+        assert_eq!(rows[..3], ["1,1", "2,4", "3,1"]);
+        assert_eq!(rows[49], format!("50,{}", &pi_known_digits()[51..52]));
+        assert_eq!(rows[49], "50,0");
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // π 小数部分的前 64 位二进制数字即十六进制 243F6A8885A308D3，按每组 8 位、每行 8 组恰好一行
+    #[test]
+    fn sixty_four_binary_digits() {
+        let pi = pi(100);
+        let bits: String = fraction_bits(&pi, 64).map(|b| char::from(b + b'0')).collect();
+        assert_eq!(bits, format!("{:064b}", 0x243F_6A88_85A3_08D3u64));
+        
+        let mut out = Vec::new();
+        stream_pi_binary(&pi, 64, &mut out, &FormatOptions::binary()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "π 的二进制展开，小数点后 64 位");
+        assert_eq!(lines[2], "11.");
+        assert_eq!(lines[3], "00100100 00111111 01101010 10001000 10000101 10100011 00001000 11010011");
+        assert!(lines[4].starts_with('='));
+    }
+}
@@ -0,0 +1,117 @@
+use std::io::{BufRead, Write};
+
+use crate::Algorithm;
+
+// 交互式读取 min..=max 范围内的整数
+// 空输入返回 default，超出范围或无法解析时提示并重新读取
+// 输入结束（EOF）或读取失败时返回 default，避免无限循环
+pub fn prompt_usize(
+    prompt: &str,
+    min: usize,
+    max: usize,
+    default: usize,
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> usize {
+    loop {
+        let _ = write!(writer, "{}", prompt);
+        let _ = writer.flush();
+        
+        let mut input = String::new();
+        match reader.read_line(&mut input) {
+            Ok(0) | Err(_) => return default,
+            Ok(_) => {}
+        }
+        let input = input.trim();
+        
+        if input.is_empty() {
+            return default;
+        }
+        
+        match input.parse::<usize>() {
+            Ok(n) if n >= min && n <= max => return n,
+            Ok(_) => {
+                let _ = writeln!(writer, "输入必须在 {} 到 {} 之间", min, max);
+            }
+            Err(_) => {
+                let _ = writeln!(writer, "请输入有效的数字");
+            }
+        }
+    }
+}
+
+// 解析算法选择：空输入使用默认算法，也可输入序号 (从 1 开始，顺序同 Algorithm::all) 或算法名称
+pub fn parse_algorithm_choice(input: &str, default: Algorithm) -> Option<Algorithm> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Some(default);
+    }
+    
+    match input.parse::<usize>() {
+        Ok(n) if n >= 1 => Algorithm::all().get(n - 1).copied(),
+        Ok(_) => None,
+        Err(_) => input.parse::<Algorithm>().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 以 input 作为标准输入调用 prompt_usize，返回结果和写出的提示文本
+    fn run(input: &str) -> (usize, String) {
+        let mut output = Vec::new();
+        let value = prompt_usize("位数: ", 1, 100, 50, &mut input.as_bytes(), &mut output);
+        (value, String::from_utf8(output).unwrap())
+    }
+    
+    #[test]
+    fn empty_input_uses_default() {
+        assert_eq!(run("\n"), (50, "位数: ".to_string()));
+        assert_eq!(run("   \n").0, 50);
+        // 输入结束时也返回默认值
+        assert_eq!(run("").0, 50);
+    }
+    
+    // 超出范围或无法解析时提示并重新读取，直到得到有效输入
+    #[test]
+    fn out_of_range_reprompts() {
+        let (value, output) = run("0\n101\nabc\n42\n");
+        assert_eq!(value, 42);
+        assert_eq!(output.matches("位数: ").count(), 4);
+        assert_eq!(output.matches("输入必须在 1 到 100 之间").count(), 2);
+        assert_eq!(output.matches("请输入有效的数字").count(), 1);
+        
+        // 一直无效直到输入结束时返回默认值
+        assert_eq!(run("1000\n").0, 50);
+    }
+    
+    #[test]
+    fn valid_input_is_returned() {
+        assert_eq!(run("1\n"), (1, "位数: ".to_string()));
+        assert_eq!(run(" 100 \n").0, 100);
+        assert_eq!(run("7").0, 7);
+    }
+    
+    // 菜单列出全部五种算法，序号和名称都能选中二进分割和 Gauss-Legendre
+    #[test]
+    fn algorithm_choice_by_number_or_name() {
+        let default = Algorithm::Bbp;
+        assert_eq!(Algorithm::all().len(), 5);
+        for (i, &algorithm) in Algorithm::all().iter().enumerate() {
+            assert_eq!(parse_algorithm_choice(&(i + 1).to_string(), default), Some(algorithm));
+            assert_eq!(parse_algorithm_choice(algorithm.name(), default), Some(algorithm));
+        }
+        
+        assert_eq!(parse_algorithm_choice("4\n", default), Some(Algorithm::ChudnovskyBinarySplit));
+        assert_eq!(parse_algorithm_choice("5", default), Some(Algorithm::GaussLegendre));
+        assert_eq!(
+            parse_algorithm_choice(" Gauss-Legendre\n", default),
+            Some(Algorithm::GaussLegendre)
+        );
+        assert_eq!(parse_algorithm_choice("\n", default), Some(default));
+        assert_eq!(parse_algorithm_choice("0", default), None);
+        assert_eq!(parse_algorithm_choice("6", default), None);
+        assert_eq!(parse_algorithm_choice("gauss", default), None);
+    }
+}
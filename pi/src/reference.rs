@@ -0,0 +1,91 @@
+use std::io::Read;
+use std::sync::OnceLock;
+use flate2::read::GzDecoder;
+use rug::Float;
+
+// 内置的 π 常数（小数点后 1020 位），用于小位数请求的快速路径
+const PI_REFERENCE: &str = "3.\
+    1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679\
+    8214808651328230664709384460955058223172535940812848111745028410270193852110555964462294895493038196\
+    4428810975665933446128475648233786783165271201909145648566923460348610454326648213393607260249141273\
+    7245870066063155881748815209209628292540917153643678925903600113305305488204665213841469519415116094\
+    3305727036575959195309218611738193261179310511854807446237996274956735188575272489122793818301194912\
+    9833673362440656643086021394946395224737190702179860943702770539217176293176752384674818467669405132\
+    0005681271452635608277857713427577896091736371787214684409012249534301465495853710507922796892589235\
+    4201995611212902196086403441815981362977477130996051870721134999999837297804995105973173281609631859\
+    5024459455346908302642522308253344685035261931188171010003137838752886587533208381420617177669147303\
+    5982534904287554687311595628638823537875937519577818577805321712268066130019278766111959092164201989\
+    38095257201065485863";
+
+// 双精度的 π，供只需要粗略值的测试和基准使用
+pub const PI_F64: f64 = std::f64::consts::PI;
+
+// 内置的 π 前 100 位小数，"3.1415..." 形式
+pub fn pi_known_digits() -> &'static str {
+    &PI_REFERENCE[..102]
+}
+
+// 快速路径可直接返回的最大位数，其余位作为舍入余量
+pub const CACHED_DIGITS: usize = 1000;
+
+// 位数不超过 CACHED_DIGITS 时，直接由内置常数得到 π，跳过级数计算
+pub(crate) fn cached_pi(digits: usize, precision: u32) -> Option<Float> {
+    if digits > CACHED_DIGITS {
+        return None;
+    }
+    
+    let parsed = Float::parse(PI_REFERENCE).ok()?;
+    Some(Float::with_val(precision, parsed))
+}
+
+// 随 crate 打包的 π 前 10000 位小数（gzip 压缩的 ASCII 数字，不含 "3."）
+const BUNDLED_REFERENCE_GZ: &[u8] = include_bytes!("../data/pi_10000.txt.gz");
+
+// 打包的参考数据包含的小数位数
+pub const BUNDLED_DIGITS: usize = 10_000;
+
+// 解压后的参考数据，第一次使用时解压
+fn bundled_reference() -> &'static [u8] {
+    static DIGITS: OnceLock<Vec<u8>> = OnceLock::new();
+    DIGITS.get_or_init(|| {
+        let mut digits = Vec::with_capacity(BUNDLED_DIGITS);
+        if let Err(e) = GzDecoder::new(BUNDLED_REFERENCE_GZ).read_to_end(&mut digits) {
+            log::error!("内置参考数据解压失败: {}", e);
+            digits.clear();
+        }
+        digits
+    })
+}
+
+// 小数点后第 start 位（从 0 开始）起的 len 位参考数字，每个元素为 ASCII 数字
+// 不需要下载外部文件；超出打包的范围时返回 None
+pub fn load_reference_chunk(start: usize, len: usize) -> Option<Vec<u8>> {
+    let end = start.checked_add(len)?;
+    bundled_reference().get(start..end).map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_pi, pi_string, Algorithm};
+    
+    // 每种算法的前 100 位都与内置常数一致；位数超过 CACHED_DIGITS，BBP 也完整计算而不走快速路径
+    #[test]
+    fn every_algorithm_matches_known_digits() {
+        for &algorithm in Algorithm::all() {
+            let result = compute_pi(algorithm, CACHED_DIGITS + 100, 2).unwrap();
+            assert_eq!(pi_string(&result.value, 100), pi_known_digits(), "{}", algorithm.name());
+        }
+        assert_eq!(pi_known_digits()[..17].parse::<f64>().unwrap(), PI_F64);
+    }
+    
+    // 打包的参考数据与内置常数的每一位小数都一致
+    #[test]
+    fn bundled_digits_match_the_builtin_constant() {
+        let fraction = &PI_REFERENCE[2..];
+        assert_eq!(fraction.len(), 1020);
+        let bundled = load_reference_chunk(0, fraction.len()).unwrap();
+        assert_eq!(bundled, fraction.as_bytes());
+        assert_eq!(bundled_reference().len(), BUNDLED_DIGITS);
+    }
+}
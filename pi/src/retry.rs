@@ -0,0 +1,93 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+// 创建输出文件失败时的重试策略
+// Windows 上杀毒软件或其他进程可能短暂锁定文件，重试几次通常就能成功
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    // 最多尝试次数
+    pub attempts: u32,
+    // 第一次重试前的等待时间，之后每次加倍
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { attempts: 3, delay: Duration::from_millis(100) }
+    }
+}
+
+// 执行 op，失败时等待后重试，等待时间每次加倍，共尝试 attempts 次
+// 路径不存在等重试也不会成功的错误直接返回
+pub fn retry_with_backoff<T>(
+    attempts: u32,
+    delay: Duration,
+    mut op: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut delay = delay;
+    let mut attempt = 1;
+    
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= attempts || !is_transient(&e) => return Err(e),
+            Err(e) => {
+                log::warn!("第 {} 次尝试失败: {}，{} 毫秒后重试", attempt, e, delay.as_millis());
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+// 可能因文件被暂时占用而出现的错误
+fn is_transient(error: &io::Error) -> bool {
+    !matches!(error.kind(), io::ErrorKind::NotFound | io::ErrorKind::InvalidInput)
+}
+
+// 按 policy 带重试地创建（或截断）文件
+pub fn create_file(path: impl AsRef<Path>, policy: &RetryPolicy) -> io::Result<File> {
+    retry_with_backoff(policy.attempts, policy.delay, || File::create(path.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 暂时性错误重试到成功为止，每次失败都等待；路径不存在的错误不重试
+    #[test]
+    fn transient_errors_are_retried() {
+        let mut calls = 0;
+        let value = retry_with_backoff(3, Duration::from_millis(1), || {
+            calls += 1;
+            if calls < 3 {
+                Err(io::Error::from(io::ErrorKind::PermissionDenied))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(value.unwrap(), 3);
+        
+        let mut calls = 0;
+        let result: io::Result<()> = retry_with_backoff(3, Duration::from_millis(1), || {
+            calls += 1;
+            Err(io::Error::from(io::ErrorKind::PermissionDenied))
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(calls, 3);
+        
+        let policy = RetryPolicy { attempts: 5, delay: Duration::from_secs(60) };
+        let missing = std::env::temp_dir()
+            .join(format!("pi_retry_missing_{}", std::process::id()))
+            .join("pi.txt");
+        assert_eq!(create_file(&missing, &policy).unwrap_err().kind(), io::ErrorKind::NotFound);
+        
+        let path = std::env::temp_dir().join(format!("pi_retry_{}.txt", std::process::id()));
+        create_file(&path, &policy).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
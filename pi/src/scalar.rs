@@ -0,0 +1,111 @@
+use rug::{Float, Assign};
+use rug::ops::Pow;
+
+// 级数计算所用的浮点运算抽象
+// 核心级数驱动对其泛化，便于替换为低精度的快速后端
+pub(crate) trait Scalar: Sized + Send + 'static {
+    // 以指定精度（二进制位）构造
+    fn from_u64(precision: u32, value: u64) -> Self;
+    
+    fn assign_u64(&mut self, value: u64);
+    fn assign_from(&mut self, other: &Self);
+    
+    fn add_mut(&mut self, other: &Self);
+    fn sub_mut(&mut self, other: &Self);
+    fn mul_mut(&mut self, other: &Self);
+    fn div_mut(&mut self, other: &Self);
+    
+    fn pow_u32(&self, exp: u32) -> Self;
+    
+    // 既不是无穷大也不是 NaN
+    fn is_finite(&self) -> bool;
+    
+    // 绝对值小于 2^-precision，即已低于目标精度的最低有效位
+    fn below_ulp(&self, precision: u32) -> bool;
+}
+
+impl Scalar for Float {
+    fn from_u64(precision: u32, value: u64) -> Self {
+        Float::with_val(precision, value)
+    }
+    
+    fn assign_u64(&mut self, value: u64) {
+        self.assign(value);
+    }
+    
+    fn assign_from(&mut self, other: &Self) {
+        self.assign(other);
+    }
+    
+    fn add_mut(&mut self, other: &Self) {
+        *self += other;
+    }
+    
+    fn sub_mut(&mut self, other: &Self) {
+        *self -= other;
+    }
+    
+    fn mul_mut(&mut self, other: &Self) {
+        *self *= other;
+    }
+    
+    fn div_mut(&mut self, other: &Self) {
+        *self /= other;
+    }
+    
+    fn pow_u32(&self, exp: u32) -> Self {
+        self.clone().pow(exp)
+    }
+    
+    fn is_finite(&self) -> bool {
+        Float::is_finite(self)
+    }
+    
+    // 指数为 e 时 |x| < 2^e，零没有指数
+    fn below_ulp(&self, precision: u32) -> bool {
+        self.is_zero() || self.get_exp().is_some_and(|exp| exp <= -(precision as i32))
+    }
+}
+
+// f64 后端：忽略精度参数，仅适合十几位以内的快速验证
+impl Scalar for f64 {
+    fn from_u64(_precision: u32, value: u64) -> Self {
+        value as f64
+    }
+    
+    fn assign_u64(&mut self, value: u64) {
+        *self = value as f64;
+    }
+    
+    fn assign_from(&mut self, other: &Self) {
+        *self = *other;
+    }
+    
+    fn add_mut(&mut self, other: &Self) {
+        *self += other;
+    }
+    
+    fn sub_mut(&mut self, other: &Self) {
+        *self -= other;
+    }
+    
+    fn mul_mut(&mut self, other: &Self) {
+        *self *= other;
+    }
+    
+    fn div_mut(&mut self, other: &Self) {
+        *self /= other;
+    }
+    
+    fn pow_u32(&self, exp: u32) -> Self {
+        self.powi(exp as i32)
+    }
+    
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+    
+    fn below_ulp(&self, precision: u32) -> bool {
+        self.abs() < 2f64.powi(-(precision as i32))
+    }
+}
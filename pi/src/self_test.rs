@@ -0,0 +1,76 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crate::bbp::compute_pi_series;
+use crate::output::fraction_digits;
+use crate::reference::cached_pi;
+use crate::{compute_pi, required_precision, Algorithm, ComputeError, ComputeOptions};
+
+// 自检的小数位数，不超过内置常数的长度，可以直接与内置常数比较
+pub const SELF_TEST_DIGITS: usize = 1000;
+
+// 用每种算法完整计算 SELF_TEST_DIGITS 位，与内置常数及第一种算法的结果逐位比较
+// 同时检验 GMP/MPFR 是否正确链接；全部一致返回 Ok
+pub fn self_test(num_threads: usize) -> Result<(), ComputeError> {
+    let precision = required_precision(SELF_TEST_DIGITS);
+    let reference = cached_pi(SELF_TEST_DIGITS, precision).expect("内置常数解析失败");
+    let reference: Vec<u8> = fraction_digits(&reference, SELF_TEST_DIGITS).collect();
+    
+    let mut first: Option<(Algorithm, Vec<u8>)> = None;
+    for &algorithm in Algorithm::all() {
+        // BBP 不走内置常数快速路径，否则只是拿常数和自己比较
+        let result = match algorithm {
+            Algorithm::Bbp => {
+                let stop = Arc::new(AtomicBool::new(false));
+                compute_pi_series(SELF_TEST_DIGITS, num_threads, &stop, &ComputeOptions::default())?
+            }
+            _ => compute_pi(algorithm, SELF_TEST_DIGITS, num_threads)?,
+        };
+        let digits: Vec<u8> = fraction_digits(&result.value, SELF_TEST_DIGITS).collect();
+        
+        if let Some(position) = first_mismatch(&digits, &reference) {
+            return Err(ComputeError::SelfTestFailed { algorithm, against: None, position });
+        }
+        match &first {
+            Some((other, other_digits)) => {
+                if let Some(position) = first_mismatch(&digits, other_digits) {
+                    return Err(ComputeError::SelfTestFailed {
+                        algorithm,
+                        against: Some(*other),
+                        position,
+                    });
+                }
+            }
+            None => first = Some((algorithm, digits)),
+        }
+        log::info!("✓ {} 算法的前 {} 位小数正确", algorithm, SELF_TEST_DIGITS);
+    }
+    
+    Ok(())
+}
+
+// 第一个不同的小数位（从 1 开始），长度不同时较短一方的末尾之后算作不同
+fn first_mismatch(a: &[u8], b: &[u8]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+        .map(|index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn self_test_passes_on_a_correct_build() {
+        assert!(self_test(2).is_ok());
+    }
+    
+    #[test]
+    fn mismatch_is_reported_from_one() {
+        assert_eq!(first_mismatch(&[1, 4, 1], &[1, 4, 1]), None);
+        assert_eq!(first_mismatch(&[1, 4, 1], &[1, 5, 1]), Some(2));
+        assert_eq!(first_mismatch(&[1, 4], &[1, 4, 1]), Some(3));
+    }
+}
@@ -0,0 +1,59 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::output::pi_string;
+use crate::{Algorithm, ComputeResult, RunSummary};
+
+// 算法序列化为名称，与命令行和 FromStr 使用的名称一致
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+// π 的值序列化为截断到请求位数的十进制字符串，而不是 MPFR 的内部表示
+impl Serialize for ComputeResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ComputeResult", 7)?;
+        state.serialize_field("value", &pi_string(&self.value, self.digits))?;
+        state.serialize_field("digits", &self.digits)?;
+        state.serialize_field("terms", &self.terms)?;
+        state.serialize_field("precision", &self.precision)?;
+        state.serialize_field("algorithm", &self.algorithm)?;
+        state.serialize_field("elapsed", &self.elapsed)?;
+        state.serialize_field("throughput", &self.throughput)?;
+        state.end()
+    }
+}
+
+impl Serialize for RunSummary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RunSummary", 9)?;
+        state.serialize_field("digits", &self.digits)?;
+        state.serialize_field("algorithm", &self.algorithm)?;
+        state.serialize_field("threads", &self.threads)?;
+        state.serialize_field("precision", &self.precision)?;
+        state.serialize_field("elapsed", &self.elapsed)?;
+        state.serialize_field("digits_per_sec", &self.digits_per_sec)?;
+        state.serialize_field("verified_digits", &self.verified_digits)?;
+        state.serialize_field("output_path", &self.output_path)?;
+        state.serialize_field("file_bytes", &self.file_bytes)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compute_pi_with, Algorithm, ComputeOptions};
+    
+    // value 是截断到请求位数的十进制字符串，其余字段原样输出
+    #[test]
+    fn compute_result_serializes_digits_and_metadata() {
+        let result = compute_pi_with(Algorithm::Chudnovsky, 50, 1, &ComputeOptions::default()).unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        
+        assert!(json.contains("\"value\":\"3.14159265358979323846264338327950288419716939937510\""), "{}", json);
+        assert!(json.contains("\"digits\":50"), "{}", json);
+        assert!(json.contains(&format!("\"precision\":{}", result.precision)), "{}", json);
+        assert!(json.contains(&format!("\"algorithm\":\"{}\"", Algorithm::Chudnovsky.name())), "{}", json);
+    }
+}
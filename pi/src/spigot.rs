@@ -0,0 +1,125 @@
+use std::sync::mpsc;
+use rug::Integer;
+
+use crate::{ComputeError, Config};
+
+// Gibbons 无界 spigot 算法，按顺序逐位产生 π 的十进制数字 3, 1, 4, 1, 5, ...
+// 只使用整数运算，每一位确定后即可输出，不需要预先知道总位数
+pub struct Spigot {
+    q: Integer,
+    r: Integer,
+    t: Integer,
+    k: u32,
+    n: Integer,
+    l: u32,
+}
+
+impl Spigot {
+    pub fn new() -> Self {
+        Self {
+            q: Integer::from(1),
+            r: Integer::from(0),
+            t: Integer::from(1),
+            k: 1,
+            n: Integer::from(3),
+            l: 3,
+        }
+    }
+}
+
+impl Default for Spigot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for Spigot {
+    type Item = u8;
+    
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            // 4q + r - t < n*t 时当前的 n 已经确定
+            let lhs = Integer::from(&self.q * 4u32) + &self.r - &self.t;
+            let rhs = Integer::from(&self.n * &self.t);
+            
+            if lhs < rhs {
+                let digit = self.n.to_u8().expect("spigot 产生的数字应在 0-9 之间");
+                
+                // n = 10(3q + r) / t - 10n
+                let mut n = Integer::from(&self.q * 3u32) + &self.r;
+                n *= 10u32;
+                n /= &self.t;
+                n -= Integer::from(&self.n * 10u32);
+                
+                // r = 10(r - n*t)，q = 10q
+                self.r -= rhs;
+                self.r *= 10u32;
+                self.q *= 10u32;
+                self.n = n;
+                
+                return Some(digit);
+            }
+            
+            // 吸收下一项: n = (q(7k + 2) + r*l) / (t*l)
+            let mut n = Integer::from(&self.q * (7 * self.k + 2)) + Integer::from(&self.r * self.l);
+            self.t *= self.l;
+            n /= &self.t;
+            
+            // r = (2q + r) * l，q = q * k
+            self.r += Integer::from(&self.q * 2u32);
+            self.r *= self.l;
+            self.q *= self.k;
+            self.n = n;
+            
+            self.k += 1;
+            self.l += 2;
+        }
+    }
+}
+
+// 用 spigot 算法逐位计算 π，每确定一位立即发送到 sink
+// 先发送整数部分 3，再发送 config 要求的小数位数，返回发送的位数（小数位数 + 1）
+// 接收端提前关闭时停止计算并返回 Cancelled
+pub fn stream_digits(config: &Config, sink: mpsc::Sender<u8>) -> Result<usize, ComputeError> {
+    let digits = config.display_digits();
+    log::info!("使用 spigot 算法逐位计算 π 到小数点后 {} 位...", digits);
+    
+    for (sent, digit) in Spigot::new().take(digits + 1).enumerate() {
+        if sink.send(digit).is_err() {
+            log::warn!("接收端已关闭，已发送 {} 位", sent);
+            return Err(ComputeError::Cancelled);
+        }
+    }
+    
+    Ok(digits + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use crate::load_reference_chunk;
+    
+    // 500 位的 spigot 计算在另一个线程中逐位发送，接收到的整数部分和小数位与参考数据一致
+    #[test]
+    fn streamed_500_digits_match_reference() {
+        let (sender, receiver) = mpsc::channel();
+        let config = Config::new(500);
+        let handle = thread::spawn(move || stream_digits(&config, sender));
+        
+        let received: Vec<u8> = receiver.iter().collect();
+        assert_eq!(handle.join().unwrap().unwrap(), 501);
+        assert_eq!(received.len(), 501);
+        assert_eq!(received[0], 3);
+        let expected: Vec<u8> = load_reference_chunk(0, 500).unwrap().iter().map(|b| b - b'0').collect();
+        assert_eq!(received[1..], expected);
+    }
+    
+    // 接收端提前关闭时停止计算
+    #[test]
+    fn closed_receiver_cancels() {
+        let (sender, receiver) = mpsc::channel();
+        drop(receiver);
+        assert!(matches!(stream_digits(&Config::new(500), sender), Err(ComputeError::Cancelled)));
+    }
+}
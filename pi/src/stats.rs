@@ -0,0 +1,372 @@
+use std::io::Write;
+use std::time::Duration;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::gauss_legendre::gauss_legendre_iterations;
+use crate::{
+    Algorithm, ComputeError, BBP_DIGITS_PER_TERM, BELLARD_DIGITS_PER_TERM,
+    CHUDNOVSKY_DIGITS_PER_TERM,
+};
+
+// 未指定时允许的最长预计耗时
+pub const DEFAULT_MAX_RUNTIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+// 未指定时允许的最大预计内存（字节），用 u64 表示，32 位平台上也不会溢出
+pub const DEFAULT_MAX_MEMORY_BYTES: u64 = 64 * 1024 * 1024 * 1024;
+
+// 各算法的耗时和内存估算参数
+struct CostModel {
+    // digits 位需要的项数（Gauss-Legendre 为迭代次数）
+    terms: fn(usize) -> f64,
+    // 单项的运算量与精度成正比，此为每项每二进制位的耗时（纳秒），按常见桌面 CPU 的实测量级取值
+    nanos_per_term_bit: f64,
+    // 每个线程同时持有的、与精度同量级的高精度数个数
+    numbers_per_thread: f64,
+    // 能利用的最多线程数，更多的线程不分担计算也不占用内存
+    max_threads: usize,
+}
+
+fn cost_model(algorithm: Algorithm) -> CostModel {
+    match algorithm {
+        // 4 个分式，12 个临时变量和局部和
+        Algorithm::Bbp => CostModel {
+            terms: |digits| digits as f64 / BBP_DIGITS_PER_TERM + 1.0,
+            nanos_per_term_bit: 0.05,
+            numbers_per_thread: 13.0,
+            max_threads: usize::MAX,
+        },
+        // 7 个分式，除法次数约为 BBP 的 2 倍；6 个临时变量和局部和
+        Algorithm::Bellard => CostModel {
+            terms: |digits| digits as f64 / BELLARD_DIGITS_PER_TERM + 1.0,
+            nanos_per_term_bit: 0.1,
+            numbers_per_thread: 7.0,
+            max_threads: usize::MAX,
+        },
+        // 每项重新计算 640320^(3k) 并把分子、分母转换为浮点数，项数约为 BBP 的 1/12
+        // 阶乘、分子和分母的位数是精度的 1 到 3 倍，按 16 个精度大小的数计
+        Algorithm::Chudnovsky => CostModel {
+            terms: |digits| digits as f64 / CHUDNOVSKY_DIGITS_PER_TERM + 1.0,
+            nanos_per_term_bit: 0.3,
+            numbers_per_thread: 16.0,
+            max_threads: usize::MAX,
+        },
+        // 各项只做整数乘法，大整数乘法用快速算法，平摊到每项每位的耗时远低于逐项计算
+        // 顶层的 P、Q、T 约为精度的 1 到 2 倍，合并时还需要同样大小的乘积，按 12 个精度大小的数计
+        Algorithm::ChudnovskyBinarySplit => CostModel {
+            terms: |digits| digits as f64 / CHUDNOVSKY_DIGITS_PER_TERM + 1.0,
+            nanos_per_term_bit: 0.02,
+            numbers_per_thread: 12.0,
+            max_threads: usize::MAX,
+        },
+        // 每次迭代一次开方、两次乘法，开方按约 10 次乘法计；a、b、t、p 和两个临时变量
+        Algorithm::GaussLegendre => CostModel {
+            terms: |digits| gauss_legendre_iterations(digits) as f64,
+            nanos_per_term_bit: 20.0,
+            numbers_per_thread: 6.0,
+            max_threads: 1,
+        },
+    }
+}
+
+// digits 位对应的二进制精度，用 f64 表示，位数远超 u32 精度范围时也不会溢出
+fn estimated_precision(digits: usize) -> f64 {
+    digits as f64 * std::f64::consts::LOG2_10 + 10.0
+}
+
+// 估算用 algorithm 计算 digits 位所需的内存（字节）
+// 包括各线程持有的高精度数、最终结果，以及转换为十进制字符串时的缓冲；超出 u64 时取 u64::MAX
+pub fn estimate_memory_bytes(algorithm: Algorithm, digits: usize, num_threads: usize) -> u64 {
+    let model = cost_model(algorithm);
+    let threads = num_threads.clamp(1, model.max_threads) as f64;
+    let numbers = threads * model.numbers_per_thread + 1.0;
+    (numbers * estimated_precision(digits) / 8.0 + digits as f64) as u64
+}
+
+// 二进分割组装 π 时同时存在的精度大小的浮点数：级数和、sqrt(10005) 和结果
+const SPLIT_ASSEMBLY_FLOATS: f64 = 3.0;
+
+// 流式写入时每块提取的十进制位数，与 raw 格式的写入块大小一致
+const STREAMING_BLOCK_DIGITS: f64 = 64.0 * 1024.0;
+
+// 二进分割 terms 项后顶层 P、Q、T 的总位数
+// 第 k 项给 P、Q、T 分别带来约 72k³、(640320³/24)k³ 和与 Q 同量级的因子，
+// 按 Σ log2 k ≈ n (log2 n - log2 e) 合计约 n (log2 72 + 2 log2(640320³/24) + 9 (log2 n - log2 e)) 位
+fn split_triple_bits(terms: f64) -> f64 {
+    if terms < 1.0 {
+        return 0.0;
+    }
+    let log_terms = terms.log2() - std::f64::consts::LOG2_E;
+    terms * (72f64.log2() + 2.0 * 10_939_058_860_032_000f64.log2() + 9.0 * log_terms.max(0.0))
+}
+
+// 估算二进分割计算 digits 位时的内存峰值（字节）
+// streaming 为 false 时 P、Q、T 一直保留到完整的十进制字符串转换完毕，峰值为三者之和；
+// 为 true 时组装出 π 后即释放 P、Q、T，再按块提取数字，峰值取组装和提取两个阶段中较大者
+pub fn estimate_binary_split_peak_bytes(digits: usize, streaming: bool) -> u64 {
+    let triple = split_triple_bits(digits as f64 / CHUDNOVSKY_DIGITS_PER_TERM + 1.0) / 8.0;
+    let floats = SPLIT_ASSEMBLY_FLOATS * estimated_precision(digits) / 8.0;
+    
+    let peak = if streaming {
+        let assembly = triple + estimated_precision(digits) / 8.0;
+        let extraction = floats + STREAMING_BLOCK_DIGITS.min(digits as f64);
+        assembly.max(extraction)
+    } else {
+        triple + floats + digits as f64
+    };
+    peak as u64
+}
+
+// 在内存上限内选择算法和线程数：先用 algorithm 从请求的线程数开始逐步减少，
+// 单线程仍超出上限时改用其他算法，按单线程内存从低到高尝试；都超出上限时返回错误
+pub fn fit_to_memory(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+    max_bytes: u64,
+) -> Result<(Algorithm, usize), ComputeError> {
+    let single_thread = |a: &Algorithm| estimate_memory_bytes(*a, digits, 1);
+    let mut fallbacks: Vec<Algorithm> =
+        Algorithm::all().iter().copied().filter(|&a| a != algorithm).collect();
+    fallbacks.sort_by_key(single_thread);
+    
+    for candidate in std::iter::once(algorithm).chain(fallbacks) {
+        for threads in (1..=num_threads.max(1)).rev() {
+            if estimate_memory_bytes(candidate, digits, threads) <= max_bytes {
+                return Ok((candidate, threads));
+            }
+        }
+    }
+    
+    Err(ComputeError::MemoryBudgetExceeded {
+        required: Algorithm::all().iter().map(single_thread).min().unwrap_or(u64::MAX),
+        budget: max_bytes,
+    })
+}
+
+// 按 algorithm 的估算参数估算计算 digits 位的耗时和内存（字节）
+// 总耗时为项数 × 精度 × 每项每位耗时，按线程数均分
+pub fn estimate_runtime_and_memory(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+) -> (Duration, u64) {
+    let model = cost_model(algorithm);
+    let threads = num_threads.clamp(1, model.max_threads) as f64;
+    let terms = (model.terms)(digits);
+    
+    let nanos = terms * estimated_precision(digits) * model.nanos_per_term_bit / threads;
+    let runtime = Duration::try_from_secs_f64(nanos / 1e9).unwrap_or(Duration::MAX);
+    
+    (runtime, estimate_memory_bytes(algorithm, digits, num_threads))
+}
+
+// 用 algorithm 计算时预计耗时或内存超出上限，返回错误，否则返回估算值
+pub fn check_feasibility(
+    algorithm: Algorithm,
+    digits: usize,
+    num_threads: usize,
+    max_runtime: Duration,
+    max_memory: u64,
+) -> Result<(Duration, u64), ComputeError> {
+    let (runtime, memory) = estimate_runtime_and_memory(algorithm, digits, num_threads);
+    if runtime > max_runtime || memory > max_memory {
+        return Err(ComputeError::Infeasible { digits, runtime, memory });
+    }
+    Ok((runtime, memory))
+}
+
+// 计算并显示内存使用统计
+pub fn print_memory_stats(algorithm: Algorithm, digits: usize, precision: u32, num_threads: usize) {
+    println!("\n内存使用估算:");
+    println!("{}", "-".repeat(40));
+    
+    // 每个 Float 的内存占用（字节）= 精度（位）/ 8
+    let float_size_bytes = precision as f64 / 8.0;
+    
+    // 线程内存占用：每个线程持有的高精度数个数由算法决定
+    let model = cost_model(algorithm);
+    let threads = num_threads.clamp(1, model.max_threads) as f64;
+    let thread_memory_mb = threads * model.numbers_per_thread * float_size_bytes / 1024.0 / 1024.0;
+    
+    // 结果内存占用
+    let result_memory_mb = float_size_bytes / 1024.0 / 1024.0;
+    
+    // 总内存占用估算
+    let total_memory_mb = estimate_memory_bytes(algorithm, digits, num_threads) as f64 / 1024.0 / 1024.0;
+    
+    println!("计算位数: {} 位十进制", digits);
+    println!("精度: {} 位二进制", precision);
+    println!("每个高精度浮点数: {:.2} MB", float_size_bytes / 1024.0 / 1024.0);
+    println!("线程内存: {:.2} MB ({} 个线程)", thread_memory_mb, num_threads);
+    println!("结果内存: {:.2} MB", result_memory_mb);
+    println!("总估算内存: {:.2} MB", total_memory_mb);
+    if algorithm == Algorithm::ChudnovskyBinarySplit {
+        let mb = |streaming| estimate_binary_split_peak_bytes(digits, streaming) as f64 / 1024.0 / 1024.0;
+        println!("峰值内存: {:.2} MB（提取数字前释放整数: {:.2} MB）", mb(false), mb(true));
+    }
+    
+    if total_memory_mb > 100.0 {
+        println!("⚠️  警告: 内存使用可能较高，考虑减少线程数或位数");
+    }
+}
+
+// 统计每个数字 0-9 出现的次数
+pub fn digit_statistics(iter: impl Iterator<Item = u8>) -> [u64; 10] {
+    let mut counts = [0u64; 10];
+    for digit in iter {
+        counts[digit as usize] += 1;
+    }
+    counts
+}
+
+// 完全随机的十进制数字每位携带 log2(10) 位信息，按每位一字节存储时的压缩比下限
+pub const RANDOM_DIGITS_COMPRESSION_RATIO: f64 = 0.41524101186092029;
+
+// 将每位数字存为一个字节 (0-9) 后用 gzip 最高压缩级别压缩，返回压缩后与压缩前的大小之比
+// 数字越接近随机，比值越接近 RANDOM_DIGITS_COMPRESSION_RATIO；没有数字时返回 0
+pub fn compression_ratio(iter: impl Iterator<Item = u8>) -> f64 {
+    let original: Vec<u8> = iter.collect();
+    if original.is_empty() {
+        return 0.0;
+    }
+    
+    // 写入内存缓冲不会失败
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&original).expect("写入内存缓冲失败");
+    let compressed = encoder.finish().expect("写入内存缓冲失败");
+    
+    compressed.len() as f64 / original.len() as f64
+}
+
+// 显示数字分布及卡方统计量（均匀分布假设，自由度 9）
+pub fn print_digit_statistics(counts: &[u64; 10]) {
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        println!("没有可统计的数字");
+        return;
+    }
+    
+    let expected = total as f64 / 10.0;
+    let mut chi_square = 0.0;
+    
+    println!("数字  出现次数  占比");
+    for (digit, &count) in counts.iter().enumerate() {
+        let diff = count as f64 - expected;
+        chi_square += diff * diff / expected;
+        println!("{:>4}  {:>8}  {:>6.3}%", digit, count, count as f64 / total as f64 * 100.0);
+    }
+    
+    println!("总计: {} 位", total);
+    println!("卡方统计量: {:.4} (自由度 9, 5% 临界值 16.919)", chi_square);
+    
+    if chi_square < 16.919 {
+        println!("✓ 分布与均匀分布无显著差异");
+    } else {
+        println!("✗ 分布与均匀分布存在显著差异");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chudnovsky::{binary_split, chudnovsky_terms};
+    use crate::output::fraction_digits;
+    use crate::{compute_pi, Algorithm};
+    
+    // 10 万位时 Chudnovsky 单线程也超出上限，改用单线程内存更低的迭代算法
+    #[test]
+    fn tiny_budget_forces_low_memory_algorithm() {
+        let digits = 100_000;
+        let budget = Algorithm::all()
+            .iter()
+            .map(|&a| estimate_memory_bytes(a, digits, 1))
+            .min()
+            .unwrap();
+        assert!(estimate_memory_bytes(Algorithm::Chudnovsky, digits, 1) > budget);
+        
+        let (algorithm, threads) = fit_to_memory(Algorithm::Chudnovsky, digits, 8, budget).unwrap();
+        assert_ne!(algorithm, Algorithm::Chudnovsky);
+        assert_eq!(threads, 1);
+        assert!(estimate_memory_bytes(algorithm, digits, threads) <= budget);
+        
+        // 上限充足时保留请求的算法和线程数
+        let fitted = fit_to_memory(Algorithm::Chudnovsky, digits, 8, u64::MAX).unwrap();
+        assert_eq!(fitted, (Algorithm::Chudnovsky, 8));
+        assert!(matches!(
+            fit_to_memory(Algorithm::Chudnovsky, digits, 8, budget - 1),
+            Err(ComputeError::MemoryBudgetExceeded { .. })
+        ));
+    }
+    
+    // 10 万位时估算的 P、Q、T 位数与实际二进分割的结果相差不到 5%，
+    // 提取数字前释放它们的流式路径峰值低于一次性路径
+    #[test]
+    fn streaming_lowers_binary_split_peak_memory() {
+        let digits = 100_000;
+        let terms = chudnovsky_terms(digits).unwrap();
+        let triple = binary_split(0, terms as u64, false);
+        let actual = [&triple.p, &triple.q, &triple.t].iter().map(|n| n.significant_bits() as f64).sum::<f64>();
+        let estimated = split_triple_bits(terms as f64);
+        assert!((estimated / actual - 1.0).abs() < 0.05, "估算 {} 位，实际 {} 位", estimated, actual);
+        
+        let all_at_once = estimate_binary_split_peak_bytes(digits, false);
+        let streaming = estimate_binary_split_peak_bytes(digits, true);
+        assert!(streaming < all_at_once, "{} >= {}", streaming, all_at_once);
+        assert!(streaming as f64 > actual / 8.0);
+    }
+    
+    // 一万亿位对任何算法都远超默认的耗时和内存上限
+    #[test]
+    fn trillion_digits_are_rejected() {
+        for &algorithm in Algorithm::all() {
+            let result = check_feasibility(
+                algorithm,
+                1_000_000_000_000,
+                8,
+                DEFAULT_MAX_RUNTIME,
+                DEFAULT_MAX_MEMORY_BYTES,
+            );
+            assert!(matches!(result, Err(ComputeError::Infeasible { .. })), "{}", algorithm.name());
+        }
+    }
+    
+    // 一万位对每种算法都在上限以内；Chudnovsky 的项数少，预计耗时低于 BBP
+    #[test]
+    fn ten_thousand_digits_are_feasible() {
+        for &algorithm in Algorithm::all() {
+            check_feasibility(algorithm, 10_000, 1, DEFAULT_MAX_RUNTIME, DEFAULT_MAX_MEMORY_BYTES)
+                .unwrap();
+        }
+        let (bbp, _) = estimate_runtime_and_memory(Algorithm::Bbp, 1_000_000, 1);
+        let (chudnovsky, _) = estimate_runtime_and_memory(Algorithm::Chudnovsky, 1_000_000, 1);
+        assert!(chudnovsky < bbp);
+    }
+    
+    #[test]
+    fn first_ten_thousand_digits_are_evenly_spread() {
+        let pi = compute_pi(Algorithm::Chudnovsky, 10_000, 2).unwrap().value;
+        let counts = digit_statistics(fraction_digits(&pi, 10_000));
+        
+        assert_eq!(counts.iter().sum::<u64>(), 10_000);
+        assert!(counts.iter().all(|&count| (900..=1100).contains(&count)), "{:?}", counts);
+        assert_eq!(counts, [968, 1026, 1021, 974, 1012, 1046, 1021, 970, 948, 1014]);
+    }
+    
+    // 10000 位 π 不低于随机数字的理论下限 log2(10)/8 ≈ 0.415，也离它不远：
+    // gzip 的 Huffman 编码每位要用 3 到 4 位，短重复串的匹配还要额外开销，实际约为 0.5；
+    // 而同样长度的重复数字几乎可以完全压缩
+    #[test]
+    fn compression_ratio_near_random_digits() {
+        let pi = compute_pi(Algorithm::Chudnovsky, 10_000, 2).unwrap().value;
+        let ratio = compression_ratio(fraction_digits(&pi, 10_000));
+        assert!(
+            (RANDOM_DIGITS_COMPRESSION_RATIO..RANDOM_DIGITS_COMPRESSION_RATIO + 0.2).contains(&ratio),
+            "压缩比 {}",
+            ratio
+        );
+        
+        assert!(compression_ratio(std::iter::repeat(7).take(10_000)) < 0.05);
+        assert_eq!(compression_ratio(std::iter::empty()), 0.0);
+    }
+}
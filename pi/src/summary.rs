@@ -0,0 +1,166 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::chudnovsky::compute_pi_binary_split_cancellable;
+use crate::output::{
+    pi_string, write_pi_csv_positions, write_pi_for_validation, write_pi_raw,
+    write_pi_to_file_chunked, OutputMode, WriteOptions,
+};
+use crate::reference::BUNDLED_DIGITS;
+use crate::verify::{ensure_sane, verify_pi_accuracy};
+use crate::{compute_pi_with, Algorithm, ComputeError, ComputeResult, Config};
+
+// 一次完整运行（计算、验证、写入）的汇总，便于程序化处理
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    // 输出的位数
+    pub digits: usize,
+    pub algorithm: Algorithm,
+    pub threads: usize,
+    // 计算使用的二进制精度
+    pub precision: u32,
+    pub elapsed: Duration,
+    pub digits_per_sec: f64,
+    // 与打包的参考数据一致的前导位数（最多 BUNDLED_DIGITS 位）
+    pub verified_digits: usize,
+    pub output_path: String,
+    // 输出文件大小，无法读取时为 0
+    pub file_bytes: u64,
+}
+
+// 按配置计算、验证并写入文件，返回汇总
+pub fn run(config: &Config) -> Result<RunSummary, ComputeError> {
+    let result = compute_pi_with(
+        config.algorithm,
+        config.compute_digits(),
+        config.num_threads,
+        &config.options,
+    )?;
+    finish_run(config, result)
+}
+
+// 同 run，但总是使用二进分割，超大位数时内存峰值更低
+// 组装出 π 后 P、Q、T 即被释放，之后按块提取小数位并写入，任何时候都不持有完整的数字串
+pub fn run_streaming(config: &Config) -> Result<RunSummary, ComputeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let result = compute_pi_binary_split_cancellable(
+        config.compute_digits(),
+        config.num_threads,
+        &stop,
+        &config.options,
+    )?;
+    finish_run(config, result)
+}
+
+// 验证并写入计算结果，返回汇总
+// 参考数据只有 BUNDLED_DIGITS 位，验证只需构造这么长的前缀，写入时按块提取小数位
+fn finish_run(config: &Config, result: ComputeResult) -> Result<RunSummary, ComputeError> {
+    let digits = config.display_digits();
+    let checked = digits.min(BUNDLED_DIGITS);
+    let (_, verified_digits) = verify_pi_accuracy(&pi_string(&result.value, checked), checked);
+    
+    ensure_sane(&result.value)?;
+    let path = &config.output_file;
+    let options = WriteOptions::default();
+    match config.output_mode {
+        OutputMode::Standard => write_pi_to_file_chunked(&result.value, digits, path, None, &options)?,
+        OutputMode::Raw => write_pi_raw(&result.value, digits, path, &options)?,
+        OutputMode::Validation => write_pi_for_validation(&result.value, digits, path, &options)?,
+        OutputMode::CsvPositions => write_pi_csv_positions(&result.value, digits, path, &options)?,
+    }
+    let file_bytes = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+    
+    Ok(RunSummary {
+        digits,
+        algorithm: result.algorithm,
+        threads: config.num_threads,
+        precision: result.precision,
+        elapsed: result.elapsed,
+        digits_per_sec: digits_per_sec(digits, result.elapsed),
+        verified_digits,
+        output_path: path.clone(),
+        file_bytes,
+    })
+}
+
+// 每秒计算的位数；耗时为零时（如 BBP 直接返回缓存结果）记为 0，而不是无穷大
+pub fn digits_per_sec(digits: usize, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    digits as f64 / elapsed.as_secs_f64()
+}
+
+// 以表格形式显示多次运行的汇总
+pub fn print_summary_table(summaries: &[RunSummary]) {
+    println!(
+        "\n{:>10}  {:<10}  {:>6}  {:>10}  {:>14}  {:>6}  {:>12}  文件",
+        "位数", "算法", "线程", "耗时(秒)", "速度(位/秒)", "验证", "大小(KB)"
+    );
+    println!("{}", "-".repeat(90));
+    for summary in summaries {
+        println!(
+            "{:>10}  {:<10}  {:>6}  {:>10.3}  {:>14.2}  {:>6}  {:>12.2}  {}",
+            summary.digits,
+            summary.algorithm.name(),
+            summary.threads,
+            summary.elapsed.as_secs_f64(),
+            summary.digits_per_sec,
+            summary.verified_digits,
+            summary.file_bytes as f64 / 1024.0,
+            summary.output_path
+        );
+    }
+}
+
+// 显示运行汇总
+pub fn print_summary(summary: &RunSummary) {
+    println!("\n运行汇总:");
+    println!("{}", "-".repeat(52));
+    println!("位数: {}", summary.digits);
+    println!("算法: {}，线程数: {}", summary.algorithm, summary.threads);
+    println!("精度: {} 位二进制", summary.precision);
+    println!("计算时间: {:.2} 秒", summary.elapsed.as_secs_f64());
+    println!("平均速度: {:.2} 位/秒", summary.digits_per_sec);
+    println!("已验证: 前 {} 位", summary.verified_digits);
+    println!("文件名: {}", summary.output_path);
+    println!("文件大小: {:.2} KB", summary.file_bytes as f64 / 1024.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chudnovsky::CHUDNOVSKY_GUARD_BITS;
+    use crate::checked_precision;
+    
+    // 500 位运行后汇总中的各项与配置和写出的文件一致
+    #[test]
+    fn summary_fields_after_500_digits() {
+        let path = std::env::temp_dir().join(format!("pi_summary_{}.txt", std::process::id()));
+        let mut config = Config::new(500);
+        config.algorithm = Algorithm::Chudnovsky;
+        config.num_threads = 2;
+        config.output_file = path.to_str().unwrap().to_string();
+        
+        let summary = run(&config).unwrap();
+        assert_eq!(summary.digits, 500);
+        assert_eq!(summary.algorithm, Algorithm::Chudnovsky);
+        assert_eq!(summary.threads, 2);
+        assert_eq!(summary.precision, checked_precision(500, CHUDNOVSKY_GUARD_BITS).unwrap());
+        assert!(summary.elapsed > Duration::ZERO);
+        assert!(summary.digits_per_sec.is_finite() && summary.digits_per_sec > 0.0);
+        assert_eq!(summary.verified_digits, 500);
+        assert_eq!(summary.output_path, config.output_file);
+        assert_eq!(summary.file_bytes, std::fs::metadata(&path).unwrap().len());
+        assert!(summary.file_bytes > 500);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 耗时为零时速度记为 0，汇总表中不会出现 inf
+    #[test]
+    fn zero_elapsed_gives_zero_speed() {
+        assert_eq!(digits_per_sec(1000, Duration::ZERO), 0.0);
+        assert_eq!(digits_per_sec(1000, Duration::from_secs(2)), 500.0);
+    }
+}
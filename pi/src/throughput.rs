@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+// BBP 公式每项贡献 4 位二进制，即 log10(16) 位十进制
+pub const BBP_DIGITS_PER_TERM: f64 = 1.2041199826559248;
+
+// 进度采样 (已用时间, 已完成项数)
+pub(crate) type ProgressSamples = Vec<(Duration, usize)>;
+
+// 进度采样的时间间隔
+pub(crate) const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+// 由进度采样计算吞吐量曲线
+// samples 为 (已用时间, 已完成项数)，按时间递增排列，第一个采样点作为起点
+// 返回之后每个采样点相对上一个采样点的瞬时速率（十进制位/秒）
+// 时间没有前进的采样点被跳过
+pub fn throughput_trace(samples: &[(Duration, usize)], digits_per_term: f64) -> Vec<(Duration, f64)> {
+    let Some((&first, rest)) = samples.split_first() else {
+        return Vec::new();
+    };
+    
+    let mut trace = Vec::with_capacity(rest.len());
+    let mut previous = first;
+    
+    for &(elapsed, done) in rest {
+        let dt = elapsed.saturating_sub(previous.0).as_secs_f64();
+        if dt <= 0.0 {
+            continue;
+        }
+        
+        let terms = done.saturating_sub(previous.1) as f64;
+        trace.push((elapsed, terms * digits_per_term / dt));
+        previous = (elapsed, done);
+    }
+    
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+    
+    // 每 200 毫秒采样一次，完成的项数依次为 100、80、50：速率按每项 10 位换算为 5000、4000、2500 位/秒；
+    // 时间没有前进的采样点被跳过，之后的速率相对最后一个有效采样点计算
+    #[test]
+    fn synthetic_schedule_gives_expected_rates() {
+        let samples = [(ms(0), 0), (ms(200), 100), (ms(400), 180), (ms(400), 190), (ms(600), 230)];
+        let trace = throughput_trace(&samples, 10.0);
+        
+        let expected = [(ms(200), 5000.0), (ms(400), 4000.0), (ms(600), 2500.0)];
+        assert_eq!(trace.len(), expected.len());
+        for ((elapsed, rate), (expected_elapsed, expected_rate)) in trace.iter().zip(expected) {
+            assert_eq!(*elapsed, expected_elapsed);
+            assert!((rate - expected_rate).abs() < 1e-9, "{:?}: {}", elapsed, rate);
+        }
+        
+        assert!(throughput_trace(&[], 10.0).is_empty());
+        assert!(throughput_trace(&[(ms(100), 5)], 10.0).is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use rug::{Float, Integer};
+
+use crate::bbp::{bbp_hex_digit, compute_pi_at_precision};
+use crate::index::build_index;
+use crate::output::{fraction_digits, is_ruler_line, pi_string};
+use crate::reference::{load_reference_chunk, pi_known_digits, BUNDLED_DIGITS};
+use crate::{ComputeError, ComputeOptions};
+
+// 验证 π 值的准确性，返回 (是否一致, 一致的前导小数位数)
+// digits 是小数位数，整数部分不计入；整数部分不是 3 时视为 0 位正确
+pub fn verify_pi_accuracy(pi_str: &str, digits: usize) -> (bool, usize) {
+    if pi_str.split('.').next() != Some("3") {
+        return (false, 0);
+    }
+    
+    // 只比较小数点之后的数字
+    let computed_digits: Vec<u8> = pi_str.bytes()
+        .skip_while(|&c| c != b'.')
+        .filter(u8::is_ascii_digit)
+        .collect();
+    
+    // 比较前 min(BUNDLED_DIGITS, digits) 位小数，参考数据取自随 crate 打包的数据
+    let compare_len = digits.min(BUNDLED_DIGITS).min(computed_digits.len());
+    let known_digits = load_reference_chunk(0, compare_len).unwrap_or_default();
+    let compare_len = compare_len.min(known_digits.len());
+    
+    let first_error = (0..compare_len).find(|&i| computed_digits[i] != known_digits[i]);
+    
+    let accurate = first_error.is_none();
+    (accurate, first_error.unwrap_or(compare_len))
+}
+
+// 写文件前快速检查的小数位数
+const SANITY_CHECK_DIGITS: usize = 5;
+
+// 写文件前的快速检查：结果应以 "3.14159" 开头
+// 不一致说明计算过程出错，不必再写出可能长达数 GB 的错误结果
+pub fn sanity_check(pi: &Float) -> bool {
+    let expected = &pi_known_digits()[..SANITY_CHECK_DIGITS + 2];
+    pi.is_finite() && pi_string(pi, SANITY_CHECK_DIGITS) == expected
+}
+
+// 同 sanity_check，不通过时返回 SanityCheckFailed
+pub(crate) fn ensure_sane(pi: &Float) -> Result<(), ComputeError> {
+    if sanity_check(pi) {
+        return Ok(());
+    }
+    
+    let found = if pi.is_finite() { pi_string(pi, SANITY_CHECK_DIGITS) } else { pi.to_string() };
+    log::error!("✗ 结果以 {} 开头，不是 π，放弃写入", found);
+    Err(ComputeError::SanityCheckFailed { found })
+}
+
+// 标准格式文件的头信息前缀
+pub(crate) const HEADER_PREFIX: &str = "π 的前 ";
+
+// 标准格式文件末尾统计信息中总位数一行的前缀，追加数字后以它为准
+pub(crate) const FOOTER_PREFIX: &str = "总位数: ";
+
+// 文件中数字的计数结果
+pub struct FileVerification {
+    // 头信息声明的位数，raw 格式文件没有头信息
+    pub claimed: Option<usize>,
+    // 正文中实际的数字个数
+    pub actual: usize,
+}
+
+impl FileVerification {
+    pub fn is_consistent(&self) -> bool {
+        self.claimed.is_none_or(|claimed| claimed == self.actual)
+    }
+}
+
+// 与已保存文件的比较结果
+pub struct FileComparison {
+    // 参与比较的小数位数（两者中较短的一方）
+    pub compared: usize,
+    // 第一个不同的小数位（从 1 开始）
+    pub first_mismatch: Option<usize>,
+}
+
+// 将 pi_str 的小数部分与本程序写出的文件（标准格式或 raw 格式）逐位比较
+// 文件的头信息、分组空格、换行和位置标记都会被忽略
+// path 为 None 时与随 crate 打包的前 BUNDLED_DIGITS 位参考数据比较
+pub fn verify_against_file(pi_str: &str, path: Option<&Path>) -> io::Result<FileComparison> {
+    let fraction = pi_str.split_once('.').map_or("", |(_, fraction)| fraction);
+    let (stored, source) = match path {
+        Some(path) => {
+            let index = build_index(path)?;
+            (index.read_window(0, index.len())?.into_bytes(), path.display().to_string())
+        }
+        None => {
+            let len = fraction.len().min(BUNDLED_DIGITS);
+            let chunk = load_reference_chunk(0, len).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "内置参考数据不可用")
+            })?;
+            (chunk, "内置参考数据".to_string())
+        }
+    };
+    
+    let compared = fraction.len().min(stored.len());
+    let first_mismatch = fraction.bytes()
+        .zip(stored.iter().copied())
+        .position(|(a, b)| a != b)
+        .map(|index| index + 1);
+    
+    match first_mismatch {
+        None => log::info!("✓ 与 {} 的前 {} 位小数完全一致", source, compared),
+        Some(position) => log::error!("✗ 与 {} 在小数点后第 {} 位开始不一致", source, position),
+    }
+    
+    Ok(FileComparison { compared, first_mismatch })
+}
+
+// 统计文件正文中的数字个数
+// 标准格式只统计两条分隔线之间正文中小数点之后的数字，并跳过 ":1000" 形式的位置标记
+pub fn count_digits_in_file(path: &Path) -> io::Result<usize> {
+    Ok(scan_file(path)?.actual)
+}
+
+// 检查文件正文的数字个数是否与头信息声明的位数一致
+pub fn verify_file(path: &Path) -> io::Result<FileVerification> {
+    let verification = scan_file(path)?;
+    
+    match verification.claimed {
+        Some(claimed) if claimed != verification.actual => {
+            log::error!("✗ 文件声明 {} 位，实际包含 {} 位，相差 {} 位",
+                    claimed, verification.actual, claimed.abs_diff(verification.actual));
+        }
+        Some(claimed) => log::info!("✓ 文件包含声明的全部 {} 位", claimed),
+        None => log::info!("文件没有头信息，共包含 {} 位", verification.actual),
+    }
+    
+    Ok(verification)
+}
+
+fn scan_file(path: &Path) -> io::Result<FileVerification> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut lines = reader.lines();
+    
+    let first_line = match lines.next() {
+        Some(line) => line?,
+        None => return Ok(FileVerification { claimed: None, actual: 0 }),
+    };
+    
+    // raw 格式：整个文件都是数字
+    let mut claimed = match first_line.strip_prefix(HEADER_PREFIX) {
+        Some(rest) => rest.split_whitespace().next().and_then(|n| n.parse::<usize>().ok()),
+        None => {
+            let mut actual = count_ascii_digits(&first_line);
+            for line in lines {
+                actual += count_ascii_digits(&line?);
+            }
+            return Ok(FileVerification { claimed: None, actual });
+        }
+    };
+    
+    // 跳过头信息直到第一条分隔线，之后统计到第二条分隔线为止
+    // 头信息声明的是小数位数，小数点之前的整数部分不计入
+    let mut in_body = false;
+    let mut seen_point = false;
+    let mut actual = 0;
+    for line in lines.by_ref() {
+        let line = line?;
+        if line.starts_with('=') {
+            if in_body {
+                break;
+            }
+            in_body = true;
+            continue;
+        }
+        // 列标尺在第一个小数位之前，不计入位数
+        if !in_body || (actual == 0 && is_ruler_line(line.as_bytes())) {
+            continue;
+        }
+        for token in line.split_whitespace().filter(|token| !token.starts_with(':')) {
+            if seen_point {
+                actual += count_ascii_digits(token);
+            } else if let Some((_, fraction)) = token.split_once('.') {
+                actual += count_ascii_digits(fraction);
+                seen_point = true;
+            }
+        }
+    }
+    
+    // 统计信息中的总位数在追加数字时会更新，头信息不会
+    for line in lines {
+        if let Some(total) = line?.trim_end().strip_prefix(FOOTER_PREFIX) {
+            claimed = total.parse().ok().or(claimed);
+        }
+    }
+    
+    Ok(FileVerification { claimed, actual })
+}
+
+fn count_ascii_digits(s: &str) -> usize {
+    s.bytes().filter(|b| b.is_ascii_digit()).count()
+}
+
+// 复核时额外增加的二进制精度
+const DOUBLE_CHECK_EXTRA_BITS: u32 = 64;
+
+// 用比 pi 高 64 位的精度重新计算一次，比较前 digits 位小数
+// 全部一致返回 None，否则返回第一个不同的小数位（从 1 开始）
+pub fn double_check(pi: &Float, digits: usize, num_threads: usize) -> Result<Option<usize>, ComputeError> {
+    let precision = pi.prec() + DOUBLE_CHECK_EXTRA_BITS;
+    log::info!("以 {} 位二进制精度复核...", precision);
+    
+    let stop = Arc::new(AtomicBool::new(false));
+    let options = ComputeOptions::default();
+    let (reference, _) =
+        compute_pi_at_precision(precision, num_threads, &stop, Instant::now(), &options)?;
+    
+    let mismatch = fraction_digits(pi, digits)
+        .zip(fraction_digits(&reference, digits))
+        .position(|(a, b)| a != b)
+        .map(|index| index + 1);
+    
+    match mismatch {
+        None => log::info!("复核通过: 两种精度的前 {} 位小数完全一致", digits),
+        Some(position) => log::error!("复核失败: 小数点后第 {} 位开始不一致", position),
+    }
+    
+    Ok(mismatch)
+}
+
+// 两个浮点数逐位比较的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatDiff {
+    pub equal: bool,
+    // 第一个不同的有效位，从最高有效位 1 开始计；符号或量级不同时为 0，相等时为 None
+    pub first_differing_bit: Option<u32>,
+    // 按两者中较高精度计的 ULP 距离，含非有限值时为 None
+    pub ulps: Option<Integer>,
+}
+
+// 逐位比较两个浮点数，用于排查不同线程数之间结果不一致的问题
+// 两者按较高精度和较大的指数对齐为整数尾数后比较
+pub fn compare_floats(a: &Float, b: &Float) -> FloatDiff {
+    // 无穷大和 NaN 没有尾数，只判断是否相同
+    if !a.is_finite() || !b.is_finite() {
+        let equal = (a.is_nan() && b.is_nan()) || (a.is_infinite() && a == b);
+        return FloatDiff { equal, first_differing_bit: (!equal).then_some(0), ulps: None };
+    }
+    
+    // 零没有指数，按另一个数的指数对齐；两者都是零时相等
+    let exp = match (a.get_exp(), b.get_exp()) {
+        (Some(ea), Some(eb)) => ea.max(eb),
+        (Some(e), None) | (None, Some(e)) => e,
+        (None, None) => {
+            return FloatDiff { equal: true, first_differing_bit: None, ulps: Some(Integer::new()) };
+        }
+    };
+    let precision = a.prec().max(b.prec());
+    
+    // 缩放到最高有效位恰好是第 precision 位的整数，缩放是精确的
+    let shift = precision as i32 - exp;
+    let to_mantissa = |x: &Float| {
+        let scaled = Float::with_val(x.prec(), x << shift);
+        scaled.to_integer().unwrap_or_default()
+    };
+    let mantissa_a = to_mantissa(a);
+    let mantissa_b = to_mantissa(b);
+    
+    let ulps = Integer::from(&mantissa_a - &mantissa_b).abs();
+    if ulps == 0 {
+        return FloatDiff { equal: true, first_differing_bit: None, ulps: Some(ulps) };
+    }
+    
+    let first_differing_bit = if mantissa_a.cmp0() != mantissa_b.cmp0() || a.get_exp() != b.get_exp() {
+        0
+    } else {
+        let abs_a = Integer::from(mantissa_a.abs_ref());
+        let abs_b = Integer::from(mantissa_b.abs_ref());
+        let xor = Integer::from(&abs_a ^ &abs_b);
+        precision + 1 - xor.significant_bits()
+    };
+    
+    FloatDiff { equal: false, first_differing_bit: Some(first_differing_bit), ulps: Some(ulps) }
+}
+
+// 每位十六进制数字约对应的十进制位数: log10(16)
+const DECIMAL_DIGITS_PER_HEX: f64 = 1.2041199826559248;
+
+// 换算十六进制数字时额外读取的十进制位数，吸收末位截断的误差
+const HEX_CHECK_MARGIN_DIGITS: usize = 20;
+
+// 用 BBP 数字提取抽查文件的结果
+pub struct ValidationReport {
+    // 抽查的十六进制位置（小数点后从 0 开始）
+    pub positions: Vec<usize>,
+    // 与 BBP 结果不一致的位置
+    pub mismatches: Vec<usize>,
+}
+
+impl ValidationReport {
+    pub fn passed(&self) -> bool {
+        !self.positions.is_empty() && self.mismatches.is_empty()
+    }
+}
+
+// 不重新计算十进制结果，而是在文件覆盖的范围内均匀抽取 sample_count 个十六进制位置，
+// 由文件中的十进制数字换算出该位置的十六进制数字，再与 bbp_hex_digit 独立计算的结果比较
+pub fn validate_file_via_bbp(path: &Path, sample_count: usize) -> io::Result<ValidationReport> {
+    let index = build_index(path)?;
+    let available = index.len().saturating_sub(HEX_CHECK_MARGIN_DIGITS);
+    let max_hex = (available as f64 / DECIMAL_DIGITS_PER_HEX) as usize;
+    
+    if sample_count == 0 || max_hex < 2 {
+        log::warn!("文件 {} 位数不足，无法抽查", path.display());
+        return Ok(ValidationReport { positions: Vec::new(), mismatches: Vec::new() });
+    }
+    
+    // 在 [0, max_hex - 1) 内均匀取样
+    let positions: Vec<usize> = (0..sample_count)
+        .map(|i| i * (max_hex - 1) / sample_count)
+        .collect();
+    let last = *positions.last().unwrap_or(&0);
+    
+    // 只读取最远位置需要的十进制前缀
+    let needed = (((last + 1) as f64) * DECIMAL_DIGITS_PER_HEX).ceil() as usize + HEX_CHECK_MARGIN_DIGITS;
+    let fraction = index.read_window(0, needed)?;
+    let precision = (fraction.len() as f64 * 3.32193).ceil() as u32 + 64;
+    let parsed = Float::parse(format!("0.{}", fraction))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    let value = Float::with_val(precision, parsed);
+    
+    let mut mismatches = Vec::new();
+    for &position in &positions {
+        // 第 position 位十六进制数字 = floor(frac(x * 16^position) * 16)
+        let mut shifted = Float::with_val(precision, &value << (4 * position as u32));
+        shifted.fract_mut();
+        shifted <<= 4u32;
+        let from_file = shifted.to_f64().floor() as u8;
+        
+        if from_file != bbp_hex_digit(position) {
+            mismatches.push(position);
+        }
+    }
+    
+    if mismatches.is_empty() {
+        log::info!("✓ 抽查 {} 个十六进制位置全部一致", positions.len());
+    } else {
+        log::error!("✗ 抽查 {} 个位置，{} 个不一致，首个位于十六进制第 {} 位",
+                positions.len(), mismatches.len(), mismatches[0]);
+    }
+    
+    Ok(ValidationReport { positions, mismatches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chudnovsky::CHUDNOVSKY_GUARD_BITS;
+    use crate::output::{stream_pi, FormatOptions};
+    use crate::{
+        compute_pi_chudnovsky, compute_pi_with, required_precision, Algorithm, ComputeOptions,
+    };
+    
+    // 线程数只改变累加顺序，两次结果的差别只来自舍入，落在保护位以内，不影响输出的数字
+    #[test]
+    fn thread_count_changes_only_the_guard_bits() {
+        let options = ComputeOptions::default();
+        let one = compute_pi_chudnovsky(1000, 1, &options).unwrap().value;
+        let eight = compute_pi_chudnovsky(1000, 8, &options).unwrap().value;
+        
+        let diff = compare_floats(&one, &eight);
+        let ulps = diff.ulps.clone().expect("结果应为有限值");
+        assert!(
+            ulps < Integer::from(1) << CHUDNOVSKY_GUARD_BITS,
+            "1 线程与 8 线程相差 {} ULP，第一个不同的位: {:?}",
+            ulps,
+            diff.first_differing_bit
+        );
+        assert_eq!(pi_string(&one, 1000), pi_string(&eight, 1000));
+    }
+    
+    // 写入中断只留下前 10 行正文（500 位）时，头信息声明的 1000 位与实际位数不一致
+    #[test]
+    fn truncated_file_is_detected() {
+        let pi = compute_pi_chudnovsky(1000, 2, &ComputeOptions::default()).unwrap().value;
+        let mut out = Vec::new();
+        stream_pi(&pi, 1000, &mut out, &FormatOptions::default()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        
+        let path = std::env::temp_dir().join(format!("pi_truncated_{}.txt", std::process::id()));
+        std::fs::write(&path, &text).unwrap();
+        assert!(verify_file(&path).unwrap().is_consistent());
+        
+        let truncated: String = text.split_inclusive('\n').take(13).collect();
+        std::fs::write(&path, truncated).unwrap();
+        assert_eq!(count_digits_in_file(&path).unwrap(), 500);
+        let verification = verify_file(&path).unwrap();
+        assert_eq!((verification.claimed, verification.actual), (Some(1000), 500));
+        assert!(!verification.is_consistent());
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 精度比所需少 60 位（约 18 位十进制）时最后十几位不可靠，复核在末尾附近发现不一致；精度足够时复核通过
+    #[test]
+    fn double_check_catches_missing_guard_bits() {
+        let options = ComputeOptions::default();
+        let pi = compute_pi_chudnovsky(1000, 2, &options).unwrap().value;
+        assert_eq!(double_check(&pi, 1000, 2).unwrap(), None);
+        
+        let low = ComputeOptions { precision: Some(required_precision(1000) - 60), ..options };
+        let pi = compute_pi_with(Algorithm::Chudnovsky, 1000, 2, &low).unwrap().value;
+        let mismatch = double_check(&pi, 1000, 2).unwrap().expect("精度不足应被复核发现");
+        assert!((950..=1000).contains(&mismatch), "第 {} 位", mismatch);
+    }
+    
+    // 新计算的 1000 位与之前保存的标准格式文件和内置参考数据都一致；改动一位后报告该位置
+    #[test]
+    fn compare_with_stored_file() {
+        let pi = compute_pi_chudnovsky(1000, 2, &ComputeOptions::default()).unwrap().value;
+        let path = std::env::temp_dir().join(format!("pi_stored_{}.txt", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        stream_pi(&pi, 1000, &mut file, &FormatOptions::default()).unwrap();
+        drop(file);
+        
+        let pi_str = pi_string(&pi, 1000);
+        let comparison = verify_against_file(&pi_str, Some(&path)).unwrap();
+        assert_eq!((comparison.compared, comparison.first_mismatch), (1000, None));
+        let comparison = verify_against_file(&pi_str, None).unwrap();
+        assert_eq!((comparison.compared, comparison.first_mismatch), (1000, None));
+        
+        // 小数点后第 500 位是 2，改为 3
+        let mut tampered = pi_str.into_bytes();
+        assert_eq!(tampered[501], b'2');
+        tampered[501] = b'3';
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert_eq!(verify_against_file(&tampered, Some(&path)).unwrap().first_mismatch, Some(500));
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 10000 位参考数据写成的 raw 文件抽查 50 个位置全部通过；
+    // 改动小数点后第 5001 位后抽查失败，且只有该位之后的十六进制位置受影响
+    #[test]
+    fn bbp_validation_detects_corruption() {
+        let mut digits = load_reference_chunk(0, BUNDLED_DIGITS).unwrap();
+        let path = std::env::temp_dir().join(format!("pi_bbp_check_{}.txt", std::process::id()));
+        std::fs::write(&path, &digits).unwrap();
+        
+        let report = validate_file_via_bbp(&path, 50).unwrap();
+        assert_eq!(report.positions.len(), 50);
+        assert!(report.passed(), "不一致: {:?}", report.mismatches);
+        
+        digits[5000] = if digits[5000] == b'0' { b'1' } else { b'0' };
+        std::fs::write(&path, &digits).unwrap();
+        let report = validate_file_via_bbp(&path, 50).unwrap();
+        assert!(!report.passed());
+        assert!(report.mismatches.iter().all(|&position| position >= 4000), "{:?}", report.mismatches);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    // 不以 3.14159 开头的结果（例如算错成 3.15）不能通过写入前的检查
+    #[test]
+    fn wrong_float_fails_the_sanity_check() {
+        let wrong = Float::with_val(64, 3.15);
+        assert!(!sanity_check(&wrong));
+        match ensure_sane(&wrong) {
+            Err(ComputeError::SanityCheckFailed { found }) => assert!(found.starts_with("3.15"), "{}", found),
+            other => panic!("应当返回 SanityCheckFailed，实际为 {:?}", other),
+        }
+        
+        let nan = Float::with_val(64, rug::float::Special::Nan);
+        assert!(!sanity_check(&nan));
+        assert!(ensure_sane(&nan).is_err());
+        
+        let pi = compute_pi_chudnovsky(100, 1, &ComputeOptions::default()).unwrap().value;
+        assert!(sanity_check(&pi));
+        assert!(ensure_sane(&pi).is_ok());
+    }
+    
+    // 比较范围扩展到打包的 10000 位：第 5000 位出错也能发现，超过 10000 位的部分不计入
+    #[test]
+    fn accuracy_check_uses_bundled_digits() {
+        let pi = compute_pi_chudnovsky(10_050, 2, &ComputeOptions::default()).unwrap().value;
+        let pi_str = pi_string(&pi, 10_050);
+        assert_eq!(verify_pi_accuracy(&pi_str, 500), (true, 500));
+        assert_eq!(verify_pi_accuracy(&pi_str, 10_050), (true, BUNDLED_DIGITS));
+        
+        let mut wrong = pi_str[..5002].to_string().into_bytes();
+        wrong[2 + 4999] = if wrong[2 + 4999] == b'9' { b'0' } else { b'9' };
+        let wrong = String::from_utf8(wrong).unwrap();
+        assert_eq!(verify_pi_accuracy(&wrong, 5000), (false, 4999));
+    }
+}
@@ -0,0 +1,77 @@
+use std::process::{Command, Stdio};
+
+// --digits 100,1000,10000 依次写出三个文件，汇总表中每个位数各一行
+#[test]
+fn batch_writes_one_file_per_digit_count() {
+    let dir = std::env::temp_dir().join(format!("pi_batch_cli_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    
+    let output = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+        .args(["--digits", " 100, 1000,10000 "])
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("请输入"), "{}", stdout);
+    for digits in [100, 1000, 10000] {
+        let filename = format!("pi_{}.txt", digits);
+        assert!(dir.join(&filename).exists(), "{}", filename);
+        let rows: Vec<&str> = stdout.lines().filter(|line| line.ends_with(&filename)).collect();
+        assert_eq!(rows.len(), 1, "{}", stdout);
+        assert_eq!(rows[0].split_whitespace().next(), Some(digits.to_string().as_str()));
+    }
+    
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// 列表中有无法解析的项或 0 时报错退出，不计算任何位数
+#[test]
+fn invalid_digit_list_is_rejected() {
+    for list in ["100,abc", "100,0", "100,,1000"] {
+        let status = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+            .args(["--digits", list])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!status.success(), "{}", list);
+    }
+}
+
+// 预计耗时超出上限时与交互模式一样拒绝计算，不写出任何文件
+#[test]
+fn batch_checks_limits() {
+    let dir = std::env::temp_dir().join(format!("pi_batch_limits_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    
+    let output = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+        .args(["--digits", "100,1000000", "--max-runtime", "0.001"])
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--force"));
+    assert!(!dir.join("pi_1000000.txt").exists());
+    
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// 批量模式不支持的选项报错退出，而不是被静默忽略
+#[test]
+fn unsupported_flags_are_rejected_in_batch_mode() {
+    for flag in [&["--sha256"][..], &["--double-check"], &["--stream"], &["--split-size", "10"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+            .args(["--digits", "100"])
+            .args(flag)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "{:?}", flag);
+        assert!(String::from_utf8(output.stderr).unwrap().contains(flag[0]), "{:?}", flag);
+    }
+}
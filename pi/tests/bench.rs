@@ -0,0 +1,26 @@
+use std::process::{Command, Stdio};
+
+// --bench 不读取标准输入、不写任何文件，只输出每个位数的耗时和速度
+#[test]
+fn bench_skips_prompts_and_output() {
+    let dir = std::env::temp_dir().join(format!("pi_bench_cli_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    
+    let output = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+        .args(["--bench", "--digits", "200,500", "--algorithm", "bellard"])
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("请输入"), "{}", stdout);
+    assert!(stdout.contains("算法: bellard"));
+    assert!(stdout.contains("位数: 200"));
+    assert!(stdout.contains("位数: 500"));
+    assert_eq!(stdout.matches("速度: ").count(), 2);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    
+    std::fs::remove_dir(&dir).unwrap();
+}
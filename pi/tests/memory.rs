@@ -0,0 +1,112 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ffi::c_void;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use gmp_mpfr_sys::gmp;
+use pi_calculator::{
+    compute_pi_binary_split, pi_string, write_pi_to_file_chunked, WriteOptions,
+};
+
+// 当前已分配和分配过的最大字节数，Rust 堆与 GMP/MPFR 的分配都计入
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+fn record(delta: isize) {
+    let now = CURRENT.fetch_add(delta, Ordering::SeqCst) + delta;
+    PEAK.fetch_max(now, Ordering::SeqCst);
+}
+
+// 统计 Rust 堆分配的全局分配器
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as isize));
+    }
+    
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+    fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+}
+
+// GMP 和 MPFR 的整数、浮点数不经过 Rust 的全局分配器，通过 GMP 的分配钩子计入同一计数
+// 钩子仍使用 GMP 默认的 malloc/realloc/free，只额外记录 GMP 传入的大小
+unsafe extern "C" fn gmp_alloc(size: usize) -> *mut c_void {
+    record(size as isize);
+    malloc(size)
+}
+
+unsafe extern "C" fn gmp_realloc(ptr: *mut c_void, old_size: usize, new_size: usize) -> *mut c_void {
+    record(new_size as isize - old_size as isize);
+    realloc(ptr, new_size)
+}
+
+unsafe extern "C" fn gmp_free(ptr: *mut c_void, size: usize) {
+    record(-(size as isize));
+    free(ptr);
+}
+
+// 运行 f 期间比开始时多占用的最大字节数
+fn peak_during(f: impl FnOnce()) -> isize {
+    let base = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(base, Ordering::SeqCst);
+    f();
+    PEAK.load(Ordering::SeqCst) - base
+}
+
+const DIGITS: usize = 100_000;
+
+// 写入缓冲区两种路径相同，不使用默认的 1 MB，避免掩盖两者的差别
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// 10 万位：二进分割返回时 P、Q、T 已释放，之后按块提取小数位写入文件，
+// 写入阶段的内存峰值低于先构造完整的数字串再写入
+#[test]
+fn streaming_write_lowers_peak_memory() {
+    unsafe {
+        gmp::set_memory_functions(Some(gmp_alloc), Some(gmp_realloc), Some(gmp_free));
+    }
+    let dir = std::env::temp_dir();
+    let streamed_path = dir.join(format!("pi_memory_streamed_{}.txt", std::process::id()));
+    let whole_path = dir.join(format!("pi_memory_whole_{}.txt", std::process::id()));
+    let options = WriteOptions { buffer_size: BUFFER_SIZE, ..WriteOptions::default() };
+    let pi = compute_pi_binary_split(DIGITS, 1).unwrap().value;
+    
+    let streaming = peak_during(|| {
+        write_pi_to_file_chunked(&pi, DIGITS, streamed_path.to_str().unwrap(), None, &options).unwrap();
+    });
+    
+    let all_at_once = peak_during(|| {
+        let pi_str = pi_string(&pi, DIGITS);
+        let mut writer = BufWriter::with_capacity(BUFFER_SIZE, File::create(&whole_path).unwrap());
+        writer.write_all(pi_str.as_bytes()).unwrap();
+        writer.flush().unwrap();
+    });
+    
+    std::fs::remove_file(&streamed_path).unwrap();
+    std::fs::remove_file(&whole_path).unwrap();
+    assert!(streaming < all_at_once, "{} >= {}", streaming, all_at_once);
+}
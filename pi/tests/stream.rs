@@ -0,0 +1,15 @@
+use std::process::{Command, Stdio};
+
+// --stream 只能使用二进分割，与其他 --algorithm 同时指定时报错退出，而不是静默替换
+#[test]
+fn stream_rejects_other_algorithms() {
+    for algorithm in ["bbp", "gauss-legendre", "chudnovsky"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_pi_calculator"))
+            .args(["--stream", "--algorithm", algorithm])
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "{}", algorithm);
+        assert!(String::from_utf8(output.stderr).unwrap().contains("--stream"), "{}", algorithm);
+    }
+}